@@ -0,0 +1,11 @@
+// Node.js 中 require npm 包示例,依赖会自动安装到本地 node_modules 缓存
+const _ = require('lodash');
+
+function handler(input) {
+    const data = input.data || [1, 2, 3, 4, 5, 6];
+    console.log("Chunking data:", data);
+    return {
+        chunks: _.chunk(data, input.chunkSize || 2),
+        sum: _.sum(data),
+    };
+}
@@ -0,0 +1,25 @@
+package main
+
+import (
+	"fmt"
+	"log"
+	"strings"
+)
+
+// Handler 是Go代码的入口函数,input 为通过 -p/--params 传递的参数
+func Handler(input map[string]any) (any, error) {
+	fmt.Println("Handler function called")
+	log.Printf("Received input: %v", input)
+
+	name, _ := input["name"].(string)
+	if name == "" {
+		name = "Guest"
+	}
+	a, _ := input["a"].(float64)
+	b, _ := input["b"].(float64)
+
+	return map[string]any{
+		"greeting": fmt.Sprintf("Hello, %s!", strings.ToUpper(name)),
+		"sum":      a + b,
+	}, nil
+}
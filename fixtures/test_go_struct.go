@@ -0,0 +1,28 @@
+package main
+
+import "fmt"
+
+// GreetInput 问候参数
+type GreetInput struct {
+	// 用户名
+	Name  string `json:"name"`
+	Times int    `json:"times,omitempty"` // 重复次数,默认为1
+}
+
+// GreetOutput 问候结果
+type GreetOutput struct {
+	Greeting string `json:"greeting"`
+}
+
+// Handler 根据用户名生成问候语
+func Handler(input GreetInput) (GreetOutput, error) {
+	times := input.Times
+	if times <= 0 {
+		times = 1
+	}
+	greeting := ""
+	for i := 0; i < times; i++ {
+		greeting += fmt.Sprintf("Hello, %s! ", input.Name)
+	}
+	return GreetOutput{Greeting: greeting}, nil
+}
@@ -0,0 +1,24 @@
+// 测试 console 各方法的日志捕获和级别
+export function handler(input) {
+    console.log("log", { a: 1 });
+    console.info("info");
+    console.debug("debug");
+    console.warn("warn");
+    console.error("error", new Map([["k", 1n]]));
+    console.assert(1 === 2, "math");
+    console.assert(true, "never logged");
+    console.count();
+    console.count();
+    console.group("group");
+    console.log("nested");
+    console.groupEnd();
+    console.table([{ id: 1 }]);
+
+    const cycle = { name: "cycle" };
+    cycle.self = cycle;
+    console.log(cycle);
+
+    // handler 返回后才触发的定时器不会执行
+    setTimeout(() => console.log("late"), 50);
+    return "done";
+}
@@ -0,0 +1,20 @@
+// 同一个文件中的多个入口函数,通过 entrypoint 选择要执行的函数;
+// 未指定时依次查找 handler、main
+
+function add(input) {
+    return input.a + input.b;
+}
+
+async function multiply(input) {
+    return input.a * input.b;
+}
+
+const describe = (input) => `a=${input.a}, b=${input.b}`;
+
+function main(input) {
+    return "main";
+}
+
+function handler(input) {
+    return "handler";
+}
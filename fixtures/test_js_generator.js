@@ -0,0 +1,11 @@
+// JavaScript异步生成器handler示例,每个yield的数据块都会实时返回给调用方
+
+async function* handler(input) {
+    const count = input.count || 3;
+    console.log(`开始生成 ${count} 个数据块`);
+    for (let index = 0; index < count; index++) {
+        await new Promise((resolve) => setTimeout(resolve, 10));
+        yield { index, text: `第${index + 1}段` };
+    }
+    return { total: count };
+}
@@ -0,0 +1,39 @@
+// handler 返回 JSON.stringify 不支持的类型时,模板会转换为可以JSON序列化的值
+
+class Temperature {
+    constructor(celsius) {
+        this.celsius = celsius;
+    }
+
+    toJSON() {
+        return `${this.celsius}°C`;
+    }
+}
+
+class Point {
+    constructor(x, y) {
+        this.x = x;
+        this.y = y;
+    }
+}
+
+function handler(input) {
+    return {
+        big: 9007199254740993n,
+        when: new Date(Date.UTC(2024, 2, 1, 8, 30)),
+        invalidDate: new Date("not a date"),
+        counts: new Map([["a", 1], ["b", 2]]),
+        byObject: new Map([[{ id: 1 }, "first"]]),
+        tags: new Set(["x", "y"]),
+        floats: new Float64Array([1.5, NaN]),
+        bigs: new BigUint64Array([1n, 2n]),
+        bytes: new Uint8Array([1, 2, 3]),
+        infinite: Infinity,
+        missing: undefined,
+        sparse: [1, undefined, () => 0],
+        error: new RangeError("out of range"),
+        pattern: /run-code/i,
+        temperature: new Temperature(21),
+        point: new Point(1, 2),
+    };
+}
@@ -0,0 +1,19 @@
+// Node.js CommonJS 示例,使用 require 和 module.exports
+const path = require('path');
+const { createHash } = require('crypto');
+
+console.log("CommonJS script loaded");
+
+function greet(name) {
+    return `Hello, ${name}!`;
+}
+
+module.exports.handler = async function (input) {
+    console.log("Handler called with:", input);
+    return {
+        greeting: greet(input.name || "Guest"),
+        ext: path.extname("report.csv"),
+        hash: createHash('sha256').update(String(input.name)).digest('hex').slice(0, 8),
+        nodeVersion: process.versions.node,
+    };
+};
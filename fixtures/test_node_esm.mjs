@@ -0,0 +1,13 @@
+// Node.js ES模块示例
+import { basename } from 'node:path';
+import os from 'os';
+
+console.log("ESM script loaded on", os.platform());
+
+export async function handler(input) {
+    console.log("Handler called with:", input);
+    return {
+        file: basename(input.path || "/tmp/example.txt"),
+        sum: (input.a || 0) + (input.b || 0),
+    };
+}
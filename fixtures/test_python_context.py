@@ -0,0 +1,16 @@
+# 测试调用上下文: 声明第二个参数时传入 context
+import time
+
+
+def handler(args, context):
+    context.logger.info(f"调用 {context.invocation_id}")
+    remaining = context.get_remaining_time_in_millis()
+    return {
+        "args": args,
+        "function_name": context.function_name,
+        "function_version": context.function_version,
+        "language": context.language,
+        "deadline_in_future": context.deadline > time.time(),
+        "remaining_positive": remaining > 0,
+        "api_url": context.env.get("API_URL"),
+    }
@@ -0,0 +1,22 @@
+# 同一个文件中的多个入口函数,通过 entrypoint 选择要执行的函数;
+# 未指定时依次查找 handler、main
+
+
+def add(args):
+    return args["a"] + args["b"]
+
+
+def multiply(args):
+    return args["a"] * args["b"]
+
+
+def ping():
+    return "pong"
+
+
+def main(args):
+    return "main"
+
+
+def handler(args):
+    return "handler"
@@ -0,0 +1,11 @@
+# handler 抛出异常时执行失败,错误信息包含异常类型、消息和用户代码的调用栈
+
+
+def average(values):
+    total = sum(values)
+    return total / len(values)
+
+
+def handler(args):
+    values = args.get("values", [])
+    return {"average": average(values)}
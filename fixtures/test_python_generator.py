@@ -0,0 +1,10 @@
+# Python生成器handler示例,每个yield的数据块都会实时返回给调用方
+import asyncio
+
+
+async def handler(input):
+    count = input.get("count", 3)
+    print(f"开始生成 {count} 个数据块")
+    for index in range(count):
+        await asyncio.sleep(0.01)
+        yield {"index": index, "text": f"第{index + 1}段"}
@@ -0,0 +1,21 @@
+# 测试标准错误、warnings、logging 级别和不完整的行的捕获
+import logging
+import sys
+import warnings
+
+
+def handler(args):
+    print("partial", end="")
+    print(" line")
+    sys.stdout.write("no newline at end")
+    print("to stderr", file=sys.stderr)
+    sys.stderr.write("progress 10%\rprogress 100%\n")
+    warnings.warn("old api")
+    logging.getLogger().setLevel(logging.DEBUG)
+    logging.debug("debug message")
+    logging.getLogger("app.db").warning("slow query")
+    try:
+        1 / 0
+    except ZeroDivisionError:
+        logging.getLogger("app").exception("failed")
+    return "ok"
@@ -0,0 +1,41 @@
+# handler 返回 json 模块不支持的类型时,模板会转换为可以JSON序列化的值
+import datetime
+import decimal
+import enum
+import uuid
+from dataclasses import dataclass
+
+
+class Status(enum.Enum):
+    ACTIVE = "active"
+
+
+@dataclass
+class Order:
+    id: int
+    created: datetime.date
+    total: decimal.Decimal
+
+
+class Money:
+    def __init__(self, cents):
+        self.cents = cents
+
+
+# 自定义类型的转换函数
+register_encoder(Money, lambda money: f"{money.cents / 100:.2f} CNY")
+
+
+def handler(args):
+    return {
+        "at": datetime.datetime(2024, 3, 1, 8, 30, tzinfo=datetime.timezone.utc),
+        "elapsed": datetime.timedelta(minutes=1, seconds=30),
+        "price": decimal.Decimal("19.99"),
+        "tags": {"b", "a"},
+        "status": Status.ACTIVE,
+        "id": uuid.UUID(int=1),
+        "order": Order(1, datetime.date(2024, 3, 1), decimal.Decimal("100")),
+        "missing": float("nan"),
+        "balance": Money(12345),
+        "by_date": {datetime.date(2024, 3, 1): 1},
+    }
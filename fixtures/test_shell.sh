@@ -0,0 +1,11 @@
+# Shell脚本示例,需要定义 handler 函数
+echo "Shell script loaded"
+
+handler() {
+    echo "Handler function called with: $1"
+    echo "Hello, ${PARAM_NAME:-Guest}"
+    echo "这是一条错误输出" >&2
+
+    local sum=$(( ${PARAM_A:-0} + ${PARAM_B:-0} ))
+    set_result "{\"sum\": $sum, \"greeting\": \"Hello, ${PARAM_NAME:-Guest}\", \"city\": \"${PARAM_USER_CITY:-}\"}"
+}
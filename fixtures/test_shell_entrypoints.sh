@@ -0,0 +1,14 @@
+# 同一个文件中的多个入口函数,通过 entrypoint 选择要执行的函数;
+# 未指定时依次查找 handler、main
+
+add() {
+    set_result "$((PARAM_A + PARAM_B))"
+}
+
+multiply() {
+    set_result "$((PARAM_A * PARAM_B))"
+}
+
+main() {
+    set_result '"main"'
+}
@@ -1,5 +1,9 @@
 use thiserror::Error;
 
+use crate::model::{
+    SchemaViolation, TypeDiagnostic, format_available, format_diagnostics, format_violations,
+};
+
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Failed to create temporary file: {0}")]
@@ -7,4 +11,22 @@ pub enum AppError {
 
     #[error("Failed to parse JSON: {0}")]
     JsonError(#[from] serde_json::Error),
+
+    #[error("Parameter validation failed: {}", format_violations(.0))]
+    ValidationError(Vec<SchemaViolation>),
+
+    #[error("Result violates output schema: {}", format_violations(.0))]
+    ContractViolation(Vec<SchemaViolation>),
+
+    #[error("Type check failed:\n{}", format_diagnostics(.0))]
+    TypeCheckError(Vec<TypeDiagnostic>),
+
+    #[error(
+        "HandlerNotFound: no function named {entrypoint}; available functions: {}",
+        format_available(.available)
+    )]
+    HandlerNotFound {
+        entrypoint: String,
+        available: Vec<String>,
+    },
 }
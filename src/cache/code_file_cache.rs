@@ -1,13 +1,49 @@
-use anyhow::{Context, Result};
-use log::info;
-use std::path::{Path, PathBuf};
+use anyhow::{Context, Result, bail};
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use std::path::{Component, Path, PathBuf};
 use tokio::fs::{self, File, create_dir_all};
 use tokio::io::AsyncWriteExt;
 
-use crate::model::LanguageScript;
+use crate::model::{LanguageScript, ProjectFiles};
+//多文件项目的缓存子目录
+pub const PROJECT_CACHE_DIR: &str = "projects";
+
+// 生成脚本使用的所有模板的指纹,计入脚本缓存的hash,升级后模板变化时不会继续使用旧的脚本缓存
+static TEMPLATE_FINGERPRINT: Lazy<String> = Lazy::new(|| {
+    let mut hasher = blake3::Hasher::new();
+    for template in [
+        include_str!("../templates/go_template.go"),
+        include_str!("../templates/js_harness.js"),
+        include_str!("../templates/js_serializer.js"),
+        include_str!("../templates/js_template_es.js"),
+        include_str!("../templates/js_template_normal.js"),
+        include_str!("../templates/node_template_cjs.js"),
+        include_str!("../templates/node_template_esm.mjs"),
+        include_str!("../templates/python_template.py"),
+        include_str!("../templates/shell_template.sh"),
+        include_str!("../templates/ts_template.ts"),
+    ] {
+        hasher.update(template.as_bytes());
+        hasher.update(b"\0");
+    }
+    hasher.finalize().to_hex().to_string()
+});
+
 ///针对用的代码，进行检测和缓存
 pub struct CodeFileCache;
 
+///多文件项目的缓存目录
+#[derive(Debug, Clone)]
+pub struct ProjectCache {
+    //项目目录
+    pub dir: PathBuf,
+    //入口文件路径(包装后的代码)
+    pub entry_path: PathBuf,
+    //是否本次新生成,新生成时需要安装依赖
+    pub created: bool,
+}
+
 impl CodeFileCache {
     /// 检查代码文件缓存
     pub fn obtain_code_hash(code: &str) -> String {
@@ -18,6 +54,11 @@ impl CodeFileCache {
         hash_str
     }
 
+    /// 生成脚本的缓存hash: 代码(及其他影响生成脚本的内容,如版本、入口函数)和模板指纹
+    pub fn obtain_script_hash(key: &str) -> String {
+        Self::obtain_code_hash(&format!("{key}\0template:{}", *TEMPLATE_FINGERPRINT))
+    }
+
     /// 根据代码的hash检查是否存在缓存
     pub async fn check_code_file_cache_exisht(hash: &str, language: &LanguageScript) -> bool {
         fs::try_exists(Self::get_cache_file_path(hash, language))
@@ -82,19 +123,29 @@ impl CodeFileCache {
             return Ok(());
         }
 
+        // Go 按代码hash缓存项目目录和编译后的二进制文件
+        if let LanguageScript::Go = language {
+            let go_dir = cache_dir.join("go");
+            if fs::try_exists(&go_dir).await.unwrap_or(false) {
+                fs::remove_dir_all(&go_dir)
+                    .await
+                    .with_context(|| format!("无法删除缓存目录: {}", go_dir.display()))?;
+            }
+        }
+
         let suffix = language.get_file_suffix();
         let mut entries = fs::read_dir(cache_dir).await?;
 
         while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
-            if let Some(file_name) = path.file_name() {
-                if let Some(name_str) = file_name.to_str() {
-                    if name_str.ends_with(suffix) {
-                        fs::remove_file(&path)
-                            .await
-                            .with_context(|| format!("无法删除缓存文件: {}", path.display()))?;
-                    }
-                }
+            let matched = path
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .is_some_and(|name_str| name_str.ends_with(suffix));
+            if matched {
+                fs::remove_file(&path)
+                    .await
+                    .with_context(|| format!("无法删除缓存文件: {}", path.display()))?;
             }
         }
 
@@ -112,6 +163,95 @@ impl CodeFileCache {
         Ok(())
     }
 
+    /// 获取缓存子目录路径(如 node 项目目录),不存在时创建
+    pub async fn ensure_cache_sub_dir(sub_dir: &str) -> Result<PathBuf> {
+        let dir = Self::get_cache_dir().join(sub_dir);
+        if !fs::try_exists(&dir).await.unwrap_or(false) {
+            create_dir_all(&dir)
+                .await
+                .with_context(|| format!("无法创建缓存目录: {}", dir.display()))?;
+        }
+        Ok(dir)
+    }
+
+    /// 在缓存子目录中保存文件,返回文件路径
+    pub async fn save_cache_sub_file(
+        sub_dir: &str,
+        file_name: &str,
+        content: &str,
+    ) -> Result<PathBuf> {
+        let file_path = Self::ensure_cache_sub_dir(sub_dir).await?.join(file_name);
+        fs::write(&file_path, content)
+            .await
+            .with_context(|| format!("无法创建缓存文件: {}", file_path.display()))?;
+        Ok(file_path)
+    }
+
+    /// 将多文件项目保存到缓存子目录 `sub_dir/<hash>` 中(已存在时直接使用),
+    /// 入口文件写入 `wrap` 包装后的代码;hash 覆盖所有文件、`key_suffix`(如语言、版本)和模板指纹;
+    /// 写入前校验所有文件路径,不会写入项目目录之外
+    pub async fn save_project_cache<F>(
+        sub_dir: &str,
+        project: &ProjectFiles,
+        entry_code: &str,
+        key_suffix: &str,
+        wrap: F,
+    ) -> Result<ProjectCache>
+    where
+        F: FnOnce(&str) -> String,
+    {
+        project.validate()?;
+        let hash =
+            Self::obtain_script_hash(&format!("{}\0{key_suffix}", project.cache_key(entry_code)));
+        let dir = Self::get_cache_dir().join(sub_dir).join(&hash);
+        let entry_path = Self::project_file_path(&dir, &project.entrypoint)?;
+
+        // 入口文件最后写入,入口文件存在说明项目目录已完整生成
+        if fs::try_exists(&entry_path).await.unwrap_or(false) {
+            debug!("从缓存中读取项目:hash值 {:?}", &hash);
+            return Ok(ProjectCache {
+                dir,
+                entry_path,
+                created: false,
+            });
+        }
+        for (path, content) in project.other_files() {
+            Self::write_project_file(&Self::project_file_path(&dir, path)?, content).await?;
+        }
+        Self::write_project_file(&entry_path, &wrap(entry_code)).await?;
+        debug!("创建项目缓存:hash值 {:?}", &hash);
+        Ok(ProjectCache {
+            dir,
+            entry_path,
+            created: true,
+        })
+    }
+
+    /// 项目文件在项目目录中的路径,路径不在项目目录中(如包含 `..` 或绝对路径)时返回错误
+    fn project_file_path(dir: &Path, path: &str) -> Result<PathBuf> {
+        let relative = Path::new(path);
+        let inside = relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        let file_path = dir.join(relative);
+        if !inside || !file_path.starts_with(dir) {
+            bail!("项目文件路径必须是不含 `..` 的相对路径: {path}");
+        }
+        Ok(file_path)
+    }
+
+    /// 写入项目文件,父目录不存在时创建
+    async fn write_project_file(path: &Path, content: &str) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)
+                .await
+                .with_context(|| format!("无法创建缓存目录: {}", parent.display()))?;
+        }
+        fs::write(path, content)
+            .await
+            .with_context(|| format!("无法创建缓存文件: {}", path.display()))
+    }
+
     /// 获取缓存目录路径
     fn get_cache_dir() -> PathBuf {
         // 在容器环境中使用固定路径
@@ -1,3 +1,3 @@
 mod code_file_cache;
 
-pub use code_file_cache::CodeFileCache;
+pub use code_file_cache::{CodeFileCache, PROJECT_CACHE_DIR, ProjectCache};
@@ -1,39 +1,69 @@
-use crate::cache::CodeFileCache;
-use crate::model::{CodeExecutor, CodeScriptExecutionResult, CommandExecutor, LanguageScript};
+use crate::cache::{CodeFileCache, PROJECT_CACHE_DIR};
+use crate::model::{
+    CodeExecutor, CodeScriptExecutionResult, ENTRYPOINT_ENV, InvocationContext, LanguageScript,
+    ProjectFiles, RunOptions, output_with_options,
+};
 use anyhow::Result;
 use log::{debug, error, info};
 use serde_json::Value;
+use std::path::PathBuf;
 use tokio::process::Command;
 
-/// 通用的 Deno 脚本执行逻辑，供 JS/TS Runner 复用
-pub async fn run_deno_script_with_params<F>(
+/// 生成 Deno 脚本缓存(已存在时直接使用),返回脚本路径;
+/// 多文件项目写入项目目录,返回包装后的入口文件路径
+pub async fn prepare_deno_script<F>(
     code: &str,
-    params: Option<Value>,
-    timeout_seconds: Option<u64>,
-    lang: LanguageScript,
+    lang: &LanguageScript,
+    project: Option<&ProjectFiles>,
     prepare_code_fn: F,
-) -> Result<CodeScriptExecutionResult>
+) -> Result<PathBuf>
 where
     F: Fn(&str, bool) -> String,
 {
-    debug!("开始执行{lang:?}脚本...,执行参数: {params:?}");
-
-    let hash = CodeFileCache::obtain_code_hash(code);
-    let cache_exist = CodeFileCache::check_code_file_cache_exisht(&hash, &lang).await;
+    if let Some(project) = project {
+        let cache = CodeFileCache::save_project_cache(
+            PROJECT_CACHE_DIR,
+            project,
+            code,
+            &format!("{lang:?}"),
+            |c| prepare_code_fn(c, true),
+        )
+        .await?;
+        return Ok(cache.entry_path);
+    }
+    let hash = CodeFileCache::obtain_script_hash(code);
+    let cache_exist = CodeFileCache::check_code_file_cache_exisht(&hash, lang).await;
 
     let run_code_script_file_tuple = if cache_exist {
-        let cache_code = CodeFileCache::get_code_file_cache(&hash, &lang).await;
+        let cache_code = CodeFileCache::get_code_file_cache(&hash, lang).await;
         debug!("从缓存中读取代码:hash值 {:?}", &hash);
         cache_code?
     } else {
         let wrapped_code = prepare_code_fn(code, true);
-        CodeFileCache::save_code_file_cache(&hash, &wrapped_code, &lang).await?;
-        let code_script_file_tuple = CodeFileCache::get_code_file_cache(&hash, &lang).await?;
+        CodeFileCache::save_code_file_cache(&hash, &wrapped_code, lang).await?;
+        let code_script_file_tuple = CodeFileCache::get_code_file_cache(&hash, lang).await?;
         debug!("创建脚本缓存:hash值 {:?}", &hash);
         code_script_file_tuple
     };
 
-    let temp_path = run_code_script_file_tuple.1;
+    Ok(run_code_script_file_tuple.1)
+}
+
+/// 通用的 Deno 脚本执行逻辑，供 JS/TS Runner 复用
+pub async fn run_deno_script_with_params<F>(
+    code: &str,
+    params: Option<Value>,
+    options: &RunOptions,
+    lang: LanguageScript,
+    prepare_code_fn: F,
+) -> Result<CodeScriptExecutionResult>
+where
+    F: Fn(&str, bool) -> String,
+{
+    debug!("开始执行{lang:?}脚本...,执行参数: {params:?}");
+
+    let temp_path =
+        prepare_deno_script(code, &lang, options.project.as_ref(), prepare_code_fn).await?;
 
     let params_json = match params {
         Some(p) => serde_json::to_string(&p)?,
@@ -49,16 +79,16 @@ where
         .arg("--no-check")
         .arg("--v8-flags=--max-heap-size=512")
         .env("INPUT_JSON", &params_json)
+        .env(ENTRYPOINT_ENV, options.entrypoint.as_deref().unwrap_or_default())
         .arg(&temp_path)
         .kill_on_drop(true);
+    // 调用上下文和声明的环境变量
+    InvocationContext::new(lang.id(), options).apply(&mut execute_command);
 
     debug!("Deno命令[{:?}]: {:?}", lang, &execute_command);
 
-    let executor = match timeout_seconds {
-        Some(timeout) => CommandExecutor::with_timeout(execute_command.output(), timeout),
-        None => CommandExecutor::default(execute_command.output()),
-    };
     info!("执行命令: {:?}", &execute_command);
+    let executor = output_with_options(&mut execute_command, options);
 
     let executor_result = executor.await;
     let output = match executor_result {
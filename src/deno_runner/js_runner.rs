@@ -1,12 +1,19 @@
 // deno 运行js脚本
-use crate::deno_runner::common_runner::run_deno_script_with_params;
-use crate::model::{CodeScriptExecutionResult, LanguageScript, RunCode};
+use crate::deno_runner::common_runner::{prepare_deno_script, run_deno_script_with_params};
+use crate::model::{
+    CodeScriptExecutionResult, JS_HARNESS, JS_SERIALIZER, JsRuntime, LanguageScript, RunCode,
+    RunOptions, js_function_table,
+};
+use crate::node_runner::NodeRunner;
+use crate::signature::ts_function_names;
 use anyhow::Result;
+use async_trait::async_trait;
 
 #[derive(Default)]
 pub struct JsRunner;
 
 
+#[async_trait]
 impl RunCode for JsRunner {
     async fn run_with_params(
         &self,
@@ -14,40 +21,57 @@ impl RunCode for JsRunner {
         params: Option<serde_json::Value>,
         timeout_seconds: Option<u64>,
     ) -> Result<CodeScriptExecutionResult> {
-        run_deno_script_with_params(
-            code,
-            params,
-            timeout_seconds,
-            LanguageScript::Js,
-            |c, show_logs| self.prepare_js_code(c, show_logs),
-        )
-        .await
+        self.run_with_options(code, params, &RunOptions::with_timeout(timeout_seconds))
+            .await
+    }
+
+    async fn run_with_options(
+        &self,
+        code: &str,
+        params: Option<serde_json::Value>,
+        options: &RunOptions,
+    ) -> Result<CodeScriptExecutionResult> {
+        match options.runtime.unwrap_or_default() {
+            JsRuntime::Deno => {
+                run_deno_script_with_params(code, params, options, LanguageScript::Js, |c, show_logs| {
+                    self.prepare_js_code(c, show_logs)
+                })
+                .await
+            }
+            JsRuntime::Node => NodeRunner.run_with_options(code, params, options).await,
+        }
+    }
+
+    async fn prepare(&self, code: &str, options: &RunOptions) -> Result<()> {
+        match options.runtime.unwrap_or_default() {
+            JsRuntime::Deno => {
+                prepare_deno_script(
+                    code,
+                    &LanguageScript::Js,
+                    options.project.as_ref(),
+                    |c, show_logs| self.prepare_js_code(c, show_logs),
+                )
+                .await?;
+                Ok(())
+            }
+            JsRuntime::Node => NodeRunner.prepare(code, options).await,
+        }
+    }
+
+    fn supports_project(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        "执行JavaScript代码,默认使用Deno,可通过runtime选择node".to_string()
     }
 }
 
 impl JsRunner {
     /// 准备JavaScript代码，添加日志捕获和handler函数执行逻辑
     fn prepare_js_code(&self, code: &str, show_logs: bool) -> String {
-        // 检查代码中是否包含ES模块特征
-        let is_esm = {
-            // 检查正式的 import/export 语句
-            let has_import_export = code.contains("import ")
-                || code.contains("export ")
-                || code.contains("import{")
-                || code.contains("export{");
-
-            // 检查动态 import
-            let has_dynamic_import = code.contains("import(");
-
-            // 检查是否有 require - CommonJS 的标志
-            let has_require = code.contains("require(");
-
-            // 如果有 import/export 特征，或者动态 import，但没有 require，则判定为 ESM
-            (has_import_export || has_dynamic_import) && !has_require
-        };
-
         // 根据代码特征选择合适的模板
-        let template = if is_esm {
+        let template = if Self::is_esm(code) {
             include_str!("../templates/js_template_es.js")
         } else {
             include_str!("../templates/js_template_normal.js")
@@ -55,7 +79,28 @@ impl JsRunner {
 
         // 替换模板中的占位符
         template
+            .replace("{{JS_HARNESS}}", JS_HARNESS)
+            .replace("{{JSON_SERIALIZER}}", JS_SERIALIZER)
+            .replace("{{USER_FUNCTIONS}}", &js_function_table(&ts_function_names(code)))
             .replace("{{USER_CODE}}", code)
             .replace("{{SHOW_LOGS}}", &show_logs.to_string())
     }
+
+    /// 检查代码中是否包含ES模块特征
+    pub(crate) fn is_esm(code: &str) -> bool {
+        // 检查正式的 import/export 语句
+        let has_import_export = code.contains("import ")
+            || code.contains("export ")
+            || code.contains("import{")
+            || code.contains("export{");
+
+        // 检查动态 import
+        let has_dynamic_import = code.contains("import(");
+
+        // 检查是否有 require - CommonJS 的标志
+        let has_require = code.contains("require(");
+
+        // 如果有 import/export 特征，或者动态 import，但没有 require，则判定为 ESM
+        (has_import_export || has_dynamic_import) && !has_require
+    }
 }
@@ -1,6 +1,8 @@
 mod common_runner;
 mod js_runner;
 mod ts_runner;
+mod type_check;
 
 pub use js_runner::JsRunner;
 pub use ts_runner::TsRunner;
+pub use type_check::check_typescript;
@@ -1,11 +1,27 @@
 // deno 运行ts脚本
-use crate::model::{CodeScriptExecutionResult, LanguageScript, RunCode};
+use crate::model::{
+    CodeScriptExecutionResult, JS_HARNESS, JS_SERIALIZER, JsRuntime, LanguageScript, RunCode,
+    RunOptions, js_function_table,
+};
 use anyhow::Result;
-use crate::deno_runner::common_runner::run_deno_script_with_params;
+use async_trait::async_trait;
+use crate::app_error::AppError;
+use crate::deno_runner::common_runner::{prepare_deno_script, run_deno_script_with_params};
+use crate::deno_runner::type_check::check_typescript;
+use crate::signature::ts_function_names;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// import 语句的结尾: 模块路径的引号,可能带有 import 属性(with { type: "json" })和分号
+static IMPORT_END_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"['"]\s*(?:(?:with|assert)\s*\{[^}]*\}\s*)?;?\s*(?://.*)?$"#)
+        .expect("invalid import end regex")
+});
 
 #[derive(Default)]
 pub struct TsRunner;
 
+#[async_trait]
 impl RunCode for TsRunner {
     async fn run_with_params(
         &self,
@@ -13,13 +29,53 @@ impl RunCode for TsRunner {
         params: Option<serde_json::Value>,
         timeout_seconds: Option<u64>,
     ) -> Result<CodeScriptExecutionResult> {
-        run_deno_script_with_params(
+        self.run_with_options(code, params, &RunOptions::with_timeout(timeout_seconds))
+            .await
+    }
+
+    async fn run_with_options(
+        &self,
+        code: &str,
+        params: Option<serde_json::Value>,
+        options: &RunOptions,
+    ) -> Result<CodeScriptExecutionResult> {
+        if options.runtime == Some(JsRuntime::Node) {
+            anyhow::bail!("TypeScript 暂不支持 node 运行时,请使用 deno");
+        }
+        // 有类型错误时不执行代码
+        if options.type_check {
+            let diagnostics =
+                check_typescript(code, options.project.as_ref(), options.timeout_seconds).await?;
+            if !diagnostics.is_empty() {
+                return Err(AppError::TypeCheckError(diagnostics).into());
+            }
+        }
+        run_deno_script_with_params(code, params, options, LanguageScript::Ts, |c, show_logs| {
+            self.prepare_ts_code(c, show_logs)
+        })
+        .await
+    }
+
+    async fn prepare(&self, code: &str, options: &RunOptions) -> Result<()> {
+        if options.runtime == Some(JsRuntime::Node) {
+            anyhow::bail!("TypeScript 暂不支持 node 运行时,请使用 deno");
+        }
+        prepare_deno_script(
             code,
-            params,
-            timeout_seconds,
-            LanguageScript::Ts,
+            &LanguageScript::Ts,
+            options.project.as_ref(),
             |c, show_logs| self.prepare_ts_code(c, show_logs),
-        ).await
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn supports_project(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        "使用Deno执行TypeScript代码".to_string()
     }
 }
 
@@ -29,9 +85,59 @@ impl TsRunner {
     /// 准备TypeScript代码，添加日志捕获和handler函数执行逻辑
     fn prepare_ts_code(&self, code: &str, show_logs: bool) -> String {
         let template = include_str!("../templates/ts_template.ts");
-        
+        let (imports, body) = split_static_imports(code);
+
         template
-            .replace("{{USER_CODE}}", code)
+            .replace("{{JS_HARNESS}}", JS_HARNESS)
+            .replace("{{JSON_SERIALIZER}}", JS_SERIALIZER)
+            .replace("{{USER_IMPORTS}}", &imports)
+            .replace("{{USER_FUNCTIONS}}", &js_function_table(&ts_function_names(&body)))
+            .replace("{{USER_CODE}}", &body)
             .replace("{{SHOW_LOGS}}", &show_logs.to_string())
     }
 }
+
+/// 拆分代码中的静态 import 语句: 模板在函数中执行用户代码,静态 import 必须放到模块顶层;
+/// 原位置保留空行,其余代码的行号不变
+fn split_static_imports(code: &str) -> (String, String) {
+    let mut imports = String::new();
+    let mut body = String::new();
+    let mut in_import = false;
+    for line in code.lines() {
+        let trimmed = line.trim_start();
+        if !in_import {
+            in_import = (trimmed.starts_with("import ") || trimmed.starts_with("import{"))
+                && !trimmed.starts_with("import (");
+        }
+        if in_import {
+            imports.push_str(line);
+            imports.push('\n');
+            body.push('\n');
+            in_import = !IMPORT_END_RE.is_match(line);
+        } else {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    (imports, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_static_imports;
+
+    #[test]
+    fn test_split_static_imports() {
+        let code = "import { add } from \"./math.ts\";\nimport {\n  sub,\n} from './math.ts'\nimport \"./setup.ts\";\nconst lazy = await import(\"./lazy.ts\");\nfunction handler() {}\n";
+        let (imports, body) = split_static_imports(code);
+        assert_eq!(
+            imports,
+            "import { add } from \"./math.ts\";\nimport {\n  sub,\n} from './math.ts'\nimport \"./setup.ts\";\n"
+        );
+        // 保留空行,其余代码的行号不变
+        assert_eq!(
+            body,
+            "\n\n\n\n\nconst lazy = await import(\"./lazy.ts\");\nfunction handler() {}\n"
+        );
+    }
+}
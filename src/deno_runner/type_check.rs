@@ -0,0 +1,259 @@
+// deno check 检查TypeScript代码的类型
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tokio::process::Command;
+
+use crate::model::{
+    CommandExecutor, DEFAULT_TIMEOUT_SECONDS, ProjectFiles, TypeDiagnostic, USER_CODE_FILE,
+};
+
+/// 提供给用户代码的全局类型声明
+const TS_GLOBALS: &str = include_str!("../templates/ts_globals.d.ts");
+
+// 类型检查目录中的全局类型声明文件和配置文件,使用不会与项目文件冲突的文件名
+const GLOBALS_FILE: &str = "run_code_globals.d.ts";
+const CONFIG_FILE: &str = "run_code_check.json";
+
+// 单文件代码在类型检查目录中的文件名
+const SINGLE_ENTRY_FILE: &str = "main.ts";
+
+// 诊断信息的第一行: "TS2322 [ERROR]: 错误信息",deno 2 中第一个诊断信息带有 "error: " 前缀
+static TS_HEADER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:error: )?(TS\d+) \[(?:ERROR|WARN)\]: (.*)$").expect("invalid ts header regex")
+});
+
+// 没有错误码的诊断信息(模块解析、语法错误),位置可能在同一行末尾
+static ERROR_HEADER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^error: (.*?)(?: at (\S+):(\d+):(\d+))?$").expect("invalid error header regex")
+});
+
+// 诊断信息的位置: "    at file:///tmp/xxx/main.ts:3:7"
+static LOCATION_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s+at (\S+):(\d+):(\d+)$").expect("invalid location regex"));
+
+// 代码片段下方标记错误位置的行: "      ^" 或 "      ~~~~"
+static MARKER_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*[~^]+\s*$").expect("invalid marker regex"));
+
+/// 使用 `deno check` 检查TypeScript代码的类型,返回所有诊断信息,类型正确时返回空列表
+///
+/// 代码按原样检查(不经过模板包装),诊断信息的行列号与用户代码一致;
+/// 多文件项目写入所有文件后检查入口文件,代码为入口文件的内容;
+/// 超过 `timeout_seconds`(未设置时使用默认超时时间)后终止 deno 并返回错误
+pub async fn check_typescript(
+    code: &str,
+    project: Option<&ProjectFiles>,
+    timeout_seconds: Option<u64>,
+) -> Result<Vec<TypeDiagnostic>> {
+    let dir = tempfile::tempdir().context("无法创建类型检查目录")?;
+    // 诊断信息中的路径为真实路径(如 macOS 的 /private/var)
+    let root = dir.path().canonicalize()?;
+
+    let entry = match project {
+        Some(project) => {
+            project.validate()?;
+            for (path, content) in project.other_files() {
+                write_file(&root, path, content).await?;
+            }
+            project.entrypoint.as_str()
+        }
+        None => SINGLE_ENTRY_FILE,
+    };
+    // 没有 import/export 的代码也按模块检查,追加在末尾不影响行号
+    write_file(&root, entry, &format!("{code}\nexport {{}};\n")).await?;
+    write_file(&root, GLOBALS_FILE, TS_GLOBALS).await?;
+    let config =
+        serde_json::json!({ "compilerOptions": { "types": [format!("./{GLOBALS_FILE}")] } });
+    write_file(&root, CONFIG_FILE, &config.to_string()).await?;
+
+    let mut command = Command::new("deno");
+    command
+        .arg("check")
+        .arg("--config")
+        .arg(root.join(CONFIG_FILE))
+        .arg(root.join(entry))
+        .current_dir(&root)
+        .env("NO_COLOR", "1")
+        .kill_on_drop(true);
+    info!("执行类型检查: {command:?}");
+    let timeout = timeout_seconds.unwrap_or(DEFAULT_TIMEOUT_SECONDS);
+    let output = CommandExecutor::with_timeout(command.output(), timeout)
+        .await
+        .with_context(|| format!("类型检查超时({timeout}秒)"))?
+        .context("无法执行 deno check")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    debug!("类型检查输出:\n{stdout}\n{stderr}");
+
+    let file_label = |path: &str| -> String {
+        let relative = path
+            .strip_prefix("file://")
+            .and_then(|p| Path::new(p).strip_prefix(&root).ok())
+            .map(|p| p.to_string_lossy().replace('\\', "/"));
+        match relative {
+            Some(relative) if project.is_none() && relative == SINGLE_ENTRY_FILE => {
+                USER_CODE_FILE.to_string()
+            }
+            Some(relative) => relative,
+            None => path.to_string(),
+        }
+    };
+    let diagnostics = parse_deno_diagnostics(&format!("{stdout}\n{stderr}"), file_label);
+    if !output.status.success() && diagnostics.is_empty() {
+        bail!("类型检查失败: {}", stderr.trim());
+    }
+    Ok(diagnostics)
+}
+
+/// 写入类型检查目录中的文件,自动创建上级目录
+async fn write_file(root: &Path, path: &str, content: &str) -> Result<()> {
+    let path = root.join(path);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&path, content).await?;
+    Ok(())
+}
+
+/// 解析 `deno check` 的输出,`file_label` 将诊断信息中的模块URL转换为报告的文件名;
+/// 没有位置的输出(如 "Found 2 errors.")会被忽略
+fn parse_deno_diagnostics(
+    output: &str,
+    file_label: impl Fn(&str) -> String,
+) -> Vec<TypeDiagnostic> {
+    let mut diagnostics = Vec::new();
+    // 当前诊断信息的错误码和到位置之前的所有行
+    let mut pending: Option<(Option<String>, Vec<&str>)> = None;
+    let mut push = |code: Option<String>, mut lines: Vec<&str>, location: (&str, &str, &str)| {
+        // 去掉位置之前的代码片段和标记行
+        if lines.len() > 2 && lines.last().is_some_and(|line| MARKER_RE.is_match(line)) {
+            lines.truncate(lines.len() - 2);
+        }
+        let message = lines
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        diagnostics.push(TypeDiagnostic {
+            file: file_label(location.0),
+            line: location.1.parse().unwrap_or(1),
+            column: location.2.parse().unwrap_or(1),
+            code,
+            message,
+        });
+    };
+
+    for line in output.lines() {
+        if let Some(captures) = TS_HEADER_RE.captures(line) {
+            let message = captures.get(2).map_or("", |m| m.as_str());
+            pending = Some((Some(captures[1].to_string()), vec![message]));
+        } else if let Some(captures) = ERROR_HEADER_RE.captures(line) {
+            let message = captures.get(1).map_or("", |m| m.as_str());
+            match (captures.get(2), captures.get(3), captures.get(4)) {
+                (Some(file), Some(row), Some(column)) => {
+                    push(
+                        None,
+                        vec![message],
+                        (file.as_str(), row.as_str(), column.as_str()),
+                    );
+                    pending = None;
+                }
+                _ => pending = Some((None, vec![message])),
+            }
+        } else if let Some(captures) = LOCATION_RE.captures(line) {
+            // 只使用第一个位置,之后的位置属于相关信息(如 "'a' is declared here.")
+            if let Some((code, lines)) = pending.take() {
+                let location = (
+                    captures.get(1).map_or("", |m| m.as_str()),
+                    captures.get(2).map_or("", |m| m.as_str()),
+                    captures.get(3).map_or("", |m| m.as_str()),
+                );
+                push(code, lines, location);
+            }
+        } else if let Some((_, lines)) = pending.as_mut() {
+            lines.push(line);
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_deno_diagnostics;
+
+    fn label(path: &str) -> String {
+        path.strip_prefix("file:///tmp/check/")
+            .unwrap_or(path)
+            .to_string()
+    }
+
+    #[test]
+    fn test_parse_ts_diagnostics() {
+        let output = r#"Check file:///tmp/check/main.ts
+error: TS2322 [ERROR]: Type 'string' is not assignable to type 'number'.
+const total: number = "1";
+      ~~~~~
+    at file:///tmp/check/main.ts:3:7
+
+TS2345 [ERROR]: Argument of type '{ a: number; }' is not assignable to parameter of type 'Input'.
+  Property 'b' is missing in type '{ a: number; }' but required in type 'Input'.
+add({ a: 1 });
+    ~~~~~~~~
+    at file:///tmp/check/lib/math.ts:12:5
+
+    'b' is declared here.
+      b: number;
+      ^
+        at file:///tmp/check/lib/math.ts:3:3
+
+Found 2 errors.
+
+error: Type checking failed.
+"#;
+        let diagnostics = parse_deno_diagnostics(output, label);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].file, "main.ts");
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (3, 7));
+        assert_eq!(diagnostics[0].code.as_deref(), Some("TS2322"));
+        assert_eq!(
+            diagnostics[0].message,
+            "Type 'string' is not assignable to type 'number'."
+        );
+        assert_eq!(diagnostics[1].file, "lib/math.ts");
+        assert_eq!((diagnostics[1].line, diagnostics[1].column), (12, 5));
+        assert_eq!(
+            diagnostics[1].message,
+            "Argument of type '{ a: number; }' is not assignable to parameter of type 'Input'.\nProperty 'b' is missing in type '{ a: number; }' but required in type 'Input'."
+        );
+    }
+
+    #[test]
+    fn test_parse_module_errors() {
+        // 没有错误码的诊断信息,位置在同一行或下一行
+        let output = "error: The module's source code could not be parsed: Expected ';', got 'x' at file:///tmp/check/main.ts:2:9\n";
+        let diagnostics = parse_deno_diagnostics(output, label);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, None);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 9));
+        assert_eq!(
+            diagnostics[0].message,
+            "The module's source code could not be parsed: Expected ';', got 'x'"
+        );
+
+        let output = "error: Module not found \"file:///tmp/check/missing.ts\".\n    at file:///tmp/check/main.ts:1:21\n";
+        let diagnostics = parse_deno_diagnostics(output, label);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, "main.ts");
+        assert_eq!(
+            diagnostics[0].message,
+            "Module not found \"file:///tmp/check/missing.ts\"."
+        );
+
+        assert!(parse_deno_diagnostics("Check file:///tmp/check/main.ts\n", label).is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::signature::{FunctionSignature, infer_signature};
+
+/// 默认别名,每次部署后指向最新的版本,未指定版本和别名时调用该别名指向的版本
+pub const LATEST_ALIAS: &str = "latest";
+
+///部署函数的参数
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FunctionDeployment {
+    #[schemars(
+        description = "函数名称,以字母开头,只能包含字母、数字、下划线和连字符,最长64个字符"
+    )]
+    pub name: String,
+
+    #[schemars(description = "语言标识,可通过 list_languages 工具获取已注册的语言")]
+    pub language: String,
+
+    #[schemars(description = "函数代码")]
+    pub code: String,
+
+    #[schemars(
+        description = "声明的依赖,部署时预先安装;Python为pip依赖(可带版本约束,如 requests<3),写入函数脚本的依赖元数据;JavaScript/TypeScript为npm包名或 jsr: 开头的JSR包名"
+    )]
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+
+    #[schemars(description = "可选的默认超时时间(秒),调用时未指定超时时间则使用该值")]
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+
+    #[schemars(description = "可选的函数描述")]
+    #[serde(default)]
+    pub description: Option<String>,
+
+    #[schemars(
+        description = "可选的参数JSON Schema(type为object),调用前校验参数,并作为该函数对应MCP工具的输入定义"
+    )]
+    #[serde(default, alias = "params_schema")]
+    pub input_schema: Option<Value>,
+
+    #[schemars(description = "可选的结果JSON Schema,调用后校验函数的返回值")]
+    #[serde(default)]
+    pub output_schema: Option<Value>,
+
+    #[schemars(
+        description = "可选的入口函数名,未指定时依次查找 handler、main;同一份代码可以使用不同的入口函数部署为多个函数"
+    )]
+    #[serde(default)]
+    pub entrypoint: Option<String>,
+
+    #[schemars(
+        description = "可选的环境变量,调用时设置到执行进程,handler 也可以通过调用上下文(context)的 env 只读访问"
+    )]
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+///函数的一个不可变版本,每次部署生成一个新版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionVersion {
+    //版本号,从1开始单调递增
+    pub version: u64,
+    //语言标识
+    pub language: String,
+    //函数代码
+    pub code: String,
+    //代码内容的BLAKE3 hash
+    pub content_hash: String,
+    //声明的依赖
+    pub dependencies: Vec<String>,
+    //默认超时时间(秒)
+    pub timeout_seconds: Option<u64>,
+    //函数描述
+    pub description: Option<String>,
+    //参数JSON Schema
+    #[serde(default, alias = "params_schema")]
+    pub input_schema: Option<Value>,
+    //结果JSON Schema
+    #[serde(default)]
+    pub output_schema: Option<Value>,
+    //入口函数名,未指定时依次查找 handler、main
+    #[serde(default)]
+    pub entrypoint: Option<String>,
+    //声明的环境变量
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    //部署时从handler签名推断的参数结构和描述
+    #[serde(default)]
+    pub signature: Option<FunctionSignature>,
+    //部署时间(unix毫秒)
+    pub created_at: u64,
+    //调用次数,保存在单独的统计文件中,读取函数时合并;旧的函数记录中保存的次数会累加
+    #[serde(default)]
+    pub invocation_count: u64,
+}
+
+impl FunctionVersion {
+    /// handler签名推断结果,旧版本没有保存推断结果时重新推断
+    pub fn signature(&self) -> FunctionSignature {
+        self.signature
+            .clone()
+            .unwrap_or_else(|| {
+                infer_signature(&self.language, &self.code, self.entrypoint.as_deref())
+            })
+    }
+
+    /// 对外展示的参数JSON Schema: 优先使用声明的 input_schema,否则使用从签名推断的结构
+    pub fn described_input_schema(&self) -> Option<Value> {
+        self.input_schema.clone().or_else(|| {
+            let signature = self.signature();
+            signature.inferred.then_some(signature.input_schema)
+        })
+    }
+
+    /// 对外展示的描述: 优先使用部署时指定的描述,否则使用从文档注释中提取的描述
+    pub fn described_description(&self) -> Option<String> {
+        self.description
+            .clone()
+            .or_else(|| self.signature().description)
+    }
+}
+
+///已部署的函数,包含所有版本和别名,持久化到本地存储
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredFunction {
+    //函数名称
+    pub name: String,
+    //所有版本,按版本号升序
+    pub versions: Vec<FunctionVersion>,
+    //别名 -> 版本号,如 latest、stable
+    pub aliases: BTreeMap<String, u64>,
+    //首次部署时间(unix毫秒)
+    pub created_at: u64,
+    //最近一次部署时间(unix毫秒)
+    pub updated_at: u64,
+}
+
+impl StoredFunction {
+    /// 获取指定版本
+    pub fn version(&self, version: u64) -> Option<&FunctionVersion> {
+        self.versions.iter().find(|v| v.version == version)
+    }
+
+    /// 获取 `latest` 别名指向的版本
+    pub fn latest(&self) -> Option<&FunctionVersion> {
+        self.aliases
+            .get(LATEST_ALIAS)
+            .and_then(|version| self.version(*version))
+    }
+
+    /// 最大的版本号,没有版本时为0
+    pub fn max_version(&self) -> u64 {
+        self.versions.iter().map(|v| v.version).max().unwrap_or(0)
+    }
+
+    /// 根据选择器获取版本
+    pub fn resolve(&self, selector: &FunctionSelector) -> Result<&FunctionVersion> {
+        let version = match selector {
+            FunctionSelector::Latest => self.aliases.get(LATEST_ALIAS).copied(),
+            FunctionSelector::Version(version) => Some(*version),
+            FunctionSelector::Alias(alias) => Some(
+                *self
+                    .aliases
+                    .get(alias)
+                    .ok_or_else(|| anyhow!("函数 {} 不存在别名: {alias}", self.name))?,
+            ),
+        };
+        version
+            .and_then(|version| self.version(version))
+            .ok_or_else(|| anyhow!("函数 {} 不存在版本: {selector}", self.name))
+    }
+
+    /// 指向指定版本的别名
+    pub fn aliases_of(&self, version: u64) -> Vec<&str> {
+        self.aliases
+            .iter()
+            .filter(|(_, v)| **v == version)
+            .map(|(alias, _)| alias.as_str())
+            .collect()
+    }
+
+    /// 函数概要信息,不包含代码
+    pub fn summary(&self) -> Value {
+        let latest = self.latest();
+        json!({
+            "name": self.name,
+            "language": latest.map(|v| v.language.as_str()),
+            "description": latest.and_then(FunctionVersion::described_description),
+            "version": latest.map(|v| v.version),
+            "content_hash": latest.map(|v| v.content_hash.as_str()),
+            "aliases": self.aliases,
+            "updated_at": self.updated_at
+        })
+    }
+}
+
+///调用函数时选择的版本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FunctionSelector {
+    //latest 别名指向的版本
+    Latest,
+    //指定版本号: name@3
+    Version(u64),
+    //指定别名: name:stable
+    Alias(String),
+}
+
+impl fmt::Display for FunctionSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FunctionSelector::Latest => write!(f, "{LATEST_ALIAS}"),
+            FunctionSelector::Version(version) => write!(f, "@{version}"),
+            FunctionSelector::Alias(alias) => write!(f, ":{alias}"),
+        }
+    }
+}
+
+///函数引用: `name`、`name@version` 或 `name:alias`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionRef {
+    //函数名称
+    pub name: String,
+    //选择的版本
+    pub selector: FunctionSelector,
+}
+
+impl FunctionRef {
+    /// 解析函数引用
+    pub fn parse(reference: &str) -> Result<Self> {
+        let reference = reference.trim();
+        let (name, selector) = if let Some((name, version)) = reference.split_once('@') {
+            let version = version
+                .parse::<u64>()
+                .map_err(|_| anyhow!("无效的函数版本号: {reference}"))?;
+            (name, FunctionSelector::Version(version))
+        } else if let Some((name, alias)) = reference.split_once(':') {
+            if alias.is_empty() {
+                bail!("无效的函数别名: {reference}");
+            }
+            (name, FunctionSelector::Alias(alias.to_string()))
+        } else {
+            (reference, FunctionSelector::Latest)
+        };
+        Ok(Self {
+            name: name.to_string(),
+            selector,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FunctionRef, FunctionSelector};
+
+    #[test]
+    fn test_parse_function_ref() {
+        let reference = FunctionRef::parse("greet").unwrap();
+        assert_eq!(reference.name, "greet");
+        assert_eq!(reference.selector, FunctionSelector::Latest);
+
+        let reference = FunctionRef::parse("greet@3").unwrap();
+        assert_eq!(reference.name, "greet");
+        assert_eq!(reference.selector, FunctionSelector::Version(3));
+
+        let reference = FunctionRef::parse("greet:stable").unwrap();
+        assert_eq!(reference.selector, FunctionSelector::Alias("stable".into()));
+
+        assert!(FunctionRef::parse("greet@abc").is_err());
+        assert!(FunctionRef::parse("greet:").is_err());
+    }
+}
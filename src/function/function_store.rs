@@ -0,0 +1,519 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result, anyhow, bail};
+use log::{info, warn};
+use once_cell::sync::{Lazy, OnceCell};
+use regex::Regex;
+use serde_json::{Value, json};
+use tokio::sync::broadcast;
+
+use crate::cache::CodeFileCache;
+use crate::function::{
+    FunctionDeployment, FunctionRef, FunctionVersion, LATEST_ALIAS, StoredFunction,
+};
+use crate::job::now_millis;
+use crate::model::{
+    CodeExecutor, CodeScriptExecutionResult, CompiledSchema, FunctionIdentity, RunOptions,
+    validate_entrypoint, validate_env,
+};
+use crate::registry::RunnerRegistry;
+use crate::signature::infer_signature;
+use crate::warm_up::install_function_dependencies;
+
+/// 函数存储的默认目录
+pub const DEFAULT_FUNCTION_STORE_DIR: &str = "/tmp/run_code_functions";
+
+/// 全局函数存储,第一次使用时使用默认目录创建,也可以通过 [`FunctionStore::init_global`] 指定目录
+static GLOBAL_FUNCTION_STORE: OnceCell<FunctionStore> = OnceCell::new();
+
+static NAME_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[A-Za-z][A-Za-z0-9_-]{0,63}$").expect("invalid function name regex")
+});
+
+///已部署函数的本地存储,每个函数保存为一个JSON文件: `<dir>/<name>.json`,
+///各版本的调用次数保存在单独的统计文件 `<dir>/<name>.stats` 中,调用函数时不改写函数记录
+#[derive(Debug, Clone)]
+pub struct FunctionStore {
+    dir: PathBuf,
+    lock: Arc<Mutex<()>>,
+    changes: broadcast::Sender<()>,
+}
+
+impl FunctionStore {
+    /// 创建存储,目录不存在时自动创建
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("无法创建函数存储目录: {}", dir.display()))?;
+        Ok(Self {
+            dir,
+            lock: Arc::new(Mutex::new(())),
+            changes: broadcast::channel(16).0,
+        })
+    }
+
+    /// 使用指定目录初始化全局函数存储,只能初始化一次
+    pub fn init_global(dir: impl AsRef<Path>) -> Result<&'static FunctionStore> {
+        let store = Self::new(dir)?;
+        GLOBAL_FUNCTION_STORE
+            .set(store)
+            .map_err(|_| anyhow!("全局函数存储已初始化"))?;
+        Self::global()
+    }
+
+    /// 获取全局函数存储,未初始化时使用默认目录创建
+    pub fn global() -> Result<&'static FunctionStore> {
+        GLOBAL_FUNCTION_STORE.get_or_try_init(|| Self::new(DEFAULT_FUNCTION_STORE_DIR))
+    }
+
+    /// 部署函数,生成一个新的不可变版本,并将 `latest` 别名指向该版本
+    ///
+    /// 部署前调用运行器的 `prepare` 生成代码缓存(编译型语言会完成编译),并安装声明的依赖
+    pub async fn deploy(&self, deployment: FunctionDeployment) -> Result<StoredFunction> {
+        validate_name(&deployment.name)?;
+        validate_input_schema(deployment.input_schema.as_ref())?;
+        validate_output_schema(deployment.output_schema.as_ref())?;
+        validate_env(&deployment.env)?;
+        let Some(runner) = RunnerRegistry::global_runner(&deployment.language) else {
+            bail!(
+                "未注册的语言: {}, 可选项: {}",
+                deployment.language,
+                RunnerRegistry::global_language_ids().join(", ")
+            );
+        };
+
+        if let Some(entrypoint) = &deployment.entrypoint {
+            validate_entrypoint(entrypoint)?;
+        }
+        install_function_dependencies(&deployment.language, &deployment.dependencies)
+            .await
+            .with_context(|| format!("安装函数 {} 的依赖失败", deployment.name))?;
+        let options = RunOptions {
+            timeout_seconds: deployment.timeout_seconds,
+            entrypoint: deployment.entrypoint.clone(),
+            dependencies: deployment.dependencies.clone(),
+            ..Default::default()
+        };
+        runner
+            .prepare(&deployment.code, &options)
+            .await
+            .with_context(|| format!("函数 {} 部署失败", deployment.name))?;
+
+        let _guard = self.lock();
+        let now = now_millis();
+        let mut function =
+            self.read_function(&deployment.name)?
+                .unwrap_or_else(|| StoredFunction {
+                    name: deployment.name.clone(),
+                    versions: vec![],
+                    aliases: BTreeMap::new(),
+                    created_at: now,
+                    updated_at: now,
+                });
+        let version = function.max_version() + 1;
+        let signature = infer_signature(
+            &deployment.language,
+            &deployment.code,
+            deployment.entrypoint.as_deref(),
+        );
+        function.versions.push(FunctionVersion {
+            version,
+            content_hash: CodeFileCache::obtain_code_hash(&deployment.code),
+            language: deployment.language,
+            code: deployment.code,
+            dependencies: deployment.dependencies,
+            timeout_seconds: deployment.timeout_seconds,
+            description: deployment.description,
+            input_schema: deployment.input_schema,
+            output_schema: deployment.output_schema,
+            entrypoint: deployment.entrypoint,
+            env: deployment.env,
+            signature: Some(signature),
+            created_at: now,
+            invocation_count: 0,
+        });
+        function.aliases.insert(LATEST_ALIAS.to_string(), version);
+        function.updated_at = now;
+        self.save(&function)?;
+        self.notify_changed();
+        info!("部署函数: {}@{version}", function.name);
+        Ok(function)
+    }
+
+    /// 获取函数(包含各版本的调用次数),不存在时返回 None
+    pub fn get(&self, name: &str) -> Result<Option<StoredFunction>> {
+        self.read_function(name)?
+            .map(|mut function| {
+                self.merge_stats(&mut function)?;
+                Ok(function)
+            })
+            .transpose()
+    }
+
+    /// 列出所有函数,按名称排序;无法解析的文件会被跳过
+    pub fn list(&self) -> Result<Vec<StoredFunction>> {
+        let mut functions = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let function = fs::read(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| Ok(serde_json::from_slice::<StoredFunction>(&content)?));
+            match function.and_then(|mut function| {
+                self.merge_stats(&mut function)?;
+                Ok(function)
+            }) {
+                Ok(function) => functions.push(function),
+                Err(e) => warn!("跳过无法解析的函数记录 {}: {e}", path.display()),
+            }
+        }
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(functions)
+    }
+
+    /// 删除函数及其所有版本,返回函数是否存在
+    pub fn delete(&self, name: &str) -> Result<bool> {
+        validate_name(name)?;
+        let _guard = self.lock();
+        let path = self.function_path(name);
+        if !path.exists() {
+            return Ok(false);
+        }
+        fs::remove_file(&path).with_context(|| format!("无法删除函数记录: {}", path.display()))?;
+        for stats_path in [self.stats_path(name), self.stats_lock_path(name)] {
+            match fs::remove_file(&stats_path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    warn!("无法删除函数统计文件 {}: {e}", stats_path.display())
+                }
+                _ => {}
+            }
+        }
+        self.notify_changed();
+        info!("删除函数: {name}");
+        Ok(true)
+    }
+
+    /// 将别名指向指定版本,如 `stable`
+    pub fn set_alias(&self, name: &str, alias: &str, version: u64) -> Result<StoredFunction> {
+        validate_name(alias).context("无效的别名")?;
+        let _guard = self.lock();
+        let mut function = self.get_existing(name)?;
+        if function.version(version).is_none() {
+            bail!("函数 {name} 不存在版本: {version}");
+        }
+        function.aliases.insert(alias.to_string(), version);
+        self.save(&function)?;
+        self.notify_changed();
+        info!("函数别名 {name}:{alias} -> {version}");
+        Ok(function)
+    }
+
+    /// 回滚别名(默认 `latest`)到指定版本,未指定版本时回滚到别名当前指向版本的上一个版本
+    pub fn rollback(
+        &self,
+        name: &str,
+        alias: Option<&str>,
+        version: Option<u64>,
+    ) -> Result<StoredFunction> {
+        let alias = alias.unwrap_or(LATEST_ALIAS);
+        let target = {
+            let function = self.get_existing(name)?;
+            match version {
+                Some(version) => version,
+                None => {
+                    let current = *function
+                        .aliases
+                        .get(alias)
+                        .ok_or_else(|| anyhow!("函数 {name} 不存在别名: {alias}"))?;
+                    function
+                        .versions
+                        .iter()
+                        .map(|v| v.version)
+                        .filter(|v| *v < current)
+                        .max()
+                        .ok_or_else(|| anyhow!("函数 {name}:{alias} 已经是最早的版本,无法回滚"))?
+                }
+            }
+        };
+        info!("回滚函数 {name}:{alias} 到版本 {target}");
+        self.set_alias(name, alias, target)
+    }
+
+    /// 调用函数,`reference` 可以是 `name`、`name@version` 或 `name:alias`
+    ///
+    /// 未指定超时时间时使用该版本的默认超时时间;函数代码与直接执行的代码一样通过 [`CodeFileCache`] 按内容hash缓存
+    pub async fn invoke(
+        &self,
+        reference: &str,
+        params: Option<Value>,
+        options: &RunOptions,
+    ) -> Result<CodeScriptExecutionResult> {
+        if options.project.is_some() {
+            bail!("调用已部署的函数时不能指定多文件项目 files,函数代码使用部署时的代码");
+        }
+        let reference = FunctionRef::parse(reference)?;
+        let version = self
+            .get_existing(&reference.name)?
+            .resolve(&reference.selector)?
+            .clone();
+        self.record_invocation(&reference.name, version.version)
+            .await?;
+
+        let options = RunOptions {
+            timeout_seconds: options.timeout_seconds.or(version.timeout_seconds),
+            input_schema: options.input_schema.clone().or(version.input_schema),
+            output_schema: options.output_schema.clone().or(version.output_schema),
+            entrypoint: options.entrypoint.clone().or(version.entrypoint),
+            dependencies: version.dependencies,
+            function: Some(FunctionIdentity {
+                name: reference.name.clone(),
+                version: version.version,
+            }),
+            // 执行选项中的环境变量覆盖部署时声明的同名环境变量
+            env: version.env.into_iter().chain(options.env.clone()).collect(),
+            ..options.clone()
+        };
+        info!(
+            "调用函数: {}@{} [{}]",
+            reference.name, version.version, version.language
+        );
+        CodeExecutor::execute_with_options(&version.code, &version.language, params, &options).await
+    }
+
+    /// 订阅函数变更(部署、删除、别名变化),只包含当前进程内的变更
+    pub fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.changes.subscribe()
+    }
+
+    /// 存储目录中所有函数记录的文件名和修改时间,用于发现其他进程(如命令行)对函数的修改
+    pub fn revision(&self) -> Result<Vec<(String, SystemTime)>> {
+        let mut revision = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !file_name.ends_with(".json") {
+                continue;
+            }
+            revision.push((file_name, entry.metadata()?.modified()?));
+        }
+        revision.sort();
+        Ok(revision)
+    }
+
+    fn notify_changed(&self) {
+        // 没有订阅者时发送失败,忽略即可
+        let _ = self.changes.send(());
+    }
+
+    /// 描述函数指定版本的签名: 参数JSON Schema、结果JSON Schema和描述
+    ///
+    /// 部署时声明了 `input_schema` 的使用声明的结构,否则使用从handler签名推断的结构
+    pub fn describe(&self, reference: &str) -> Result<Value> {
+        let reference = FunctionRef::parse(reference)?;
+        let function = self.get_existing(&reference.name)?;
+        let version = function.resolve(&reference.selector)?;
+        let signature = version.signature();
+        Ok(json!({
+            "name": function.name,
+            "version": version.version,
+            "language": version.language,
+            "handler": signature.handler,
+            "description": version.described_description(),
+            "input_schema": version
+                .described_input_schema()
+                .unwrap_or_else(|| json!({ "type": "object" })),
+            "input_schema_source": if version.input_schema.is_some() {
+                "declared"
+            } else if signature.inferred {
+                "inferred"
+            } else {
+                "none"
+            },
+            "output_schema": version.output_schema,
+        }))
+    }
+
+    /// 读取函数记录,不包含统计文件中的调用次数;修改函数记录时使用,避免调用次数写回函数记录
+    fn read_function(&self, name: &str) -> Result<Option<StoredFunction>> {
+        validate_name(name)?;
+        let path = self.function_path(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read(&path).with_context(|| format!("无法读取函数记录: {}", path.display()))?;
+        Ok(Some(serde_json::from_slice(&content)?))
+    }
+
+    fn get_existing(&self, name: &str) -> Result<StoredFunction> {
+        self.read_function(name)?
+            .ok_or_else(|| anyhow!("函数不存在: {name}"))
+    }
+
+    /// 读取各版本的调用次数: 版本号 -> 调用次数,统计文件不存在时为空
+    fn read_stats(&self, name: &str) -> Result<BTreeMap<u64, u64>> {
+        let path = self.stats_path(name);
+        match fs::read(&path) {
+            Ok(content) => serde_json::from_slice(&content)
+                .with_context(|| format!("无法解析函数统计文件: {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e).with_context(|| format!("无法读取函数统计文件: {}", path.display())),
+        }
+    }
+
+    /// 将统计文件中的调用次数累加到各版本
+    fn merge_stats(&self, function: &mut StoredFunction) -> Result<()> {
+        let stats = self.read_stats(&function.name)?;
+        for version in &mut function.versions {
+            version.invocation_count += stats.get(&version.version).copied().unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    /// 记录一次调用,文件锁和文件读写可能阻塞,在阻塞任务中执行,不占用异步运行时的工作线程
+    async fn record_invocation(&self, name: &str, version: u64) -> Result<()> {
+        let store = self.clone();
+        let name = name.to_string();
+        tokio::task::spawn_blocking(move || store.record_invocation_blocking(&name, version))
+            .await
+            .context("记录函数调用次数的任务异常结束")?
+    }
+
+    /// 在统计文件的锁内读-改-写,多个进程同时调用不会丢失次数;
+    /// 先写临时文件再重命名,中断时不会留下不完整的统计文件
+    fn record_invocation_blocking(&self, name: &str, version: u64) -> Result<()> {
+        let _lock = self.lock_stats(name)?;
+        let mut stats = self.read_stats(name)?;
+        *stats.entry(version).or_default() += 1;
+        let path = self.stats_path(name);
+        let temp_path = path.with_extension("stats.tmp");
+        fs::write(&temp_path, serde_json::to_vec(&stats)?)
+            .with_context(|| format!("无法写入函数统计文件: {}", temp_path.display()))?;
+        fs::rename(&temp_path, &path)
+            .with_context(|| format!("无法保存函数统计文件: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// 打开并独占锁定统计文件的锁文件,返回的文件关闭时释放锁,只在阻塞任务中调用;
+    /// 文件锁对其他进程和同一进程中的其他调用都有效
+    fn lock_stats(&self, name: &str) -> Result<fs::File> {
+        let path = self.stats_lock_path(name);
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("无法打开函数统计锁文件: {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::fd::AsRawFd;
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+                return Err(std::io::Error::last_os_error())
+                    .with_context(|| format!("无法锁定函数统计文件: {}", path.display()));
+            }
+        }
+        Ok(file)
+    }
+
+    // 串行化同一进程内对函数记录的读-改-写
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 保存函数记录,先写临时文件再重命名,避免留下不完整的文件
+    fn save(&self, function: &StoredFunction) -> Result<()> {
+        let path = self.function_path(&function.name);
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, serde_json::to_vec_pretty(function)?)
+            .with_context(|| format!("无法写入函数记录: {}", temp_path.display()))?;
+        fs::rename(&temp_path, &path)
+            .with_context(|| format!("无法保存函数记录: {}", path.display()))?;
+        Ok(())
+    }
+
+    fn function_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.json"))
+    }
+
+    fn stats_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.stats"))
+    }
+
+    fn stats_lock_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.stats.lock"))
+    }
+}
+
+/// 校验函数名称和别名,避免与文件路径和调用语法冲突
+fn validate_name(name: &str) -> Result<()> {
+    if !NAME_REGEX.is_match(name) {
+        bail!("无效的名称: {name:?},需以字母开头,只能包含字母、数字、下划线和连字符,最长64个字符");
+    }
+    Ok(())
+}
+
+/// 校验参数JSON Schema,必须是 type 为 object 的有效JSON Schema对象
+fn validate_input_schema(schema: Option<&Value>) -> Result<()> {
+    let Some(schema) = schema else {
+        return Ok(());
+    };
+    let Some(object) = schema.as_object() else {
+        bail!("参数JSON Schema必须是JSON对象");
+    };
+    if object
+        .get("type")
+        .is_some_and(|schema_type| schema_type != "object")
+    {
+        bail!("参数JSON Schema的type必须是object");
+    }
+    CompiledSchema::compile(schema).context("参数JSON Schema无效")?;
+    Ok(())
+}
+
+/// 校验结果JSON Schema,必须是有效的JSON Schema对象或布尔值
+fn validate_output_schema(schema: Option<&Value>) -> Result<()> {
+    match schema {
+        None => Ok(()),
+        Some(schema @ (Value::Object(_) | Value::Bool(_))) => {
+            CompiledSchema::compile(schema).context("结果JSON Schema无效")?;
+            Ok(())
+        }
+        Some(_) => bail!("结果JSON Schema必须是JSON对象"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_input_schema, validate_name, validate_output_schema};
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("hello").is_ok());
+        assert!(validate_name("sum_2-numbers").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("2fast").is_err());
+        assert!(validate_name("../etc/passwd").is_err());
+        assert!(validate_name("name@1").is_err());
+        assert!(validate_name(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_validate_schemas() {
+        assert!(validate_input_schema(None).is_ok());
+        assert!(validate_input_schema(Some(&json!({ "type": "object" }))).is_ok());
+        assert!(validate_input_schema(Some(&json!({ "properties": {} }))).is_ok());
+        assert!(validate_input_schema(Some(&json!({ "type": "array" }))).is_err());
+        assert!(validate_input_schema(Some(&json!("object"))).is_err());
+        assert!(validate_output_schema(Some(&json!({ "type": "array" }))).is_ok());
+        assert!(validate_output_schema(Some(&json!([]))).is_err());
+        assert!(validate_input_schema(Some(&json!({ "$ref": "#/$defs/missing" }))).is_err());
+        assert!(validate_output_schema(Some(&json!({ "minimum": "1" }))).is_err());
+    }
+}
@@ -0,0 +1,8 @@
+mod function_model;
+mod function_store;
+
+pub use function_model::{
+    FunctionDeployment, FunctionRef, FunctionSelector, FunctionVersion, LATEST_ALIAS,
+    StoredFunction,
+};
+pub use function_store::{DEFAULT_FUNCTION_STORE_DIR, FunctionStore};
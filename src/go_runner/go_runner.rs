@@ -0,0 +1,326 @@
+//通过 go build 编译并运行 go 代码,编译后的二进制文件按代码hash缓存
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cache::CodeFileCache,
+    model::{
+        CodeExecutor, CodeScriptExecutionResult, CommandExecutor, InvocationContext,
+        LanguageScript, RunCode, RunOptions, output_with_options,
+    },
+    signature::{go_handler_takes_context, resolve_go_entrypoint},
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use log::{debug, error, info};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tokio::{fs, process::Command, sync::Mutex};
+
+#[derive(Default)]
+pub struct GoRunner;
+
+//go 项目缓存子目录,每个代码hash一个项目目录
+const GO_CACHE_DIR: &str = "go";
+
+//编译后的二进制文件名
+const GO_BINARY_NAME: &str = "handler";
+
+//定义国内go模块代理地址
+const GO_PROXY_ADDRESS: &str = "https://goproxy.cn,direct";
+
+//编译超时时间(秒),首次下载依赖可能较慢
+const GO_BUILD_TIMEOUT_SECONDS: u64 = 300;
+
+// 避免同一份代码被并发编译
+static GO_BUILD_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+static PACKAGE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*package\s+\w+").expect("invalid package regex"));
+
+static IMPORT_BLOCK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?s)\bimport\s*\((.*?)\)|\bimport\s+(?:\w+\s+|[._]\s+)?"([^"]+)""#)
+        .expect("invalid import regex")
+});
+
+static QUOTED_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#""([^"]+)""#).expect("invalid quoted regex"));
+
+#[async_trait]
+impl RunCode for GoRunner {
+    async fn run_with_params(
+        &self,
+        code: &str,
+        params: Option<serde_json::Value>,
+        timeout_seconds: Option<u64>,
+    ) -> Result<CodeScriptExecutionResult> {
+        self.run_with_options(code, params, &RunOptions::with_timeout(timeout_seconds))
+            .await
+    }
+
+    async fn run_with_options(
+        &self,
+        code: &str,
+        params: Option<serde_json::Value>,
+        options: &RunOptions,
+    ) -> Result<CodeScriptExecutionResult> {
+        debug!("开始执行Go代码...,执行参数: {params:?}");
+
+        let handler = match resolve_go_entrypoint(code, options.entrypoint.as_deref()) {
+            Ok(handler) => handler,
+            Err(e) => {
+                return Ok(CodeScriptExecutionResult {
+                    result: None,
+                    logs: vec![],
+                    log_entries: vec![],
+                    success: false,
+                    error: Some(e.to_string()),
+                });
+            }
+        };
+        let (project_dir, build_error) = self.ensure_compiled(code, &handler).await?;
+        if let Some(build_error) = build_error {
+            return Ok(CodeScriptExecutionResult {
+                result: None,
+                logs: vec![],
+                log_entries: vec![],
+                success: false,
+                error: Some(format!("Go编译失败: {build_error}")),
+            });
+        }
+        let binary_path = project_dir.join(GO_BINARY_NAME);
+
+        let params_json = match params {
+            Some(p) => serde_json::to_string(&p)?,
+            None => "{}".to_string(),
+        };
+
+        let mut execute_command = Command::new(&binary_path);
+        execute_command
+            .env("INPUT_JSON", &params_json)
+            .current_dir(&project_dir)
+            .kill_on_drop(true);
+        // 调用上下文和声明的环境变量
+        InvocationContext::new(LanguageScript::Go.id(), options).apply(&mut execute_command);
+
+        info!("执行命令: {:?}", &execute_command);
+
+        let executor = output_with_options(&mut execute_command, options);
+
+        let output = match executor.await {
+            Ok(cmd_result) => match cmd_result {
+                Ok(output) => output,
+                Err(e) => {
+                    error!("Go程序执行失败: {e:?}");
+                    return Err(e.into());
+                }
+            },
+            Err(e) => {
+                error!("Go任务执行异常: {e:?}");
+                return Err(e.into());
+            }
+        };
+        debug!("标准输出:\n{}", String::from_utf8_lossy(&output.stdout));
+        debug!("错误输出:\n{}", String::from_utf8_lossy(&output.stderr));
+
+        CodeExecutor::parse_execution_output(&output.stdout, &output.stderr).await
+    }
+
+    async fn prepare(&self, code: &str, options: &RunOptions) -> Result<()> {
+        let handler = resolve_go_entrypoint(code, options.entrypoint.as_deref())?;
+        if let (_, Some(build_error)) = self.ensure_compiled(code, &handler).await? {
+            anyhow::bail!("Go编译失败: {build_error}");
+        }
+        Ok(())
+    }
+
+    fn description(&self) -> String {
+        "使用Go工具链编译执行Go代码,需定义 func Handler(input map[string]any) (any, error),参数也可以是结构体;可通过 entrypoint 指定其他函数"
+            .to_string()
+    }
+}
+
+impl GoRunner {
+    /// 确保代码已编译(二进制文件按代码和入口函数的hash缓存),返回项目目录,
+    /// 编译失败时同时返回编译错误信息
+    async fn ensure_compiled(&self, code: &str, handler: &str) -> Result<(PathBuf, Option<String>)> {
+        let hash = CodeFileCache::obtain_script_hash(&format!("{code}\0entrypoint:{handler}"));
+        let project_dir =
+            CodeFileCache::ensure_cache_sub_dir(&format!("{GO_CACHE_DIR}/{hash}")).await?;
+
+        if fs::try_exists(project_dir.join(GO_BINARY_NAME))
+            .await
+            .unwrap_or(false)
+        {
+            debug!("使用缓存的Go二进制文件:hash值 {:?}", &hash);
+            return Ok((project_dir, None));
+        }
+        let build_error = self.build(code, handler, &project_dir).await?;
+        Ok((project_dir, build_error))
+    }
+
+    /// 生成 main 包的项目文件并编译,编译失败时返回编译错误信息
+    async fn build(&self, code: &str, handler: &str, project_dir: &Path) -> Result<Option<String>> {
+        let _guard = GO_BUILD_LOCK.lock().await;
+
+        let binary_path = project_dir.join(GO_BINARY_NAME);
+        if fs::try_exists(&binary_path).await.unwrap_or(false) {
+            return Ok(None);
+        }
+
+        fs::write(
+            project_dir.join("handler.go"),
+            Self::prepare_handler_code(code),
+        )
+        .await
+        .context("无法写入 handler.go")?;
+        fs::write(
+            project_dir.join("main.go"),
+            self.prepare_main_code(code, handler, true),
+        )
+            .await
+            .context("无法写入 main.go")?;
+        fs::write(project_dir.join("go.mod"), "module runcode\n\ngo 1.21\n")
+            .await
+            .context("无法写入 go.mod")?;
+
+        // 引用了第三方模块时,需要先下载依赖
+        if Self::has_third_party_imports(code) {
+            info!("正在下载Go依赖...");
+            let mut tidy = Command::new("go");
+            tidy.arg("mod")
+                .arg("tidy")
+                .env("GOPROXY", GO_PROXY_ADDRESS)
+                .current_dir(project_dir)
+                .kill_on_drop(true);
+            let output = CommandExecutor::with_timeout(tidy.output(), GO_BUILD_TIMEOUT_SECONDS)
+                .await?
+                .context("执行 go mod tidy 失败")?;
+            if !output.status.success() {
+                return Ok(Some(String::from_utf8_lossy(&output.stderr).to_string()));
+            }
+        }
+
+        // 先编译到临时文件,成功后再重命名,避免留下不完整的二进制文件
+        let temp_binary = format!("{GO_BINARY_NAME}.{}.tmp", std::process::id());
+        let mut build = Command::new("go");
+        build
+            .arg("build")
+            .arg("-o")
+            .arg(&temp_binary)
+            .arg(".")
+            .env("GOPROXY", GO_PROXY_ADDRESS)
+            .current_dir(project_dir)
+            .kill_on_drop(true);
+        info!("编译命令: {build:?}");
+
+        let output = CommandExecutor::with_timeout(build.output(), GO_BUILD_TIMEOUT_SECONDS)
+            .await?
+            .context("执行 go build 失败")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            error!("Go编译失败: {stderr}");
+            return Ok(Some(stderr));
+        }
+
+        fs::rename(project_dir.join(&temp_binary), &binary_path)
+            .await
+            .context("无法保存编译后的二进制文件")?;
+        info!("Go编译完成: {}", binary_path.display());
+        Ok(None)
+    }
+
+    /// 准备用户代码,统一使用 main 包
+    pub(crate) fn prepare_handler_code(code: &str) -> String {
+        if PACKAGE_RE.is_match(code) {
+            PACKAGE_RE.replace(code, "package main").to_string()
+        } else {
+            format!("package main\n\n{code}")
+        }
+    }
+
+    /// 准备 main 函数,读取INPUT_JSON、调用入口函数、捕获日志并输出执行结果;
+    /// 入口函数声明了调用上下文参数时,通过 `runCodeWithContext` 传入调用上下文
+    fn prepare_main_code(&self, code: &str, handler: &str, show_logs: bool) -> String {
+        let template = include_str!("../templates/go_template.go");
+        let handler = if go_handler_takes_context(code, handler) {
+            format!("runCodeWithContext({handler})")
+        } else {
+            handler.to_string()
+        };
+
+        template
+            .replace("{{HANDLER}}", &handler)
+            .replace("{{SHOW_LOGS}}", &show_logs.to_string())
+    }
+
+    /// 解析代码中导入的包路径
+    pub(crate) fn parse_imports(code: &str) -> Vec<String> {
+        let mut imports = Vec::new();
+        for captures in IMPORT_BLOCK_RE.captures_iter(code) {
+            if let Some(block) = captures.get(1) {
+                imports.extend(
+                    QUOTED_RE
+                        .captures_iter(block.as_str())
+                        .map(|quoted| quoted[1].to_string()),
+                );
+            } else if let Some(single) = captures.get(2) {
+                imports.push(single.as_str().to_string());
+            }
+        }
+        imports
+    }
+
+    /// 是否引用了第三方模块(路径的第一段包含域名,如 github.com/...)
+    fn has_third_party_imports(code: &str) -> bool {
+        Self::parse_imports(code).iter().any(|path| {
+            path.split('/')
+                .next()
+                .is_some_and(|host| host.contains('.'))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GoRunner;
+
+    #[test]
+    fn test_prepare_handler_code() {
+        let code = "func Handler(input map[string]any) (any, error) { return input, nil }";
+        let prepared = GoRunner::prepare_handler_code(code);
+        assert!(prepared.starts_with("package main\n"));
+
+        let code = "// comment\npackage handler\n\nfunc Handler(input map[string]any) (any, error) { return nil, nil }";
+        let prepared = GoRunner::prepare_handler_code(code);
+        assert!(prepared.contains("package main\n"));
+        assert!(!prepared.contains("package handler"));
+    }
+
+    #[test]
+    fn test_parse_imports() {
+        let code = r#"
+package main
+
+import "strings"
+import str "strconv"
+
+import (
+    "fmt"
+    m "math"
+    "github.com/google/uuid"
+)
+"#;
+        assert_eq!(
+            GoRunner::parse_imports(code),
+            vec![
+                "strings",
+                "strconv",
+                "fmt",
+                "math",
+                "github.com/google/uuid"
+            ]
+        );
+        assert!(GoRunner::has_third_party_imports(code));
+        assert!(!GoRunner::has_third_party_imports("import \"fmt\""));
+    }
+}
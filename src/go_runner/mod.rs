@@ -0,0 +1,4 @@
+#[allow(clippy::module_inception)]
+mod go_runner;
+
+pub use go_runner::GoRunner;
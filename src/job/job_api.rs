@@ -0,0 +1,166 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::Path,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde_json::{Value, json};
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use tokio_stream::{StreamExt, wrappers::UnboundedReceiverStream};
+
+use crate::job::{JobManager, JobSubmission};
+use crate::model::{ChunkSink, CodeExecutor, CodeScriptExecutionResult, LogSink, RunOptions};
+
+/// 异步任务的REST接口:
+/// - `POST /jobs` 提交任务
+/// - `GET /jobs` 列出任务
+/// - `GET /jobs/{id}` 查询任务状态和到目前为止的日志
+/// - `GET /jobs/{id}/result` 查询任务结果
+/// - `POST /jobs/{id}/cancel` 取消任务
+/// - `POST /run/stream` 同步执行代码,以NDJSON实时返回日志、数据块和最终结果
+pub fn job_router() -> Router {
+    Router::new()
+        .route("/run/stream", post(run_stream))
+        .route("/jobs", post(submit_job).get(list_jobs))
+        .route("/jobs/{id}", get(get_job_status))
+        .route("/jobs/{id}/result", get(get_job_result))
+        .route("/jobs/{id}/cancel", post(cancel_job))
+}
+
+async fn submit_job(Json(submission): Json<JobSubmission>) -> Response {
+    match JobManager::global().and_then(|manager| manager.submit(submission)) {
+        Ok(record) => (
+            StatusCode::ACCEPTED,
+            Json(json!({ "job_id": record.id, "status": record.status })),
+        )
+            .into_response(),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, e.to_string()),
+    }
+}
+
+async fn list_jobs() -> Response {
+    match JobManager::global() {
+        Ok(manager) => {
+            let jobs: Vec<Value> = manager
+                .list()
+                .iter()
+                .map(|record| {
+                    json!({
+                        "job_id": record.id,
+                        "language": record.language,
+                        "status": record.status,
+                        "created_at": record.created_at,
+                        "finished_at": record.finished_at
+                    })
+                })
+                .collect();
+            Json(json!({ "jobs": jobs })).into_response()
+        }
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn get_job_status(Path(id): Path<String>) -> Response {
+    match JobManager::global() {
+        Ok(manager) => match manager.get(&id) {
+            Some(record) => Json(record.status_view()).into_response(),
+            None => job_not_found(&id),
+        },
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn get_job_result(Path(id): Path<String>) -> Response {
+    match JobManager::global() {
+        Ok(manager) => match manager.get(&id) {
+            Some(record) => Json(record.result_view()).into_response(),
+            None => job_not_found(&id),
+        },
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn cancel_job(Path(id): Path<String>) -> Response {
+    match JobManager::global().and_then(|manager| manager.cancel(&id)) {
+        Ok(Some(record)) => Json(record.status_view()).into_response(),
+        Ok(None) => job_not_found(&id),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// 执行代码并以NDJSON(每行一个JSON对象)流式返回:
+/// - `{"type":"log","line":...}` 执行过程中的日志
+/// - `{"type":"chunk","index":...,"data":...}` 生成器handler产生的数据块
+/// - `{"type":"result","success":...,"result":...,"error":...,"logs":[...],"log_entries":[...]}`
+///   最终结果,总是最后一行
+async fn run_stream(Json(submission): Json<JobSubmission>) -> Response {
+    let (sender, receiver) = unbounded_channel::<Value>();
+    tokio::spawn(async move {
+        let result_sender = sender.clone();
+        let log_sender = sender.clone();
+        let index = AtomicUsize::new(0);
+        let options = RunOptions {
+            log_sink: Some(LogSink::new(move |line| {
+                let _ = log_sender.send(json!({ "type": "log", "line": line }));
+            })),
+            chunk_sink: Some(ChunkSink::new(move |chunk| {
+                let index = index.fetch_add(1, Ordering::SeqCst);
+                let _ = sender.send(json!({ "type": "chunk", "index": index, "data": chunk }));
+            })),
+            ..submission.options.clone().into_run_options()
+        };
+        let execution = CodeExecutor::execute_with_options(
+            &submission.code,
+            &submission.language,
+            submission.params,
+            &options,
+        )
+        .await;
+        send_result(&result_sender, execution);
+    });
+    let lines = UnboundedReceiverStream::new(receiver)
+        .map(|event| Ok::<_, std::convert::Infallible>(format!("{event}\n")));
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
+
+/// 发送最终结果事件
+fn send_result(
+    sender: &UnboundedSender<Value>,
+    execution: anyhow::Result<CodeScriptExecutionResult>,
+) {
+    let event = match execution {
+        Ok(result) => json!({
+            "type": "result",
+            "success": result.success,
+            "result": result.result,
+            "error": result.error,
+            "logs": result.logs,
+            "log_entries": result.log_entries
+        }),
+        Err(e) => json!({
+            "type": "result",
+            "success": false,
+            "result": null,
+            "error": format!("{e:#}"),
+            "logs": [],
+            "log_entries": []
+        }),
+    };
+    let _ = sender.send(event);
+}
+
+fn job_not_found(id: &str) -> Response {
+    error_response(StatusCode::NOT_FOUND, format!("任务不存在: {id}"))
+}
+
+fn error_response(status: StatusCode, error: String) -> Response {
+    (status, Json(json!({ "success": false, "error": error }))).into_response()
+}
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+use log::{info, warn};
+use once_cell::sync::OnceCell;
+use serde_json::Value;
+use tokio::sync::Semaphore;
+use tokio::task::AbortHandle;
+
+use crate::job::{JobRecord, JobStatus, JobStore, JobSubmission, now_millis};
+use crate::model::{ChunkSink, CodeExecutor, LogSink, RunOptions};
+use crate::registry::RunnerRegistry;
+
+/// 全局任务管理器,第一次使用时按默认配置创建,也可以通过 [`JobManager::init_global`] 指定配置
+static GLOBAL_JOB_MANAGER: OnceCell<JobManager> = OnceCell::new();
+
+// 生成任务ID的计数器
+static JOB_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+///异步任务管理器配置
+#[derive(Debug, Clone)]
+pub struct JobManagerConfig {
+    //任务记录的存储目录
+    pub store_dir: PathBuf,
+    //同时执行的任务数上限,超过时排队等待
+    pub max_concurrent_jobs: usize,
+    //已结束任务的保留时间,超过后自动清理
+    pub retention: Duration,
+    //单个任务执行中保留的最多日志行数
+    pub max_log_lines: usize,
+}
+
+impl Default for JobManagerConfig {
+    fn default() -> Self {
+        Self {
+            store_dir: PathBuf::from("/tmp/run_code_jobs"),
+            max_concurrent_jobs: 4,
+            retention: Duration::from_secs(24 * 60 * 60),
+            max_log_lines: 10_000,
+        }
+    }
+}
+
+struct JobEntry {
+    record: JobRecord,
+    abort_handle: Option<AbortHandle>,
+}
+
+struct JobManagerInner {
+    config: JobManagerConfig,
+    store: JobStore,
+    jobs: Mutex<HashMap<String, JobEntry>>,
+    semaphore: Arc<Semaphore>,
+}
+
+///进程内的异步任务管理器,任务记录持久化到本地存储,重启后未完成的任务标记为 unknown
+#[derive(Clone)]
+pub struct JobManager {
+    inner: Arc<JobManagerInner>,
+}
+
+impl JobManager {
+    /// 创建任务管理器并加载已持久化的任务
+    pub fn new(config: JobManagerConfig) -> Result<Self> {
+        let store = JobStore::new(&config.store_dir)?;
+
+        let mut jobs = HashMap::new();
+        for mut record in store.load_all()? {
+            if !record.status.is_finished() {
+                record.finish(
+                    JobStatus::Unknown,
+                    Some("服务重启前任务未完成,状态未知".to_string()),
+                );
+                store.save(&record)?;
+            }
+            jobs.insert(
+                record.id.clone(),
+                JobEntry {
+                    record,
+                    abort_handle: None,
+                },
+            );
+        }
+        info!(
+            "任务管理器已启动,存储目录: {},已加载任务数: {}",
+            config.store_dir.display(),
+            jobs.len()
+        );
+
+        let manager = Self {
+            inner: Arc::new(JobManagerInner {
+                semaphore: Arc::new(Semaphore::new(config.max_concurrent_jobs.max(1))),
+                config,
+                store,
+                jobs: Mutex::new(jobs),
+            }),
+        };
+        manager.purge_expired();
+        Ok(manager)
+    }
+
+    /// 使用指定配置初始化全局任务管理器,只能初始化一次
+    pub fn init_global(config: JobManagerConfig) -> Result<&'static JobManager> {
+        let manager = Self::new(config)?;
+        GLOBAL_JOB_MANAGER
+            .set(manager)
+            .map_err(|_| anyhow!("全局任务管理器已初始化"))?;
+        Self::global()
+    }
+
+    /// 获取全局任务管理器,未初始化时使用默认配置创建
+    pub fn global() -> Result<&'static JobManager> {
+        GLOBAL_JOB_MANAGER.get_or_try_init(|| Self::new(JobManagerConfig::default()))
+    }
+
+    /// 提交任务,立即返回任务记录;任务在后台执行
+    pub fn submit(&self, submission: JobSubmission) -> Result<JobRecord> {
+        if RunnerRegistry::global_runner(&submission.language).is_none() {
+            bail!(
+                "未注册的语言: {}, 可选项: {}",
+                submission.language,
+                RunnerRegistry::global_language_ids().join(", ")
+            );
+        }
+        self.purge_expired();
+
+        let record = JobRecord::new(Self::next_job_id(), submission.language.clone());
+        self.inner.store.save(&record)?;
+        let id = record.id.clone();
+        self.inner.lock_jobs().insert(
+            id.clone(),
+            JobEntry {
+                record: record.clone(),
+                abort_handle: None,
+            },
+        );
+        info!("提交任务: {id} [{}]", record.language);
+
+        let handle = tokio::spawn(Self::run_job(self.inner.clone(), id.clone(), submission));
+        if let Some(entry) = self.inner.lock_jobs().get_mut(&id) {
+            entry.abort_handle = Some(handle.abort_handle());
+        }
+        Ok(record)
+    }
+
+    /// 获取任务记录,执行中的任务包含到目前为止的日志
+    pub fn get(&self, id: &str) -> Option<JobRecord> {
+        self.inner
+            .lock_jobs()
+            .get(id)
+            .map(|entry| entry.record.clone())
+    }
+
+    /// 列出所有任务,按提交时间排序
+    pub fn list(&self) -> Vec<JobRecord> {
+        let mut records: Vec<JobRecord> = self
+            .inner
+            .lock_jobs()
+            .values()
+            .map(|entry| entry.record.clone())
+            .collect();
+        records.sort_by_key(|record| record.created_at);
+        records
+    }
+
+    /// 取消任务,任务不存在时返回 None;已结束的任务不受影响
+    pub fn cancel(&self, id: &str) -> Result<Option<JobRecord>> {
+        let record = {
+            let mut jobs = self.inner.lock_jobs();
+            let Some(entry) = jobs.get_mut(id) else {
+                return Ok(None);
+            };
+            if entry.record.status.is_finished() {
+                return Ok(Some(entry.record.clone()));
+            }
+            if let Some(handle) = entry.abort_handle.take() {
+                handle.abort();
+            }
+            entry
+                .record
+                .finish(JobStatus::Cancelled, Some("任务已取消".to_string()));
+            entry.record.clone()
+        };
+        info!("取消任务: {id}");
+        self.inner.store.save(&record)?;
+        Ok(Some(record))
+    }
+
+    /// 清理超过保留时间的已结束任务
+    pub fn purge_expired(&self) {
+        let retention_ms = self.inner.config.retention.as_millis() as u64;
+        let now = now_millis();
+        let expired: Vec<String> = {
+            let mut jobs = self.inner.lock_jobs();
+            let expired: Vec<String> = jobs
+                .values()
+                .filter(|entry| {
+                    entry
+                        .record
+                        .finished_at
+                        .is_some_and(|finished_at| finished_at + retention_ms <= now)
+                })
+                .map(|entry| entry.record.id.clone())
+                .collect();
+            for id in &expired {
+                jobs.remove(id);
+            }
+            expired
+        };
+        for id in expired {
+            info!("清理过期任务: {id}");
+            if let Err(e) = self.inner.store.remove(&id) {
+                warn!("删除过期任务记录失败: {e}");
+            }
+        }
+    }
+
+    /// 在后台执行任务,并发数超过上限时排队等待
+    async fn run_job(inner: Arc<JobManagerInner>, id: String, submission: JobSubmission) {
+        let Ok(_permit) = inner.semaphore.clone().acquire_owned().await else {
+            return;
+        };
+        let started = inner.update(&id, |record| {
+            if record.status != JobStatus::Pending {
+                return false;
+            }
+            record.status = JobStatus::Running;
+            record.started_at = Some(now_millis());
+            true
+        });
+        if !started {
+            return;
+        }
+
+        let sink_inner = inner.clone();
+        let sink_id = id.clone();
+        let chunk_inner = inner.clone();
+        let chunk_id = id.clone();
+        let options = RunOptions {
+            log_sink: Some(LogSink::new(move |line| {
+                sink_inner.append_log(&sink_id, line)
+            })),
+            chunk_sink: Some(ChunkSink::new(move |chunk| {
+                chunk_inner.append_chunk(&chunk_id, chunk)
+            })),
+            ..submission.options.clone().into_run_options()
+        };
+        let execution = CodeExecutor::execute_with_options(
+            &submission.code,
+            &submission.language,
+            submission.params,
+            &options,
+        )
+        .await;
+
+        inner.update(&id, |record| {
+            // 执行期间已被取消
+            if record.status.is_finished() {
+                return false;
+            }
+            match execution {
+                Ok(result) => {
+                    let status = if result.success {
+                        JobStatus::Succeeded
+                    } else {
+                        JobStatus::Failed
+                    };
+                    record.logs = result.logs;
+                    record.log_entries = result.log_entries;
+                    record.result = result.result;
+                    record.finish(status, result.error);
+                }
+                Err(e) => record.finish(JobStatus::Failed, Some(format!("{e:#}"))),
+            }
+            true
+        });
+        info!("任务执行结束: {id}");
+    }
+
+    fn next_job_id() -> String {
+        let seed = format!(
+            "{}-{}-{}",
+            std::process::id(),
+            now_millis(),
+            JOB_ID_COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+        blake3::hash(seed.as_bytes()).to_hex()[..32].to_string()
+    }
+}
+
+impl JobManagerInner {
+    fn lock_jobs(&self) -> std::sync::MutexGuard<'_, HashMap<String, JobEntry>> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 修改任务记录,修改后持久化;返回是否有修改
+    fn update(&self, id: &str, apply: impl FnOnce(&mut JobRecord) -> bool) -> bool {
+        let record = {
+            let mut jobs = self.lock_jobs();
+            let Some(entry) = jobs.get_mut(id) else {
+                return false;
+            };
+            if !apply(&mut entry.record) {
+                return false;
+            }
+            entry.record.clone()
+        };
+        if let Err(e) = self.store.save(&record) {
+            warn!("保存任务记录失败: {e}");
+        }
+        true
+    }
+
+    /// 追加执行中的日志,只保存在内存中,任务结束时随结果一起持久化
+    fn append_log(&self, id: &str, line: &str) {
+        let max_log_lines = self.config.max_log_lines;
+        if let Some(entry) = self
+            .lock_jobs()
+            .get_mut(id)
+            .filter(|entry| entry.record.logs.len() < max_log_lines)
+        {
+            entry.record.logs.push(line.to_string());
+        }
+    }
+
+    /// 记录生成器handler产生的数据块
+    fn append_chunk(&self, id: &str, chunk: &Value) {
+        if let Some(entry) = self.lock_jobs().get_mut(id) {
+            entry.record.chunks.push(chunk.clone());
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::model::{LogEntry, RunOptionsRequest};
+
+///异步任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    //等待执行(超过并发上限时排队)
+    Pending,
+    //执行中
+    Running,
+    //执行成功
+    Succeeded,
+    //执行失败
+    Failed,
+    //已取消
+    Cancelled,
+    //服务重启前未完成,状态未知
+    Unknown,
+}
+
+impl JobStatus {
+    /// 是否已经结束(不会再变化)
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, JobStatus::Pending | JobStatus::Running)
+    }
+}
+
+///提交异步任务的参数
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct JobSubmission {
+    #[schemars(description = "语言标识,可通过 list_languages 工具获取已注册的语言")]
+    pub language: String,
+
+    #[schemars(description = "要执行的代码,提供 files 时可以省略,使用 files 中的入口文件")]
+    #[serde(default)]
+    pub code: String,
+
+    #[schemars(description = "可选的执行参数")]
+    #[serde(default)]
+    pub params: Option<Value>,
+
+    #[serde(flatten)]
+    pub options: RunOptionsRequest,
+}
+
+///异步任务记录,持久化到本地存储
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRecord {
+    //任务ID
+    pub id: String,
+    //语言标识
+    pub language: String,
+    //任务状态
+    pub status: JobStatus,
+    //提交时间(unix毫秒)
+    pub created_at: u64,
+    //开始执行时间(unix毫秒)
+    pub started_at: Option<u64>,
+    //结束时间(unix毫秒)
+    pub finished_at: Option<u64>,
+    //已产生的日志,执行中可以查询到目前为止的日志
+    pub logs: Vec<String>,
+    //结构化的日志记录,任务结束后才有
+    #[serde(default)]
+    pub log_entries: Vec<LogEntry>,
+    //生成器handler已产生的数据块,执行中可以查询到目前为止的数据块
+    #[serde(default)]
+    pub chunks: Vec<Value>,
+    //执行结果
+    pub result: Option<Value>,
+    //如果执行错误的话,错误信息
+    pub error: Option<String>,
+}
+
+impl JobRecord {
+    pub fn new(id: String, language: String) -> Self {
+        Self {
+            id,
+            language,
+            status: JobStatus::Pending,
+            created_at: now_millis(),
+            started_at: None,
+            finished_at: None,
+            logs: vec![],
+            log_entries: vec![],
+            chunks: vec![],
+            result: None,
+            error: None,
+        }
+    }
+
+    /// 任务状态信息,包含到目前为止的日志和数据块
+    pub fn status_view(&self) -> Value {
+        json!({
+            "job_id": self.id,
+            "language": self.language,
+            "status": self.status,
+            "created_at": self.created_at,
+            "started_at": self.started_at,
+            "finished_at": self.finished_at,
+            "logs": self.logs,
+            "chunks": self.chunks
+        })
+    }
+
+    /// 任务结果信息,任务未结束时 finished 为 false
+    pub fn result_view(&self) -> Value {
+        json!({
+            "job_id": self.id,
+            "status": self.status,
+            "finished": self.status.is_finished(),
+            "success": self.status == JobStatus::Succeeded,
+            "result": self.result,
+            "error": self.error,
+            "logs": self.logs,
+            "log_entries": self.log_entries
+        })
+    }
+
+    /// 结束任务
+    pub(crate) fn finish(&mut self, status: JobStatus, error: Option<String>) {
+        self.status = status;
+        self.error = error;
+        self.finished_at = Some(now_millis());
+    }
+}
+
+/// 当前时间(unix毫秒)
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,67 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::warn;
+
+use crate::job::JobRecord;
+
+///异步任务的本地存储,每个任务保存为一个JSON文件: `<dir>/<id>.json`
+#[derive(Debug, Clone)]
+pub struct JobStore {
+    dir: PathBuf,
+}
+
+impl JobStore {
+    /// 创建存储,目录不存在时自动创建
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("无法创建任务存储目录: {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    /// 保存任务记录,先写临时文件再重命名,避免留下不完整的文件
+    pub fn save(&self, record: &JobRecord) -> Result<()> {
+        let path = self.record_path(&record.id);
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, serde_json::to_vec_pretty(record)?)
+            .with_context(|| format!("无法写入任务记录: {}", temp_path.display()))?;
+        fs::rename(&temp_path, &path)
+            .with_context(|| format!("无法保存任务记录: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// 删除任务记录
+    pub fn remove(&self, id: &str) -> Result<()> {
+        let path = self.record_path(id);
+        if path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| format!("无法删除任务记录: {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// 加载所有任务记录,无法解析的文件会被跳过
+    pub fn load_all(&self) -> Result<Vec<JobRecord>> {
+        let mut records = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let record = fs::read(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| Ok(serde_json::from_slice::<JobRecord>(&content)?));
+            match record {
+                Ok(record) => records.push(record),
+                Err(e) => warn!("跳过无法解析的任务记录 {}: {e}", path.display()),
+            }
+        }
+        Ok(records)
+    }
+
+    fn record_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+}
@@ -0,0 +1,10 @@
+mod job_api;
+mod job_manager;
+mod job_model;
+mod job_store;
+
+pub use job_api::job_router;
+pub use job_manager::{JobManager, JobManagerConfig};
+pub(crate) use job_model::now_millis;
+pub use job_model::{JobRecord, JobStatus, JobSubmission};
+pub use job_store::JobStore;
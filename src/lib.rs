@@ -1,17 +1,51 @@
 mod app_error;
 mod cache;
 mod deno_runner;
+mod function;
+mod go_runner;
+mod job;
 mod mcp;
 mod model;
+mod node_runner;
 mod python_runner;
+mod registry;
+mod shell_runner;
+mod signature;
 #[cfg(test)]
 mod tests;
 mod warm_up;
 
+pub use app_error::AppError;
 pub use cache::*;
 pub use deno_runner::*;
-pub use mcp::CodeRunRequest;
+pub use function::{
+    DEFAULT_FUNCTION_STORE_DIR, FunctionDeployment, FunctionRef, FunctionSelector, FunctionStore,
+    FunctionVersion, LATEST_ALIAS, StoredFunction,
+};
+pub use go_runner::*;
+pub use job::{
+    JobManager, JobManagerConfig, JobRecord, JobStatus, JobStore, JobSubmission, job_router,
+};
+pub use mcp::{
+    BatchRunRequest, CodeRunRequest, CodeRunnerService, DescribeFunctionRequest,
+    FunctionDeleteRequest, FunctionInvokeRequest,
+    JobIdRequest,
+    LanguageCodeRunRequest, PipelineRunRequest, TypeCheckRequest,
+};
 pub use model::RunCodeHttpResult;
-pub use model::{CodeExecutor, CodeScriptExecutionResult, LanguageScript};
+pub use model::{
+    BINARY_KEY, BatchExecutionResult, BatchItemResult, BinaryContent, CHUNK_MARKER, CONTENT_REF_KEY,
+    CONTEXT_ENV, ChunkSink, CodeExecutor, CodeScriptExecutionResult, DATAFRAME_ORIENT_ENV,
+    DEADLINE_ENV, DEFAULT_ENTRYPOINTS, DEFAULT_TIMEOUT_SECONDS, DataFrameOrient, ENTRYPOINT_ENV,
+    FunctionIdentity, InvocationContext, JsRuntime, LanguageScript, LogEntry, LogSink, LogStream,
+    PipelineExecutionResult, PipelineStep, PipelineStepResult, ProjectFiles, RunCode, RunOptions,
+    RunOptionsRequest,
+    TypeDiagnostic, USER_CODE_FILE, extract_binaries, parse_env_pair, split_entrypoint,
+    validate_entrypoint,
+};
+pub use node_runner::*;
 pub use python_runner::*;
+pub use registry::RunnerRegistry;
+pub use shell_runner::*;
+pub use signature::{FunctionSignature, infer_signature};
 pub use warm_up::warm_up_all_envs;
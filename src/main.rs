@@ -4,19 +4,15 @@ use log::{error, info};
 use serde_json::Value;
 use std::{fs, path::PathBuf};
 
-mod app_error;
-mod cache;
-mod deno_runner;
-mod mcp;
-mod model;
-mod python_runner;
-
-use crate::cache::CodeFileCache;
-use crate::model::{CodeExecutor, CodeScriptExecutionResult, LanguageScript};
+use run_code_rmcp::{
+    CodeExecutor, CodeFileCache, CodeScriptExecutionResult, DEFAULT_FUNCTION_STORE_DIR,
+    DataFrameOrient, FunctionDeployment, FunctionRef, FunctionStore, JsRuntime, LATEST_ALIAS,
+    LanguageScript, RunOptions, RunnerRegistry, StoredFunction, parse_env_pair,
+};
 
 #[derive(Parser)]
 #[command(name = "run_code_rmcp")]
-#[command(about = "Execute JavaScript and Python code using MCP SDK", long_about = None)]
+#[command(about = "Execute JavaScript, TypeScript, Python, Shell and Go code using MCP SDK", long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
@@ -32,6 +28,10 @@ struct Cli {
     /// Clear cache before execution
     #[arg(short = 'c', long)]
     clear_cache: bool,
+
+    /// Directory where deployed functions are stored
+    #[arg(long, global = true, default_value = DEFAULT_FUNCTION_STORE_DIR)]
+    function_store_dir: PathBuf,
 }
 
 #[derive(Subcommand)]
@@ -45,14 +45,161 @@ enum Commands {
     /// Execute Python code
     Python(CodeArgs),
 
+    /// Execute Shell (bash) script
+    Shell(CodeArgs),
+
+    /// Execute Go code
+    Go(CodeArgs),
+
+    /// Execute code with any registered language runner
+    Run {
+        /// Language id of a registered runner (see the `languages` subcommand)
+        #[arg(short, long)]
+        language: String,
+
+        #[command(flatten)]
+        args: CodeArgs,
+    },
+
+    /// List registered language runners
+    Languages,
+
+    /// Manage and invoke stored functions
+    #[command(name = "fn", subcommand)]
+    Function(FnCommands),
+
     /// Clear cache files
     ClearCache {
-        /// Language to clear cache for (js, ts, python, or all)
+        /// Language to clear cache for (js, ts, python, shell, go, or all)
         #[arg(short, long)]
         language: String,
     },
 }
 
+#[derive(Subcommand, Clone)]
+enum FnCommands {
+    /// Deploy a function as a new immutable version and point `latest` at it
+    Deploy {
+        /// Function name
+        name: String,
+
+        /// Language id of a registered runner
+        #[arg(short, long)]
+        language: String,
+
+        /// Path to the code file
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+
+        /// Code content as string
+        #[arg(short, long)]
+        code: Option<String>,
+
+        /// Declared dependency, installed on deploy (repeatable)
+        #[arg(short, long = "dependency")]
+        dependencies: Vec<String>,
+
+        /// Default timeout in seconds
+        #[arg(short, long)]
+        timeout: Option<u64>,
+
+        /// Function description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// JSON Schema of the function parameters, validated before each invocation
+        /// and used as the input schema of its MCP tool
+        #[arg(long, alias = "params-schema")]
+        input_schema: Option<String>,
+
+        /// JSON Schema of the function result, validated after each invocation
+        #[arg(long)]
+        output_schema: Option<String>,
+
+        /// Entrypoint function name, defaults to `handler` then `main`
+        #[arg(short, long)]
+        entrypoint: Option<String>,
+
+        /// Declared environment variable as KEY=VALUE, set on each invocation
+        /// and readable through the handler context (repeatable)
+        #[arg(long = "env", value_parser = parse_env_pair)]
+        env: Vec<(String, String)>,
+    },
+
+    /// List deployed functions
+    List,
+
+    /// Show a deployed function version (`name`, `name@version` or `name:alias`)
+    Show {
+        /// Function reference
+        reference: String,
+    },
+
+    /// List all versions of a function with their aliases and invocation counts
+    Versions {
+        /// Function name
+        name: String,
+    },
+
+    /// Point an alias (e.g. `stable`) at a version
+    Alias {
+        /// Function name
+        name: String,
+
+        /// Alias name
+        alias: String,
+
+        /// Version number
+        version: u64,
+    },
+
+    /// Roll an alias back to an earlier version
+    Rollback {
+        /// Function name
+        name: String,
+
+        /// Alias to roll back
+        #[arg(short, long, default_value = LATEST_ALIAS)]
+        alias: String,
+
+        /// Target version, defaults to the version before the alias' current version
+        #[arg(short, long)]
+        version: Option<u64>,
+    },
+
+    /// Delete a deployed function
+    Delete {
+        /// Function name
+        name: String,
+    },
+
+    /// Invoke a deployed function (`name`, `name@version` or `name:alias`)
+    Invoke {
+        /// Function reference
+        reference: String,
+
+        /// Parameters to pass to the function (JSON format)
+        #[arg(short, long)]
+        params: Option<String>,
+
+        /// Timeout in seconds, overrides the function's default timeout
+        #[arg(short, long)]
+        timeout: Option<u64>,
+
+        /// JavaScript runtime (deno or node), only used for JavaScript
+        #[arg(long, value_enum)]
+        runtime: Option<JsRuntime>,
+
+        /// Python version (e.g. 3.11), only used for Python
+        #[arg(long)]
+        python_version: Option<String>,
+
+        /// JSON format of pandas DataFrame results, only used for Python
+        #[arg(long, value_enum)]
+        dataframe_orient: Option<DataFrameOrient>,
+    },
+}
+
 #[derive(Args)]
 struct CodeArgs {
     /// Path to the code file
@@ -66,6 +213,49 @@ struct CodeArgs {
     /// Parameters to pass to the script (JSON format)
     #[arg(short, long)]
     params: Option<String>,
+
+    /// JavaScript runtime (deno or node), only used for JavaScript
+    #[arg(long, value_enum)]
+    runtime: Option<JsRuntime>,
+    /// Python version (e.g. 3.11), only used for Python; must be installed by uv
+    #[arg(long)]
+    python_version: Option<String>,
+
+    /// JSON format of pandas DataFrame results, only used for Python
+    #[arg(long, value_enum)]
+    dataframe_orient: Option<DataFrameOrient>,
+
+    /// JSON Schema the parameters must match, checked before the code runs
+    #[arg(long)]
+    input_schema: Option<String>,
+
+    /// JSON Schema the handler result must match
+    #[arg(long)]
+    output_schema: Option<String>,
+
+    /// Entrypoint function name, defaults to `handler` then `main`
+    #[arg(short, long)]
+    entrypoint: Option<String>,
+
+    /// Type-check the code before running it, only used for TypeScript
+    #[arg(long)]
+    type_check: bool,
+}
+
+impl CodeArgs {
+    /// 根据命令行参数构建执行选项
+    fn run_options(&self) -> Result<RunOptions> {
+        Ok(RunOptions {
+            runtime: self.runtime,
+            python_version: self.python_version.clone(),
+            dataframe_orient: self.dataframe_orient,
+            input_schema: parse_params(&self.input_schema)?,
+            output_schema: parse_params(&self.output_schema)?,
+            entrypoint: self.entrypoint.clone(),
+            type_check: self.type_check,
+            ..Default::default()
+        })
+    }
 }
 
 #[tokio::main]
@@ -82,49 +272,46 @@ async fn main() -> Result<()> {
     builder.init();
 
     if let Commands::ClearCache { language } = &cli.command {
-        match language.to_lowercase().as_str() {
-            "js" => {
-                CodeFileCache::clear_cache_by_language(&LanguageScript::Js).await?;
-                info!("已清除 JavaScript 缓存");
-            }
-            "ts" => {
-                CodeFileCache::clear_cache_by_language(&LanguageScript::Ts).await?;
-                info!("已清除 TypeScript 缓存");
-            }
-            "python" => {
-                CodeFileCache::clear_cache_by_language(&LanguageScript::Python).await?;
-                info!("已清除 Python 缓存");
-            }
-            "all" => {
-                CodeFileCache::clear_all_cache().await?;
-                info!("已清除所有缓存");
-            }
-            _ => {
-                info!("无效的语言类型，可选项: js, ts, python, all");
-                return Ok(());
-            }
+        if language.eq_ignore_ascii_case("all") {
+            CodeFileCache::clear_all_cache().await?;
+            info!("已清除所有缓存");
+        } else if let Some(language) = LanguageScript::from_id(language) {
+            CodeFileCache::clear_cache_by_language(&language).await?;
+            info!("已清除 {language:?} 缓存");
+        } else {
+            info!("无效的语言类型，可选项: js, ts, python, shell, go, all");
         }
         return Ok(());
     }
 
-    // 解析传递给脚本的参数
-    let params = match &cli.command {
-        Commands::Js(args) => parse_params(&args.params)?,
-        Commands::Ts(args) => parse_params(&args.params)?,
-        Commands::Python(args) => parse_params(&args.params)?,
-        _ => None,
-    };
+    if let Commands::Languages = &cli.command {
+        for (language, description) in RunnerRegistry::global_describe() {
+            println!("{language}\t{description}");
+        }
+        return Ok(());
+    }
+
+    if let Commands::Function(command) = &cli.command {
+        let store = FunctionStore::new(&cli.function_store_dir)?;
+        return run_function_command(&store, command.clone()).await;
+    }
 
-    // 从参数获取代码
-    let (code, language) = match &cli.command {
-        Commands::Js(args) => (get_code(args)?, LanguageScript::Js),
-        Commands::Ts(args) => (get_code(args)?, LanguageScript::Ts),
-        Commands::Python(args) => (get_code(args)?, LanguageScript::Python),
+    // 从参数获取代码、参数和语言标识
+    let (args, language_id) = match &cli.command {
+        Commands::Js(args) => (args, "js"),
+        Commands::Ts(args) => (args, "ts"),
+        Commands::Python(args) => (args, "python"),
+        Commands::Shell(args) => (args, "shell"),
+        Commands::Go(args) => (args, "go"),
+        Commands::Run { language, args } => (args, language.as_str()),
         _ => unreachable!(),
     };
+    let code = get_code(args)?;
+    let params = parse_params(&args.params)?;
+    let options = args.run_options()?;
 
     // 如果指定了清除缓存选项，则清除对应语言的缓存
-    if cli.clear_cache {
+    if let Some(language) = LanguageScript::from_id(language_id).filter(|_| cli.clear_cache) {
         CodeFileCache::clear_cache_by_language(&language).await?;
         info!("已清除 {language:?} 缓存");
     }
@@ -132,10 +319,10 @@ async fn main() -> Result<()> {
     // 执行代码
     let result = if cli.use_mcp {
         // 使用MCP SDK集成
-        CodeExecutor::execute_with_params_compat(&code, language, params).await?
+        CodeExecutor::execute_with_options(&code, language_id, params, &options).await?
     } else {
         // 直接执行
-        CodeExecutor::execute_with_params_compat(&code, language, params).await?
+        CodeExecutor::execute_with_options(&code, language_id, params, &options).await?
     };
 
     // 打印结果
@@ -144,10 +331,132 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// 执行 `fn` 子命令
+async fn run_function_command(store: &FunctionStore, command: FnCommands) -> Result<()> {
+    match command {
+        FnCommands::Deploy {
+            name,
+            language,
+            file,
+            code,
+            dependencies,
+            timeout,
+            description,
+            input_schema,
+            output_schema,
+            entrypoint,
+            env,
+        } => {
+            let code = read_code(file.as_ref(), code.as_ref())?;
+            let input_schema = parse_params(&input_schema)?;
+            let output_schema = parse_params(&output_schema)?;
+            let function = store
+                .deploy(FunctionDeployment {
+                    name,
+                    language,
+                    code,
+                    dependencies,
+                    timeout_seconds: timeout,
+                    description,
+                    input_schema,
+                    output_schema,
+                    entrypoint,
+                    env: env.into_iter().collect(),
+                })
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&function.summary())?);
+        }
+        FnCommands::List => {
+            for function in store.list()? {
+                let Some(latest) = function.latest() else {
+                    continue;
+                };
+                println!(
+                    "{}\t@{}\t{}\t{}",
+                    function.name,
+                    latest.version,
+                    latest.language,
+                    latest.description.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        FnCommands::Show { reference } => {
+            let reference = FunctionRef::parse(&reference)?;
+            let function = get_function(store, &reference.name)?;
+            let version = function.resolve(&reference.selector)?;
+            println!("{}", serde_json::to_string_pretty(version)?);
+        }
+        FnCommands::Versions { name } => {
+            let function = get_function(store, &name)?;
+            for version in &function.versions {
+                println!(
+                    "@{}\t{}\t{}\tinvocations: {}\t{}",
+                    version.version,
+                    version.language,
+                    version.content_hash,
+                    version.invocation_count,
+                    function.aliases_of(version.version).join(",")
+                );
+            }
+        }
+        FnCommands::Alias {
+            name,
+            alias,
+            version,
+        } => {
+            store.set_alias(&name, &alias, version)?;
+            info!("已设置别名 {name}:{alias} -> {version}");
+        }
+        FnCommands::Rollback {
+            name,
+            alias,
+            version,
+        } => {
+            let function = store.rollback(&name, Some(&alias), version)?;
+            println!("{}", serde_json::to_string_pretty(&function.summary())?);
+        }
+        FnCommands::Delete { name } => {
+            if !store.delete(&name)? {
+                anyhow::bail!("Function not found: {name}");
+            }
+            info!("已删除函数: {name}");
+        }
+        FnCommands::Invoke {
+            reference,
+            params,
+            timeout,
+            runtime,
+            python_version,
+            dataframe_orient,
+        } => {
+            let params = parse_params(&params)?;
+            let options = RunOptions {
+                timeout_seconds: timeout,
+                runtime,
+                python_version,
+                dataframe_orient,
+                ..Default::default()
+            };
+            print_result(store.invoke(&reference, params, &options).await?);
+        }
+    }
+    Ok(())
+}
+
+fn get_function(store: &FunctionStore, name: &str) -> Result<StoredFunction> {
+    store
+        .get(name)?
+        .with_context(|| format!("Function not found: {name}"))
+}
+
 fn get_code(args: &CodeArgs) -> Result<String> {
-    if let Some(file) = &args.file {
+    read_code(args.file.as_ref(), args.code.as_ref())
+}
+
+fn read_code(file: Option<&PathBuf>, code: Option<&String>) -> Result<String> {
+    if let Some(file) = file {
         fs::read_to_string(file).context("Failed to read code file")
-    } else if let Some(code) = &args.code {
+    } else if let Some(code) = code {
         Ok(code.clone())
     } else {
         anyhow::bail!("Either file or code must be provided")
@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{info, warn};
+use rmcp::{
+    Peer, RoleServer,
+    model::{JsonObject, Tool},
+};
+use serde_json::{Value, json};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::function::{FunctionStore, StoredFunction};
+
+// 检查其他进程(如命令行)修改函数的间隔
+const FUNCTION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 为每个已部署的函数生成一个MCP工具,与静态工具重名的函数会被跳过
+pub(crate) fn function_tools(static_tool_names: &HashSet<String>) -> Vec<Tool> {
+    let functions = match FunctionStore::global().and_then(|store| store.list()) {
+        Ok(functions) => functions,
+        Err(e) => {
+            warn!("读取已部署函数失败: {e}");
+            return vec![];
+        }
+    };
+    functions
+        .iter()
+        .filter(|function| {
+            let conflict = static_tool_names.contains(&function.name);
+            if conflict {
+                warn!(
+                    "函数 {} 与内置工具重名,不会作为单独的工具提供",
+                    function.name
+                );
+            }
+            !conflict
+        })
+        .filter_map(function_tool)
+        .collect()
+}
+
+/// 根据函数 `latest` 版本的描述和参数JSON Schema生成MCP工具,
+/// 未声明时使用从handler签名推断的结构和描述
+fn function_tool(function: &StoredFunction) -> Option<Tool> {
+    let latest = function.latest()?;
+    let description = latest
+        .described_description()
+        .unwrap_or_else(|| format!("调用已部署的{}函数 {}", latest.language, function.name));
+    let input_schema: JsonObject = match latest.described_input_schema() {
+        Some(Value::Object(mut schema)) => {
+            schema
+                .entry("type")
+                .or_insert_with(|| Value::String("object".to_string()));
+            schema
+        }
+        _ => json!({ "type": "object", "additionalProperties": true })
+            .as_object()
+            .cloned()
+            .unwrap_or_default(),
+    };
+    Some(Tool {
+        name: function.name.clone().into(),
+        description: Some(format!("{description} (版本 {})", latest.version).into()),
+        input_schema: Arc::new(input_schema),
+        output_schema: None,
+        annotations: None,
+    })
+}
+
+/// 监听函数变更,发生变化时通知客户端工具列表已改变,客户端断开后结束
+pub(crate) fn watch_function_changes(peer: Peer<RoleServer>) {
+    let store = match FunctionStore::global() {
+        Ok(store) => store,
+        Err(e) => {
+            warn!("无法监听函数变更: {e}");
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        let mut changes = store.subscribe();
+        let mut interval = tokio::time::interval(FUNCTION_POLL_INTERVAL);
+        let mut revision = store.revision().ok();
+        loop {
+            tokio::select! {
+                change = changes.recv() => {
+                    if let Err(RecvError::Closed) = change {
+                        break;
+                    }
+                    // 当前进程内的变更,同步记录的版本,避免轮询时重复通知
+                    revision = store.revision().ok();
+                }
+                _ = interval.tick() => {
+                    let current = store.revision().ok();
+                    if current == revision {
+                        continue;
+                    }
+                    revision = current;
+                }
+            }
+            info!("函数列表已变化,通知客户端刷新工具列表");
+            if let Err(e) = peer.notify_tool_list_changed().await {
+                info!("客户端已断开,停止监听函数变更: {e}");
+                break;
+            }
+        }
+    });
+}
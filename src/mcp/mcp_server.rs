@@ -1,165 +1,699 @@
 use anyhow::Result;
+use std::collections::{BTreeMap, HashSet};
+
 use rmcp::{
-    ErrorData as McpError, ServerHandler,
-    handler::server::tool::Parameters,
+    ErrorData as McpError, RoleServer, ServerHandler,
+    handler::server::tool::{Parameters, ToolCallContext},
     model::{
-        CallToolResult, Content, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo,
+        CallToolRequestParam, CallToolResult, Content, Implementation, ListToolsResult,
+        PaginatedRequestParam, ProtocolVersion, ResourceContents, ServerCapabilities, ServerInfo,
     },
-    tool, tool_handler, tool_router,
+    service::{NotificationContext, RequestContext},
+    tool, tool_router,
 };
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::model::{CodeExecutor, LanguageScript};
+use crate::app_error::AppError;
+use crate::deno_runner::check_typescript;
+use crate::function::{FunctionDeployment, FunctionStore};
+use crate::mcp::function_tools::{function_tools, watch_function_changes};
+use crate::mcp::progress::run_with_progress;
+use crate::job::{JobManager, JobSubmission};
+use crate::model::{
+    BinaryContent, CodeExecutor, CodeScriptExecutionResult, LanguageScript, PipelineStep,
+    ProjectFiles, RunOptions, RunOptionsRequest, extract_binaries,
+};
+use crate::registry::RunnerRegistry;
+use crate::signature::infer_signature;
 
 /// 代码执行请求参数
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CodeRunRequest {
-    #[schemars(description = "要执行的代码")]
+    #[schemars(description = "要执行的代码,提供 files 时可以省略,使用 files 中的入口文件")]
+    #[serde(default)]
+    pub code: String,
+
+    #[schemars(description = "可选的执行参数")]
+    pub params: Option<serde_json::Value>,
+
+    #[serde(flatten)]
+    pub options: RunOptionsRequest,
+}
+
+/// 指定语言的代码执行请求参数,语言可以是任意已注册到 [`RunnerRegistry`] 的语言
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct LanguageCodeRunRequest {
+    #[schemars(description = "语言标识,可通过 list_languages 工具获取已注册的语言")]
+    pub language: String,
+
+    #[schemars(description = "要执行的代码,提供 files 时可以省略,使用 files 中的入口文件")]
+    #[serde(default)]
     pub code: String,
 
     #[schemars(description = "可选的执行参数")]
     pub params: Option<serde_json::Value>,
+
+    #[serde(flatten)]
+    pub options: RunOptionsRequest,
+}
+
+/// 批量执行请求参数,同一份代码使用多组参数执行
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct BatchRunRequest {
+    #[schemars(description = "语言标识,可通过 list_languages 工具获取已注册的语言")]
+    pub language: String,
+
+    #[schemars(description = "要执行的代码,提供 files 时可以省略,使用 files 中的入口文件")]
+    #[serde(default)]
+    pub code: String,
+
+    #[schemars(description = "参数数组,每个元素作为一次执行的参数")]
+    pub params_list: Vec<serde_json::Value>,
+
+    #[schemars(description = "可选的并发数,默认4,最大32")]
+    pub concurrency: Option<usize>,
+
+    #[serde(flatten)]
+    pub options: RunOptionsRequest,
+}
+
+/// 流水线执行请求参数,按顺序执行多个步骤,每个步骤的结果作为下一个步骤的参数
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct PipelineRunRequest {
+    #[schemars(description = "按顺序执行的步骤列表,最多20个")]
+    pub steps: Vec<PipelineStep>,
+
+    #[schemars(description = "可选的第一个步骤的执行参数")]
+    pub params: Option<serde_json::Value>,
+}
+
+/// 按名称调用已部署函数的请求参数,执行选项覆盖部署时设置的同名选项
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct FunctionInvokeRequest {
+    #[schemars(
+        description = "函数名称,可通过 list_functions 工具获取已部署的函数;可以用 name@版本号 调用指定版本,或 name:别名 调用别名(如 stable)指向的版本,默认调用 latest"
+    )]
+    pub name: String,
+
+    #[schemars(description = "可选的执行参数")]
+    pub params: Option<serde_json::Value>,
+
+    #[serde(flatten)]
+    pub options: RunOptionsRequest,
+}
+
+/// 删除已部署函数的请求参数
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct FunctionDeleteRequest {
+    #[schemars(description = "要删除的函数名称,会删除该函数的所有版本")]
+    pub name: String,
+}
+
+/// 描述函数签名的请求参数,指定已部署的函数名称或直接提供代码
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DescribeFunctionRequest {
+    #[schemars(
+        description = "已部署的函数名称,支持 name@版本号 和 name:别名;指定后忽略 language 和 code"
+    )]
+    pub name: Option<String>,
+
+    #[schemars(description = "未指定 name 时必填,语言标识")]
+    pub language: Option<String>,
+
+    #[schemars(description = "未指定 name 时必填,要分析的代码")]
+    pub code: Option<String>,
+
+    #[schemars(description = "未指定 name 时可选,要分析的入口函数名,未指定时依次查找 handler、main")]
+    pub entrypoint: Option<String>,
+}
+
+/// TypeScript 类型检查请求参数
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct TypeCheckRequest {
+    #[schemars(
+        description = "要检查的TypeScript代码,提供 files 时可以省略,使用 files 中的入口文件"
+    )]
+    #[serde(default)]
+    pub code: String,
+
+    #[schemars(
+        description = "可选的多文件项目: 相对路径 → 文件内容,需要同时指定 entrypoint;检查入口文件及其导入的文件"
+    )]
+    #[serde(default)]
+    pub files: Option<BTreeMap<String, String>>,
+
+    #[schemars(description = "多文件项目的入口文件,如 src/index.ts")]
+    #[serde(default)]
+    pub entrypoint: Option<String>,
+
+    #[schemars(description = "可选的超时时间(秒),包括下载远程导入的时间")]
+    pub timeout_seconds: Option<u64>,
+}
+
+/// 异步任务查询参数
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct JobIdRequest {
+    #[schemars(description = "submit_job 返回的任务ID")]
+    pub job_id: String,
 }
 
 /// 代码执行工具服务
 #[derive(Debug, Clone, Default)]
 pub struct CodeRunnerService;
 
+impl CodeRunnerService {
+    /// 将代码执行结果转换为MCP工具调用结果
+    fn to_call_tool_result(
+        execution: Result<CodeScriptExecutionResult>,
+    ) -> Result<CallToolResult, McpError> {
+        let content = match execution {
+            Ok(mut result) if result.success => {
+                // 二进制数据作为单独的内容返回,图片为 image,其他为内嵌资源
+                let binaries = result
+                    .result
+                    .as_mut()
+                    .map(|value| extract_binaries(value, 1))
+                    .unwrap_or_default();
+                let mut contents = vec![Content::json(json!({
+                    "result": result.result,
+                    "logs": result.logs,
+                    "log_entries": result.log_entries,
+                    "success": true
+                }))?];
+                contents.extend(
+                    binaries
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, binary)| Self::binary_content(binary, index + 1)),
+                );
+                return Ok(CallToolResult::success(contents));
+            }
+            Ok(result) => Content::json(json!({
+                "success": false,
+                "error": result.error,
+                "logs": result.logs,
+                "log_entries": result.log_entries
+            }))?,
+            Err(err) => match err.downcast_ref::<AppError>() {
+                // 类型错误同时返回结构化的诊断信息
+                Some(AppError::TypeCheckError(diagnostics)) => Content::json(json!({
+                    "success": false,
+                    "error": err.to_string(),
+                    "diagnostics": diagnostics,
+                    "logs": [],
+                    "log_entries": []
+                }))?,
+                _ => Content::json(json!({
+                    "success": false,
+                    "error": err.to_string(),
+                    "logs": [],
+                    "log_entries": []
+                }))?,
+            },
+        };
+        Ok(CallToolResult::success(vec![content]))
+    }
+
+    /// 二进制数据转换为工具结果内容,`index` 为其在内容列表中的位置
+    fn binary_content(binary: BinaryContent, index: usize) -> Content {
+        if binary.is_image() {
+            return Content::image(binary.data, binary.mime);
+        }
+        Content::resource(ResourceContents::BlobResourceContents {
+            uri: format!("run-code://result/{index}"),
+            mime_type: Some(binary.mime),
+            blob: binary.data,
+        })
+    }
+
+    /// 获取全局任务管理器
+    fn job_manager() -> Result<&'static JobManager, McpError> {
+        JobManager::global().map_err(|e| McpError::internal_error(e.to_string(), None))
+    }
+
+    /// 任务不存在时的工具调用结果
+    fn job_not_found(job_id: &str) -> Result<CallToolResult, McpError> {
+        Ok(CallToolResult::success(vec![Content::json(json!({
+            "success": false,
+            "error": format!("任务不存在: {job_id}")
+        }))?]))
+    }
+}
+
 #[tool_router]
 impl CodeRunnerService {
-    #[tool(description = "执行JavaScript代码并返回结果")]
+    #[tool(description = "执行JavaScript代码并返回结果,可通过runtime选择deno或node运行时")]
     async fn run_javascript(
         &self,
         request: Parameters<CodeRunRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         let request = request.0;
-        match CodeExecutor::execute_with_params_compat(
-            &request.code,
-            LanguageScript::Js,
-            request.params,
-        )
-        .await
-        {
-            Ok(result) => {
-                if result.success {
-                    let content = Content::json(json!({
-                        "result": result.result,
-                        "logs": result.logs,
-                        "success": true
-                    }))?;
-                    Ok(CallToolResult::success(vec![content]))
-                } else {
-                    let content = Content::json(json!({
-                        "success": false,
-                        "error": result.error,
-                        "logs": result.logs
-                    }))?;
-                    Ok(CallToolResult::success(vec![content]))
-                }
-            }
-            Err(err) => {
-                let content = Content::json(json!({
-                    "success": false,
-                    "error": err.to_string(),
-                    "logs": []
-                }))?;
-                Ok(CallToolResult::success(vec![content]))
-            }
-        }
+        let options = request.options.into_run_options();
+        let execution = run_with_progress(&context, options, |options| async move {
+            CodeExecutor::execute_with_options(
+                &request.code,
+                LanguageScript::Js.id(),
+                request.params,
+                &options,
+            )
+            .await
+        })
+        .await;
+        Self::to_call_tool_result(execution)
     }
 
     #[tool(description = "执行TypeScript代码并返回结果")]
     async fn run_typescript(
         &self,
         request: Parameters<CodeRunRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         let request = request.0;
-        match CodeExecutor::execute_with_params_compat(
-            &request.code,
-            LanguageScript::Ts,
-            request.params,
-        )
-        .await
-        {
-            Ok(result) => {
-                if result.success {
-                    let content = Content::json(json!({
-                        "result": result.result,
-                        "logs": result.logs,
-                        "success": true
-                    }))?;
-                    Ok(CallToolResult::success(vec![content]))
-                } else {
-                    let content = Content::json(json!({
-                        "success": false,
-                        "error": result.error,
-                        "logs": result.logs
-                    }))?;
-                    Ok(CallToolResult::success(vec![content]))
-                }
-            }
-            Err(err) => {
-                let content = Content::json(json!({
-                    "success": false,
-                    "error": err.to_string(),
-                    "logs": []
-                }))?;
-                Ok(CallToolResult::success(vec![content]))
-            }
-        }
+        let options = request.options.into_run_options();
+        let execution = run_with_progress(&context, options, |options| async move {
+            CodeExecutor::execute_with_options(
+                &request.code,
+                LanguageScript::Ts.id(),
+                request.params,
+                &options,
+            )
+            .await
+        })
+        .await;
+        Self::to_call_tool_result(execution)
     }
 
-    #[rmcp::tool(description = "执行Python代码并返回结果")]
+    #[rmcp::tool(description = "执行Python代码并返回结果,可通过python_version指定Python版本")]
     async fn run_python(
         &self,
         request: Parameters<CodeRunRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         let request = request.0;
-        match CodeExecutor::execute_with_params_compat(
+        let options = request.options.into_run_options();
+        let execution = run_with_progress(&context, options, |options| async move {
+            CodeExecutor::execute_with_options(
+                &request.code,
+                LanguageScript::Python.id(),
+                request.params,
+                &options,
+            )
+            .await
+        })
+        .await;
+        Self::to_call_tool_result(execution)
+    }
+
+    #[tool(
+        description = "执行Shell(bash)脚本并返回结果,脚本需定义handler函数,参数通过INPUT_JSON和PARAM_*环境变量传入,结果通过set_result写入"
+    )]
+    async fn run_shell(
+        &self,
+        request: Parameters<CodeRunRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let request = request.0;
+        let options = request.options.into_run_options();
+        let execution = run_with_progress(&context, options, |options| async move {
+            CodeExecutor::execute_with_options(
+                &request.code,
+                LanguageScript::Shell.id(),
+                request.params,
+                &options,
+            )
+            .await
+        })
+        .await;
+        Self::to_call_tool_result(execution)
+    }
+
+    #[tool(
+        description = "编译并执行Go代码,代码需定义 func Handler(input map[string]any) (any, error)"
+    )]
+    async fn run_go(
+        &self,
+        request: Parameters<CodeRunRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let request = request.0;
+        let options = request.options.into_run_options();
+        let execution = run_with_progress(&context, options, |options| async move {
+            CodeExecutor::execute_with_options(
+                &request.code,
+                LanguageScript::Go.id(),
+                request.params,
+                &options,
+            )
+            .await
+        })
+        .await;
+        Self::to_call_tool_result(execution)
+    }
+
+    #[tool(description = "使用指定语言执行代码并返回结果,支持所有已注册的语言")]
+    async fn run_code(
+        &self,
+        request: Parameters<LanguageCodeRunRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let request = request.0;
+        let options = request.options.into_run_options();
+        let execution = run_with_progress(&context, options, |options| async move {
+            CodeExecutor::execute_with_options(
+                &request.code,
+                &request.language,
+                request.params,
+                &options,
+            )
+            .await
+        })
+        .await;
+        Self::to_call_tool_result(execution)
+    }
+
+    #[tool(
+        description = "使用多组参数批量执行同一份代码,按输入顺序返回每组参数的结果、日志和错误,以及成功/失败统计"
+    )]
+    async fn run_batch(
+        &self,
+        request: Parameters<BatchRunRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let request = request.0;
+        let options = request.options.into_run_options();
+        let content = match CodeExecutor::execute_batch(
             &request.code,
-            LanguageScript::Python,
-            request.params,
+            &request.language,
+            request.params_list,
+            &options,
+            request.concurrency,
         )
         .await
         {
-            Ok(result) => {
-                if result.success {
-                    let content = Content::json(json!({
-                        "result": result.result,
-                        "logs": result.logs,
-                        "success": true
-                    }))?;
-                    Ok(CallToolResult::success(vec![content]))
-                } else {
-                    let content = Content::json(json!({
-                        "success": false,
-                        "error": result.error,
-                        "logs": result.logs
-                    }))?;
-                    Ok(CallToolResult::success(vec![content]))
-                }
-            }
-            Err(err) => {
-                let content = Content::json(json!({
+            Ok(batch_result) => Content::json(json!({
+                "success": batch_result.failed == 0,
+                "total": batch_result.total,
+                "succeeded": batch_result.succeeded,
+                "failed": batch_result.failed,
+                "items": batch_result.items
+            }))?,
+            Err(err) => Content::json(json!({
+                "success": false,
+                "error": err.to_string(),
+                "items": []
+            }))?,
+        };
+        Ok(CallToolResult::success(vec![content]))
+    }
+
+    #[tool(
+        description = "按顺序执行多语言流水线,每个步骤的result作为下一个步骤的参数,返回每个步骤的结果、日志、耗时和第一个失败的步骤"
+    )]
+    async fn run_pipeline(
+        &self,
+        request: Parameters<PipelineRunRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let request = request.0;
+        let content =
+            match CodeExecutor::execute_pipeline(&request.steps, request.params).await {
+                Ok(pipeline_result) => Content::json(pipeline_result)?,
+                Err(err) => Content::json(json!({
                     "success": false,
                     "error": err.to_string(),
-                    "logs": []
-                }))?;
-                Ok(CallToolResult::success(vec![content]))
-            }
+                    "steps": []
+                }))?,
+            };
+        Ok(CallToolResult::success(vec![content]))
+    }
+
+    #[tool(
+        description = "提交异步任务并立即返回任务ID,适合长时间运行的代码;通过 get_job_status 查询状态和日志,通过 get_job_result 获取结果"
+    )]
+    async fn submit_job(
+        &self,
+        request: Parameters<JobSubmission>,
+    ) -> Result<CallToolResult, McpError> {
+        let content = match Self::job_manager()?.submit(request.0) {
+            Ok(record) => Content::json(json!({
+                "success": true,
+                "job_id": record.id,
+                "status": record.status
+            }))?,
+            Err(err) => Content::json(json!({
+                "success": false,
+                "error": err.to_string()
+            }))?,
+        };
+        Ok(CallToolResult::success(vec![content]))
+    }
+
+    #[tool(description = "查询异步任务的状态,以及到目前为止的日志")]
+    async fn get_job_status(
+        &self,
+        request: Parameters<JobIdRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let job_id = request.0.job_id;
+        match Self::job_manager()?.get(&job_id) {
+            Some(record) => Ok(CallToolResult::success(vec![Content::json(
+                record.status_view(),
+            )?])),
+            None => Self::job_not_found(&job_id),
+        }
+    }
+
+    #[tool(description = "获取异步任务的执行结果,任务未结束时 finished 为 false")]
+    async fn get_job_result(
+        &self,
+        request: Parameters<JobIdRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let job_id = request.0.job_id;
+        match Self::job_manager()?.get(&job_id) {
+            Some(record) => Ok(CallToolResult::success(vec![Content::json(
+                record.result_view(),
+            )?])),
+            None => Self::job_not_found(&job_id),
         }
     }
+
+    #[tool(description = "取消等待中或执行中的异步任务,已结束的任务不受影响")]
+    async fn cancel_job(
+        &self,
+        request: Parameters<JobIdRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let job_id = request.0.job_id;
+        let content = match Self::job_manager()?.cancel(&job_id) {
+            Ok(Some(record)) => Content::json(record.status_view())?,
+            Ok(None) => return Self::job_not_found(&job_id),
+            Err(err) => Content::json(json!({
+                "success": false,
+                "error": err.to_string()
+            }))?,
+        };
+        Ok(CallToolResult::success(vec![content]))
+    }
+
+    #[tool(description = "列出所有已部署的函数,包含名称、语言和描述")]
+    async fn list_functions(&self) -> Result<CallToolResult, McpError> {
+        let content = match FunctionStore::global().and_then(|store| store.list()) {
+            Ok(functions) => Content::json(json!({
+                "functions": functions.iter().map(|f| f.summary()).collect::<Vec<_>>()
+            }))?,
+            Err(err) => Content::json(json!({
+                "success": false,
+                "error": err.to_string()
+            }))?,
+        };
+        Ok(CallToolResult::success(vec![content]))
+    }
+
+    #[tool(description = "按名称调用已部署的函数并返回结果,无需发送函数代码")]
+    async fn invoke_function(
+        &self,
+        request: Parameters<FunctionInvokeRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let request = request.0;
+        let options = request.options.into_run_options();
+        let execution = run_with_progress(&context, options, |options| async move {
+            let store = FunctionStore::global()?;
+            store.invoke(&request.name, request.params, &options).await
+        })
+        .await;
+        Self::to_call_tool_result(execution)
+    }
+
+    #[tool(
+        description = "部署函数,生成一个新版本并将 latest 指向该版本;部署后该函数会作为单独的工具提供"
+    )]
+    async fn deploy_function(
+        &self,
+        request: Parameters<FunctionDeployment>,
+    ) -> Result<CallToolResult, McpError> {
+        let deployment = match FunctionStore::global() {
+            Ok(store) => store.deploy(request.0).await,
+            Err(err) => Err(err),
+        };
+        let content = match deployment {
+            Ok(function) => Content::json(json!({
+                "success": true,
+                "function": function.summary()
+            }))?,
+            Err(err) => Content::json(json!({
+                "success": false,
+                "error": format!("{err:#}")
+            }))?,
+        };
+        Ok(CallToolResult::success(vec![content]))
+    }
+
+    #[tool(
+        description = "静态分析handler的参数类型(TypeScript类型/JSDoc、Python类型提示/文档字符串、Go结构体),返回参数的JSON Schema和函数描述,不会执行代码"
+    )]
+    async fn describe_function(
+        &self,
+        request: Parameters<DescribeFunctionRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let request = request.0;
+        let description = match (request.name, request.language, request.code) {
+            (Some(name), _, _) => FunctionStore::global().and_then(|store| store.describe(&name)),
+            (None, Some(language), Some(code)) => {
+                let signature = infer_signature(&language, &code, request.entrypoint.as_deref());
+                serde_json::to_value(signature).map_err(Into::into)
+            }
+            _ => Err(anyhow::anyhow!("需要指定 name,或同时提供 language 和 code")),
+        };
+        let content = match description {
+            Ok(description) => Content::json(description)?,
+            Err(err) => Content::json(json!({
+                "success": false,
+                "error": err.to_string()
+            }))?,
+        };
+        Ok(CallToolResult::success(vec![content]))
+    }
+
+    #[tool(
+        description = "使用deno check检查TypeScript代码的类型,不会执行代码;返回诊断信息的文件、行号、列号、错误码和错误信息,行号对应用户代码中的行"
+    )]
+    async fn check_typescript(
+        &self,
+        request: Parameters<TypeCheckRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let request = request.0;
+        let project =
+            ProjectFiles::from_request(request.files.as_ref(), request.entrypoint.as_deref());
+        let code = project.as_ref().map_or(request.code.as_str(), |project| {
+            project.entry_code(&request.code)
+        });
+        let checked = check_typescript(code, project.as_ref(), request.timeout_seconds).await;
+        let content = match checked {
+            Ok(diagnostics) => Content::json(json!({
+                "success": diagnostics.is_empty(),
+                "diagnostics": diagnostics
+            }))?,
+            Err(err) => Content::json(json!({
+                "success": false,
+                "error": err.to_string()
+            }))?,
+        };
+        Ok(CallToolResult::success(vec![content]))
+    }
+
+    #[tool(description = "删除已部署的函数及其所有版本")]
+    async fn delete_function(
+        &self,
+        request: Parameters<FunctionDeleteRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let name = request.0.name;
+        let content = match FunctionStore::global().and_then(|store| store.delete(&name)) {
+            Ok(true) => Content::json(json!({ "success": true }))?,
+            Ok(false) => Content::json(json!({
+                "success": false,
+                "error": format!("函数不存在: {name}")
+            }))?,
+            Err(err) => Content::json(json!({
+                "success": false,
+                "error": err.to_string()
+            }))?,
+        };
+        Ok(CallToolResult::success(vec![content]))
+    }
+
+    #[tool(description = "列出所有已注册、可执行的语言")]
+    async fn list_languages(&self) -> Result<CallToolResult, McpError> {
+        let languages: Vec<_> = RunnerRegistry::global_describe()
+            .into_iter()
+            .map(|(id, description)| json!({ "language": id, "description": description }))
+            .collect();
+        Ok(CallToolResult::success(vec![Content::json(json!({
+            "languages": languages
+        }))?]))
+    }
 }
 
-#[tool_handler(router = Self::tool_router())]
+/// 除静态的 `run_*` 等工具外,每个已部署的函数也作为单独的工具提供,
+/// 函数变化时发送 `notifications/tools/list_changed`
 impl ServerHandler for CodeRunnerService {
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let router = Self::tool_router();
+        if router.has_route(&request.name) {
+            let tool_context = ToolCallContext::new(self, request, context);
+            return router.call(tool_context).await;
+        }
+
+        let store =
+            FunctionStore::global().map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        let exists = store
+            .get(&request.name)
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?
+            .is_some();
+        if !exists {
+            return Err(McpError::invalid_params("tool not found", None));
+        }
+        let params = request.arguments.map(serde_json::Value::Object);
+        let execution = run_with_progress(&context, RunOptions::default(), |options| async move {
+            store.invoke(&request.name, params, &options).await
+        })
+        .await;
+        Self::to_call_tool_result(execution)
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        let mut tools = Self::tool_router().list_all();
+        let static_tool_names: HashSet<String> =
+            tools.iter().map(|tool| tool.name.to_string()).collect();
+        tools.extend(function_tools(&static_tool_names));
+        Ok(ListToolsResult {
+            tools,
+            next_cursor: None,
+        })
+    }
+
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        watch_function_changes(context.peer);
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .build(),
             server_info: Implementation::from_build_env(),
-            instructions: Some("一个支持执行JavaScript、TypeScript和Python代码的服务".to_string()),
+            instructions: Some(
+                "一个支持执行JavaScript、TypeScript、Python、Shell和Go代码的服务,可通过 list_languages 查看所有已注册的语言"
+                    .to_string(),
+            ),
         }
     }
 }
@@ -1,3 +1,10 @@
+mod function_tools;
 mod mcp_server;
+mod progress;
 
-pub use mcp_server::{CodeRunnerService,CodeRunRequest};
\ No newline at end of file
+pub use mcp_server::{
+    BatchRunRequest, CodeRunRequest, CodeRunnerService, DescribeFunctionRequest,
+    FunctionDeleteRequest, FunctionInvokeRequest,
+    JobIdRequest,
+    LanguageCodeRunRequest, PipelineRunRequest, TypeCheckRequest,
+};
@@ -0,0 +1,74 @@
+use std::future::Future;
+
+use anyhow::Result;
+use log::warn;
+use rmcp::{
+    RoleServer,
+    model::{ProgressNotificationParam, ProgressToken},
+    service::{Peer, RequestContext},
+};
+use serde_json::Value;
+use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+
+use crate::model::{ChunkSink, CodeScriptExecutionResult, RunOptions};
+
+/// 执行代码,请求携带 `progressToken` 时将生成器handler产生的每个数据块作为
+/// `notifications/progress` 实时发送给客户端(`message` 为数据块,非字符串时为其JSON文本);
+/// 执行结束后等待所有通知发送完成,保证通知先于工具结果到达客户端
+pub(crate) async fn run_with_progress<F, Fut>(
+    context: &RequestContext<RoleServer>,
+    options: RunOptions,
+    run: F,
+) -> Result<CodeScriptExecutionResult>
+where
+    F: FnOnce(RunOptions) -> Fut,
+    Fut: Future<Output = Result<CodeScriptExecutionResult>>,
+{
+    let Some(progress_token) = context.meta.get_progress_token() else {
+        return run(options).await;
+    };
+    let (sender, receiver) = unbounded_channel();
+    let forwarder = tokio::spawn(forward_progress(
+        context.peer.clone(),
+        progress_token,
+        receiver,
+    ));
+    let options = RunOptions {
+        chunk_sink: Some(ChunkSink::new(move |chunk| {
+            let _ = sender.send(chunk.clone());
+        })),
+        ..options
+    };
+    // 执行结束后 options 被释放,发送端随之关闭,通知转发任务在发送完剩余数据块后结束
+    let execution = run(options).await;
+    if let Err(e) = forwarder.await {
+        warn!("发送进度通知的任务异常结束: {e}");
+    }
+    execution
+}
+
+/// 按顺序发送进度通知,progress 为已产生的数据块数量
+async fn forward_progress(
+    peer: Peer<RoleServer>,
+    progress_token: ProgressToken,
+    mut receiver: UnboundedReceiver<Value>,
+) {
+    let mut progress = 0.0;
+    while let Some(chunk) = receiver.recv().await {
+        progress += 1.0;
+        let message = match chunk {
+            Value::String(text) => text,
+            chunk => chunk.to_string(),
+        };
+        let notification = ProgressNotificationParam {
+            progress_token: progress_token.clone(),
+            progress,
+            total: None,
+            message: Some(message),
+        };
+        if let Err(e) = peer.notify_progress(notification).await {
+            warn!("发送进度通知失败: {e}");
+            break;
+        }
+    }
+}
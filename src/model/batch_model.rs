@@ -0,0 +1,205 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{Result, anyhow, bail};
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::Semaphore;
+
+use crate::model::{CodeExecutor, LogEntry, RunOptions, SchemaContract};
+use crate::registry::RunnerRegistry;
+
+//批量执行默认并发数
+const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+//批量执行最大并发数
+const MAX_BATCH_CONCURRENCY: usize = 32;
+
+//单次批量执行最多的参数个数
+const MAX_BATCH_SIZE: usize = 1000;
+
+///批量执行中单组参数的执行结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchItemResult {
+    //参数在输入数组中的下标
+    pub index: usize,
+    //是否执行成功
+    pub success: bool,
+    //执行结果
+    pub result: Option<Value>,
+    //打印的log日志
+    pub logs: Vec<String>,
+    //结构化的日志记录
+    pub log_entries: Vec<LogEntry>,
+    //如果执行错误的话,错误信息
+    pub error: Option<String>,
+    //执行耗时(毫秒)
+    pub duration_ms: u64,
+}
+
+impl BatchItemResult {
+    /// 执行失败的结果
+    fn failed(index: usize, error: String) -> Self {
+        Self {
+            index,
+            success: false,
+            result: None,
+            logs: vec![],
+            log_entries: vec![],
+            error: Some(error),
+            duration_ms: 0,
+        }
+    }
+}
+
+///批量执行结果,items 与输入参数顺序一致
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchExecutionResult {
+    //参数总数
+    pub total: usize,
+    //执行成功的个数
+    pub succeeded: usize,
+    //执行失败的个数
+    pub failed: usize,
+    //每组参数的执行结果
+    pub items: Vec<BatchItemResult>,
+}
+
+impl BatchExecutionResult {
+    fn from_items(items: Vec<BatchItemResult>) -> Self {
+        let succeeded = items.iter().filter(|item| item.success).count();
+        Self {
+            total: items.len(),
+            succeeded,
+            failed: items.len() - succeeded,
+            items,
+        }
+    }
+
+    /// 所有参数都返回同一个错误(如依赖安装或编译失败)
+    fn all_failed(total: usize, error: &str) -> Self {
+        let items = (0..total)
+            .map(|index| BatchItemResult::failed(index, error.to_string()))
+            .collect();
+        Self::from_items(items)
+    }
+}
+
+impl CodeExecutor {
+    /// 使用多组参数批量执行同一份代码,最多 `concurrency` 组参数同时执行
+    ///
+    /// 执行前只调用一次 [`RunCode::prepare`](crate::model::RunCode::prepare) 准备缓存和依赖,
+    /// 单组参数执行失败不影响其他参数,结果按输入顺序返回
+    pub async fn execute_batch(
+        code: &str,
+        language_id: &str,
+        params_list: Vec<Value>,
+        options: &RunOptions,
+        concurrency: Option<usize>,
+    ) -> Result<BatchExecutionResult> {
+        if params_list.len() > MAX_BATCH_SIZE {
+            bail!(
+                "批量执行的参数个数 {} 超过上限 {MAX_BATCH_SIZE}",
+                params_list.len()
+            );
+        }
+        let concurrency = concurrency
+            .unwrap_or(DEFAULT_BATCH_CONCURRENCY)
+            .clamp(1, MAX_BATCH_CONCURRENCY);
+        info!(
+            "开始批量执行代码... 语言[{language_id}],参数个数: {},并发数: {concurrency}",
+            params_list.len()
+        );
+
+        let runner = RunnerRegistry::global_runner(language_id).ok_or_else(|| {
+            anyhow!(
+                "未注册的语言: {language_id}, 可选项: {}",
+                RunnerRegistry::global_language_ids().join(", ")
+            )
+        })?;
+
+        // 参数和结果约定只编译一次;与执行时使用相同的代码准备,多文件项目为入口文件的内容
+        let prepared = match SchemaContract::compile(options).and_then(|contract| {
+            CodeExecutor::resolve_entry_code(runner.as_ref(), code, options)
+                .map(|entry_code| (contract, entry_code))
+        }) {
+            Ok((contract, entry_code)) => {
+                runner.prepare(entry_code, options).await.map(|_| contract)
+            }
+            Err(e) => Err(e),
+        };
+        let contract = match prepared {
+            Ok(contract) => Arc::new(contract),
+            Err(e) => {
+                warn!("批量执行准备失败: {e:#}");
+                return Ok(BatchExecutionResult::all_failed(
+                    params_list.len(),
+                    &format!("{e:#}"),
+                ));
+            }
+        };
+
+        let semaphore = Arc::new(Semaphore::new(concurrency));
+        let code: Arc<str> = Arc::from(code);
+        let options = Arc::new(options.clone());
+
+        let handles: Vec<_> = params_list
+            .into_iter()
+            .enumerate()
+            .map(|(index, params)| {
+                let semaphore = semaphore.clone();
+                let runner = runner.clone();
+                let code = code.clone();
+                let options = options.clone();
+                let contract = contract.clone();
+                tokio::spawn(async move {
+                    let _permit = semaphore.acquire_owned().await?;
+                    let start_time = Instant::now();
+                    let execution = CodeExecutor::run_with_contract(
+                        runner.as_ref(),
+                        &code,
+                        Some(params),
+                        &options,
+                        &contract,
+                    )
+                    .await;
+                    let duration_ms = start_time.elapsed().as_millis() as u64;
+                    let item = match execution {
+                        Ok(result) => BatchItemResult {
+                            index,
+                            success: result.success,
+                            result: result.result,
+                            logs: result.logs,
+                            log_entries: result.log_entries,
+                            error: result.error,
+                            duration_ms,
+                        },
+                        Err(e) => BatchItemResult {
+                            duration_ms,
+                            ..BatchItemResult::failed(index, format!("{e:#}"))
+                        },
+                    };
+                    anyhow::Ok(item)
+                })
+            })
+            .collect();
+
+        let mut items = Vec::with_capacity(handles.len());
+        for (index, handle) in handles.into_iter().enumerate() {
+            let item = match handle.await {
+                Ok(Ok(item)) => item,
+                Ok(Err(e)) => BatchItemResult::failed(index, format!("{e:#}")),
+                Err(e) => BatchItemResult::failed(index, format!("执行任务异常: {e}")),
+            };
+            items.push(item);
+        }
+
+        let batch_result = BatchExecutionResult::from_items(items);
+        info!(
+            "批量执行完成,成功: {},失败: {}",
+            batch_result.succeeded, batch_result.failed
+        );
+        Ok(batch_result)
+    }
+}
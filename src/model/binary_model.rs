@@ -0,0 +1,171 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::{Value, json};
+
+/// 模板将handler返回的二进制数据编码为 `{"$binary": base64, "mime": 类型}`
+pub const BINARY_KEY: &str = "$binary";
+
+/// 从结果中取出二进制数据后,原位置替换为 `{"$content": 序号, "mime": 类型, "size": 字节数}`,
+/// 序号为二进制数据在工具结果内容列表中的位置
+pub const CONTENT_REF_KEY: &str = "$content";
+
+// 未知类型的二进制数据
+const OCTET_STREAM: &str = "application/octet-stream";
+
+// 常见文件格式的文件头,用于模板未提供类型时识别
+const MAGIC_NUMBERS: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"BM", "image/bmp"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+];
+
+///handler 返回的二进制数据
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryContent {
+    //base64 编码的数据
+    pub data: String,
+    //MIME 类型,模板未提供时根据文件头识别
+    pub mime: String,
+}
+
+impl BinaryContent {
+    /// 如果值是 `{"$binary": ..., "mime": ...}` 形式的二进制数据,返回解析结果
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let object = value.as_object()?;
+        let data = object.get(BINARY_KEY)?.as_str()?;
+        let bytes = STANDARD.decode(data).ok()?;
+        let mime = object
+            .get("mime")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|mime| !mime.is_empty() && *mime != OCTET_STREAM)
+            .map(String::from)
+            .unwrap_or_else(|| sniff_mime(&bytes).to_string());
+        Some(Self {
+            data: data.to_string(),
+            mime,
+        })
+    }
+
+    /// 是否为图片(不含参数的 MIME 类型以 `image/` 开头)
+    pub fn is_image(&self) -> bool {
+        self.essence().starts_with("image/")
+    }
+
+    /// 去掉参数(如 `; charset=utf-8`)后的 MIME 类型
+    pub fn essence(&self) -> &str {
+        self.mime.split(';').next().unwrap_or_default().trim()
+    }
+
+    /// 解码后的字节数
+    pub fn size(&self) -> usize {
+        let padding = self.data.bytes().rev().take_while(|b| *b == b'=').count();
+        (self.data.len() / 4 * 3).saturating_sub(padding)
+    }
+}
+
+/// 取出结果中所有的二进制数据(包括嵌套在对象和数组中的),原位置替换为引用对象,
+/// 引用序号从 `first_index` 开始
+pub fn extract_binaries(value: &mut Value, first_index: usize) -> Vec<BinaryContent> {
+    let mut binaries = vec![];
+    collect_binaries(value, first_index, &mut binaries);
+    binaries
+}
+
+fn collect_binaries(value: &mut Value, first_index: usize, binaries: &mut Vec<BinaryContent>) {
+    if let Some(binary) = BinaryContent::from_value(value) {
+        *value = json!({
+            CONTENT_REF_KEY: first_index + binaries.len(),
+            "mime": binary.mime,
+            "size": binary.size()
+        });
+        binaries.push(binary);
+        return;
+    }
+    match value {
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| collect_binaries(item, first_index, binaries)),
+        Value::Object(object) => object
+            .values_mut()
+            .for_each(|item| collect_binaries(item, first_index, binaries)),
+        _ => {}
+    }
+}
+
+/// 根据文件头识别 MIME 类型,无法识别时为 `application/octet-stream`
+fn sniff_mime(bytes: &[u8]) -> &'static str {
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return "image/webp";
+    }
+    MAGIC_NUMBERS
+        .iter()
+        .find(|(magic, _)| bytes.starts_with(magic))
+        .map(|(_, mime)| *mime)
+        .unwrap_or(OCTET_STREAM)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BinaryContent, extract_binaries};
+    use serde_json::json;
+
+    #[test]
+    fn test_sniff_mime_when_missing() {
+        // "\x89PNG\r\n\x1a\n" 的 base64
+        let png = BinaryContent::from_value(&json!({ "$binary": "iVBORw0KGgo=", "mime": null }))
+            .expect("binary");
+        assert_eq!(png.mime, "image/png");
+        assert!(png.is_image());
+        assert_eq!(png.size(), 8);
+
+        let pdf = BinaryContent::from_value(&json!({ "$binary": "JVBERi0xLjQ=" })).expect("binary");
+        assert_eq!(pdf.mime, "application/pdf");
+        assert!(!pdf.is_image());
+
+        let unknown = BinaryContent::from_value(&json!({ "$binary": "AAEC" })).expect("binary");
+        assert_eq!(unknown.mime, "application/octet-stream");
+    }
+
+    #[test]
+    fn test_declared_mime_and_invalid_values() {
+        let svg = BinaryContent::from_value(
+            &json!({ "$binary": "PHN2Zz4=", "mime": "image/svg+xml; charset=utf-8" }),
+        )
+        .expect("binary");
+        assert_eq!(svg.essence(), "image/svg+xml");
+        assert!(svg.is_image());
+
+        assert!(BinaryContent::from_value(&json!({ "$binary": "不是base64" })).is_none());
+        assert!(BinaryContent::from_value(&json!({ "data": "AAEC" })).is_none());
+        assert!(BinaryContent::from_value(&json!("AAEC")).is_none());
+    }
+
+    #[test]
+    fn test_extract_nested_binaries() {
+        let mut result = json!({
+            "title": "报表",
+            "chart": { "$binary": "iVBORw0KGgo=", "mime": "image/png" },
+            "files": [{ "$binary": "JVBERi0xLjQ=", "mime": "application/pdf" }]
+        });
+        let binaries = extract_binaries(&mut result, 1);
+        assert_eq!(binaries.len(), 2);
+        assert_eq!(
+            result,
+            json!({
+                "title": "报表",
+                "chart": { "$content": 1, "mime": "image/png", "size": 8 },
+                "files": [{ "$content": 2, "mime": "application/pdf", "size": 8 }]
+            })
+        );
+
+        let mut plain = json!({ "a": [1, 2] });
+        assert!(extract_binaries(&mut plain, 1).is_empty());
+        assert_eq!(plain, json!({ "a": [1, 2] }));
+    }
+}
@@ -1,9 +1,15 @@
 use std::{
+    collections::BTreeMap,
+    fmt,
     pin::Pin,
+    process::{Output, Stdio},
+    sync::Arc,
     task::{Context, Poll},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use anyhow::{Context as AnyHowContext, Result};
+use anyhow::{Context as AnyHowContext, Result, anyhow, bail};
+use async_trait::async_trait;
 use log::{info, warn};
 use pin_project::pin_project;
 use regex::Regex;
@@ -11,12 +17,17 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::future::Future;
 use tokio::{
-    io,
+    io::{self, AsyncBufReadExt, AsyncRead, BufReader},
     process::Command,
     time::{Duration, Sleep, sleep},
 };
 
-use crate::{deno_runner::JsRunner, deno_runner::TsRunner, python_runner::PythonRunner};
+use crate::app_error::AppError;
+use crate::model::{
+    CompiledSchema, FunctionIdentity, LogEntry, ProjectFiles, format_violations,
+    validate_entrypoint,
+};
+use crate::registry::RunnerRegistry;
 
 ///语言脚本,选择对应的语言脚本运行期
 #[derive(Debug, Clone)]
@@ -24,6 +35,8 @@ pub enum LanguageScript {
     Js,
     Ts,
     Python,
+    Shell,
+    Go,
 }
 
 impl LanguageScript {
@@ -33,8 +46,209 @@ impl LanguageScript {
             LanguageScript::Js => ".js",
             LanguageScript::Ts => ".ts",
             LanguageScript::Python => ".py",
+            LanguageScript::Shell => ".sh",
+            LanguageScript::Go => ".go",
+        }
+    }
+
+    /// 获取语言标识,用于在 [`RunnerRegistry`] 中查找对应的运行器
+    pub fn id(&self) -> &'static str {
+        match self {
+            LanguageScript::Js => "js",
+            LanguageScript::Ts => "ts",
+            LanguageScript::Python => "python",
+            LanguageScript::Shell => "shell",
+            LanguageScript::Go => "go",
+        }
+    }
+
+    /// 根据语言标识(或常用别名)获取内置语言
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id.to_lowercase().as_str() {
+            "js" | "javascript" => Some(LanguageScript::Js),
+            "ts" | "typescript" => Some(LanguageScript::Ts),
+            "python" | "py" => Some(LanguageScript::Python),
+            "shell" | "bash" | "sh" => Some(LanguageScript::Shell),
+            "go" | "golang" => Some(LanguageScript::Go),
+            _ => None,
+        }
+    }
+}
+
+///JavaScript 运行时,默认使用 Deno
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum JsRuntime {
+    #[default]
+    Deno,
+    Node,
+}
+
+///Python 结果中的 pandas DataFrame 转换为JSON的格式,与 `DataFrame.to_dict(orient=...)` 一致,默认 records
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFrameOrient {
+    //行记录列表: [{列名: 值}]
+    #[default]
+    Records,
+    //{"index": [...], "columns": [...], "data": [[...]]}
+    Split,
+    //{列名: {索引: 值}}
+    Columns,
+    //{索引: {列名: 值}}
+    Index,
+    //{列名: [值]}
+    List,
+    //与 split 相同,另外包含索引和列的名称
+    Tight,
+}
+
+impl DataFrameOrient {
+    /// 传给 `DataFrame.to_dict` 的 orient 参数
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataFrameOrient::Records => "records",
+            DataFrameOrient::Split => "split",
+            DataFrameOrient::Columns => "columns",
+            DataFrameOrient::Index => "index",
+            DataFrameOrient::List => "list",
+            DataFrameOrient::Tight => "tight",
+        }
+    }
+}
+
+/// Python 模板通过该环境变量获取 DataFrame 的转换格式
+pub const DATAFRAME_ORIENT_ENV: &str = "RUN_CODE_DATAFRAME_ORIENT";
+
+///日志回调,在代码执行过程中逐行接收标准输出和标准错误(如异步任务查询已产生的日志)
+#[derive(Clone)]
+pub struct LogSink(Arc<dyn Fn(&str) + Send + Sync>);
+
+impl LogSink {
+    pub fn new(sink: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(sink))
+    }
+
+    /// 输出一行日志
+    pub fn log(&self, line: &str) {
+        (self.0)(line)
+    }
+}
+
+impl fmt::Debug for LogSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LogSink")
+    }
+}
+
+/// 模板输出生成器数据块时使用的行前缀,后面紧跟数据块的JSON;需要与 templates 目录中的模板保持一致
+pub const CHUNK_MARKER: &str = "__RUN_CODE_CHUNK__";
+
+/// JavaScript/TypeScript 模板共用的结果序列化代码,替换模板中的 `{{JSON_SERIALIZER}}`
+pub(crate) const JS_SERIALIZER: &str = include_str!("../templates/js_serializer.js");
+
+/// JavaScript/TypeScript 模板共用的日志捕获、未捕获错误处理和结果输出代码,替换模板中的 `{{JS_HARNESS}}`
+pub(crate) const JS_HARNESS: &str = include_str!("../templates/js_harness.js");
+
+///数据块回调,handler 为生成器函数时逐个接收产生的数据块
+#[derive(Clone)]
+pub struct ChunkSink(Arc<dyn Fn(&Value) + Send + Sync>);
+
+impl ChunkSink {
+    pub fn new(sink: impl Fn(&Value) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(sink))
+    }
+
+    /// 输出一个数据块
+    pub fn chunk(&self, chunk: &Value) {
+        (self.0)(chunk)
+    }
+}
+
+impl fmt::Debug for ChunkSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ChunkSink")
+    }
+}
+
+/// 未设置超时时间时使用的默认超时时间(秒)
+pub const DEFAULT_TIMEOUT_SECONDS: u64 = 180;
+
+/// 模板通过该环境变量获取执行的截止时间(Unix 时间戳,秒),用于异步handler的超时控制
+pub const DEADLINE_ENV: &str = "RUN_CODE_DEADLINE";
+
+///代码执行选项,按请求传递给运行器
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    //超时时间(秒),不设置则使用默认超时时间
+    pub timeout_seconds: Option<u64>,
+    //JavaScript 运行时,仅对js有效
+    pub runtime: Option<JsRuntime>,
+    //Python 版本,如 3.11,仅对python有效;需要已通过 uv 安装
+    pub python_version: Option<String>,
+    //日志回调,设置后执行过程中的输出会实时转发
+    pub log_sink: Option<LogSink>,
+    //数据块回调,设置后生成器handler产生的数据块会实时转发
+    pub chunk_sink: Option<ChunkSink>,
+    //参数的JSON Schema,设置后在启动子进程前校验参数
+    pub input_schema: Option<Value>,
+    //执行结果的JSON Schema,设置后校验handler的返回值
+    pub output_schema: Option<Value>,
+    //多文件项目,设置后代码为项目的入口文件
+    pub project: Option<ProjectFiles>,
+    //入口函数名,不设置时依次查找 handler、main
+    pub entrypoint: Option<String>,
+    //pandas DataFrame 结果的JSON格式,仅对python有效
+    pub dataframe_orient: Option<DataFrameOrient>,
+    //调用已部署的函数时的函数名称和版本,传入handler的调用上下文
+    pub function: Option<FunctionIdentity>,
+    //声明的环境变量,设置到子进程,handler 也可以通过调用上下文的 env 读取
+    pub env: BTreeMap<String, String>,
+    //执行前检查代码的类型,有类型错误时不执行,仅对TypeScript有效
+    pub type_check: bool,
+    //声明的依赖,如 requests<3,写入脚本的依赖元数据并计入缓存key,仅对python有效
+    pub dependencies: Vec<String>,
+}
+
+impl RunOptions {
+    /// 只设置超时时间的执行选项
+    pub fn with_timeout(timeout_seconds: Option<u64>) -> Self {
+        Self {
+            timeout_seconds,
+            ..Default::default()
         }
     }
+
+    /// 从当前时间开始计算的执行截止时间(Unix 时间戳,秒),未设置超时时间时使用默认超时时间
+    pub fn deadline(&self) -> f64 {
+        let timeout = self.timeout_seconds.unwrap_or(DEFAULT_TIMEOUT_SECONDS);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        (now + Duration::from_secs(timeout)).as_secs_f64()
+    }
 }
 
 ///执行结果,包含js/python 执行结果,和打印的log日志
@@ -44,6 +258,9 @@ pub struct CodeScriptExecutionResult {
     pub result: Option<Value>,
     //js/python 打印的log日志
     pub logs: Vec<String>,
+    //结构化的日志记录,与 logs 一一对应
+    #[serde(default)]
+    pub log_entries: Vec<LogEntry>,
     // 是否执行成功,ture:默认值,执行成功
     #[serde(skip_serializing)]
     pub success: bool,
@@ -52,9 +269,9 @@ pub struct CodeScriptExecutionResult {
     pub error: Option<String>,
 }
 
-///运行代码的抽象
-#[allow(async_fn_in_trait)]
-pub trait RunCode {
+///运行代码的抽象,对象安全,可以通过 `Arc<dyn RunCode>` 注册到 [`RunnerRegistry`]
+#[async_trait]
+pub trait RunCode: Send + Sync {
     ///运行代码并传递参数，可选设置超时时间
     async fn run_with_params(
         &self,
@@ -62,6 +279,58 @@ pub trait RunCode {
         params: Option<serde_json::Value>,
         timeout_seconds: Option<u64>,
     ) -> Result<CodeScriptExecutionResult>;
+
+    ///使用执行选项运行代码,默认只使用超时时间;需要其他选项的运行器需要覆盖此方法
+    async fn run_with_options(
+        &self,
+        code: &str,
+        params: Option<serde_json::Value>,
+        options: &RunOptions,
+    ) -> Result<CodeScriptExecutionResult> {
+        self.run_with_params(code, params, options.timeout_seconds)
+            .await
+    }
+
+    ///预先准备代码的执行环境(生成脚本缓存、安装依赖、编译等),批量执行时只准备一次;默认不做处理
+    async fn prepare(&self, _code: &str, _options: &RunOptions) -> Result<()> {
+        Ok(())
+    }
+
+    /// 运行器的描述信息,用于列出已注册的语言
+    fn description(&self) -> String {
+        String::new()
+    }
+
+    /// 是否支持多文件项目(执行选项中的 `project`);默认不支持
+    fn supports_project(&self) -> bool {
+        false
+    }
+}
+
+///执行选项中编译后的参数和结果约定,批量执行时所有参数共用
+#[derive(Debug, Default)]
+pub(crate) struct SchemaContract {
+    //参数的 JSON Schema
+    input: Option<CompiledSchema>,
+    //结果的 JSON Schema
+    output: Option<CompiledSchema>,
+}
+
+impl SchemaContract {
+    /// 编译执行选项中的 `input_schema`/`output_schema`,schema 无效时在执行代码前返回错误
+    pub(crate) fn compile(options: &RunOptions) -> Result<Self> {
+        let compile = |schema: Option<&Value>, name: &str| {
+            schema
+                .map(|schema| {
+                    CompiledSchema::compile(schema).with_context(|| format!("{name} 无效"))
+                })
+                .transpose()
+        };
+        Ok(Self {
+            input: compile(options.input_schema.as_ref(), "input_schema")?,
+            output: compile(options.output_schema.as_ref(), "output_schema")?,
+        })
+    }
 }
 
 /// 代码执行器
@@ -75,25 +344,99 @@ impl CodeExecutor {
         params: Option<serde_json::Value>,
         timeout_seconds: Option<u64>,
     ) -> Result<CodeScriptExecutionResult> {
+        Self::execute_by_language_id(code, language.id(), params, timeout_seconds).await
+    }
+
+    /// 根据语言标识,从全局 [`RunnerRegistry`] 中查找运行器并执行代码
+    pub async fn execute_by_language_id(
+        code: &str,
+        language_id: &str,
+        params: Option<serde_json::Value>,
+        timeout_seconds: Option<u64>,
+    ) -> Result<CodeScriptExecutionResult> {
+        Self::execute_with_options(
+            code,
+            language_id,
+            params,
+            &RunOptions::with_timeout(timeout_seconds),
+        )
+        .await
+    }
+
+    /// 根据语言标识和执行选项执行代码
+    pub async fn execute_with_options(
+        code: &str,
+        language_id: &str,
+        params: Option<serde_json::Value>,
+        options: &RunOptions,
+    ) -> Result<CodeScriptExecutionResult> {
+        // 只记录不含敏感内容的选项,环境变量的值和项目文件的内容不写入日志
         info!(
-            "开始执行代码... 语言[{language:?}],执行参数: {params:?}"
+            "开始执行代码... 语言[{language_id}],执行参数: {params:?},超时时间: {:?},运行时: {:?},入口函数: {:?}",
+            options.timeout_seconds, options.runtime, options.entrypoint
         );
-        match language {
-            LanguageScript::Js => {
-                JsRunner
-                    .run_with_params(code, params, timeout_seconds)
-                    .await
+        let runner = RunnerRegistry::global_runner(language_id).ok_or_else(|| {
+            anyhow!(
+                "未注册的语言: {language_id}, 可选项: {}",
+                RunnerRegistry::global_language_ids().join(", ")
+            )
+        })?;
+        let contract = SchemaContract::compile(options)?;
+        Self::run_with_contract(runner.as_ref(), code, params, options, &contract).await
+    }
+
+    /// 按编译后的 `input_schema`/`output_schema` 校验参数和结果并执行代码
+    ///
+    /// 参数不符合时不会启动子进程,返回 [`AppError::ValidationError`];
+    /// 结果不符合时标记为执行失败,错误信息为 [`AppError::ContractViolation`]
+    pub(crate) async fn run_with_contract(
+        runner: &dyn RunCode,
+        code: &str,
+        params: Option<Value>,
+        options: &RunOptions,
+        contract: &SchemaContract,
+    ) -> Result<CodeScriptExecutionResult> {
+        let code = Self::resolve_entry_code(runner, code, options)?;
+        if let Some(entrypoint) = &options.entrypoint {
+            validate_entrypoint(entrypoint)?;
+        }
+        if let Some(schema) = &contract.input {
+            // 未传参数时按空对象校验,与运行器的处理一致
+            let input = params.clone().unwrap_or_else(|| Value::Object(Default::default()));
+            let violations = schema.validate(&input);
+            if !violations.is_empty() {
+                return Err(AppError::ValidationError(violations).into());
             }
-            LanguageScript::Ts => {
-                TsRunner
-                    .run_with_params(code, params, timeout_seconds)
-                    .await
+        }
+
+        let mut result = runner.run_with_options(code, params, options).await?;
+        if let Some(schema) = contract.output.as_ref().filter(|_| result.success) {
+            let output = result.result.clone().unwrap_or(Value::Null);
+            let violations = schema.validate(&output);
+            if !violations.is_empty() {
+                warn!("执行结果不符合输出约定: {}", format_violations(&violations));
+                result.success = false;
+                result.error = Some(AppError::ContractViolation(violations).to_string());
             }
-            LanguageScript::Python => {
-                PythonRunner
-                    .run_with_params(code, params, timeout_seconds)
-                    .await
+        }
+        Ok(result)
+    }
+
+    /// 实际执行的代码: 多文件项目为入口文件的内容(校验项目文件路径),否则为请求中的代码
+    pub(crate) fn resolve_entry_code<'a>(
+        runner: &dyn RunCode,
+        code: &'a str,
+        options: &'a RunOptions,
+    ) -> Result<&'a str> {
+        match &options.project {
+            Some(project) => {
+                if !runner.supports_project() {
+                    bail!("该语言暂不支持多文件项目");
+                }
+                project.validate()?;
+                Ok(project.entry_code(code))
             }
+            None => Ok(code),
         }
     }
 
@@ -123,15 +466,23 @@ impl CodeExecutor {
             let parsed: serde_json::Value =
                 serde_json::from_str(json_str).context("Failed to parse JSON output")?;
 
-            // 从JSON中提取logs、result和error
-            let logs = parsed["logs"]
+            // 从JSON中提取logs、result和error,数据块不作为日志返回
+            let logs: Vec<String> = parsed["logs"]
                 .as_array()
                 .map(|arr| {
                     arr.iter()
-                        .filter_map(|v| v.as_str().map(String::from))
+                        .filter_map(|v| v.as_str())
+                        .filter(|line| !line.starts_with(CHUNK_MARKER))
+                        .map(String::from)
                         .collect()
                 })
                 .unwrap_or_default();
+            // 模板没有输出结构化的日志记录时(如Go)从日志文本解析
+            let log_entries = match parsed.get("log_entries") {
+                Some(entries) => serde_json::from_value(entries.clone())
+                    .context("Failed to parse log entries")?,
+                None => logs.iter().map(|line| LogEntry::from_text(line)).collect(),
+            };
 
             // 处理结果，尝试解析JSON字符串
             let result = if parsed["result"].is_null() {
@@ -151,6 +502,7 @@ impl CodeExecutor {
 
             return Ok(CodeScriptExecutionResult {
                 logs,
+                log_entries,
                 result,
                 success: error.is_none(),
                 error,
@@ -158,12 +510,14 @@ impl CodeExecutor {
         }
 
         // 如果没有找到结构化输出，返回原始输出
+        let logs = if !stdout_str.is_empty() {
+            vec![stdout_str]
+        } else {
+            vec![]
+        };
         Ok(CodeScriptExecutionResult {
-            logs: if !stdout_str.is_empty() {
-                vec![stdout_str]
-            } else {
-                vec![]
-            },
+            log_entries: logs.iter().map(|line| LogEntry::from_text(line)).collect(),
+            logs,
             result: None,
             success: false,
             error: Some(format!(
@@ -173,6 +527,84 @@ impl CodeExecutor {
     }
 }
 
+/// 执行命令并收集输出,超过 `timeout_seconds` 后终止(需要设置 kill_on_drop);
+/// 设置了 `log_sink` 或 `chunk_sink` 时,标准输出和标准错误会逐行实时转发
+pub async fn output_with_options(
+    command: &mut Command,
+    options: &RunOptions,
+) -> io::Result<io::Result<Output>> {
+    if options.log_sink.is_none() && options.chunk_sink.is_none() {
+        return match options.timeout_seconds {
+            Some(timeout) => CommandExecutor::with_timeout(command.output(), timeout).await,
+            None => CommandExecutor::default(command.output()).await,
+        };
+    }
+    let future = output_with_sinks(command, options);
+    match options.timeout_seconds {
+        Some(timeout) => CommandExecutor::with_timeout(future, timeout).await,
+        None => CommandExecutor::default(future).await,
+    }
+}
+
+/// 与 `Command::output` 相同,但在读取输出的同时转发每一行日志和每个数据块
+async fn output_with_sinks(command: &mut Command, options: &RunOptions) -> io::Result<Output> {
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = command.spawn()?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let (stdout, stderr, status) = tokio::try_join!(
+        forward_lines(stdout, options, false),
+        forward_lines(stderr, options, true),
+        child.wait()
+    )?;
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+/// 逐行读取输出并转发给回调,返回读取到的全部内容;最终的结构化输出不作为日志转发,
+/// 以 [`CHUNK_MARKER`] 开头的行作为数据块转发
+async fn forward_lines<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    options: &RunOptions,
+    is_stderr: bool,
+) -> io::Result<Vec<u8>> {
+    let mut content = Vec::new();
+    let Some(reader) = reader else {
+        return Ok(content);
+    };
+
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    while reader.read_until(b'\n', &mut line).await? > 0 {
+        content.extend_from_slice(&line);
+        let text = String::from_utf8_lossy(&line);
+        let text = text.trim_end();
+        let chunk = text
+            .strip_prefix(CHUNK_MARKER)
+            .filter(|_| !is_stderr)
+            .map(|json| {
+                serde_json::from_str::<Value>(json).unwrap_or_else(|_| Value::String(json.into()))
+            });
+        match (chunk, &options.chunk_sink, &options.log_sink) {
+            (Some(chunk), Some(chunk_sink), _) => chunk_sink.chunk(&chunk),
+            (Some(_), None, _) => {}
+            (None, _, Some(log_sink)) if is_stderr && !text.is_empty() => {
+                log_sink.log(&format!("[stderr] {text}"))
+            }
+            (None, _, Some(log_sink)) if !text.is_empty() && !text.starts_with(r#"{"logs""#) => {
+                log_sink.log(text)
+            }
+            _ => {}
+        }
+        line.clear();
+    }
+    Ok(content)
+}
+
 /// 封装 tokio::command 的执行,并设置堆大小限制
 #[allow(dead_code)]
 pub struct TokioHeapSize {
@@ -198,7 +630,7 @@ impl TokioHeapSize {
         #[cfg(target_os = "linux")]
         {
             use libc::{RLIMIT_AS, rlimit, setrlimit};
-            let heap_size = self.heap_size.clone();
+            let heap_size = self.heap_size;
             unsafe {
                 command.pre_exec(move || {
                     let rlim = rlimit {
@@ -227,7 +659,7 @@ pub struct CommandExecutor<F> {
 
 impl<F> CommandExecutor<F> {
     pub fn default(future: F) -> Self {
-        let timeout = sleep(Duration::from_secs(180));
+        let timeout = sleep(Duration::from_secs(DEFAULT_TIMEOUT_SECONDS));
 
         Self { timeout, future }
     }
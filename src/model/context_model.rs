@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, bail};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::model::RunOptions;
+
+/// 模板通过该环境变量获取调用上下文(JSON),入口函数声明了第二个参数时作为 context 传入
+pub const CONTEXT_ENV: &str = "RUN_CODE_CONTEXT";
+
+/// 模板和运行器使用的环境变量前缀,声明的环境变量不能使用
+const RESERVED_ENV_PREFIXES: &[&str] = &["RUN_CODE_", "PARAM_"];
+
+/// 运行器传递参数使用的环境变量,声明的环境变量不能使用
+const RESERVED_ENV_NAMES: &[&str] = &["INPUT_JSON"];
+
+static ENV_NAME_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").expect("invalid env name regex"));
+
+static INVOCATION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+///调用已部署的函数时的函数名称和版本
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionIdentity {
+    //函数名称
+    pub name: String,
+    //版本号
+    pub version: u64,
+}
+
+///handler 的调用上下文,所有语言的字段一致
+#[derive(Debug, Clone, Serialize)]
+pub struct InvocationContext {
+    //本次调用的唯一标识
+    pub invocation_id: String,
+    //已部署函数的名称,直接执行代码时为 null
+    pub function_name: Option<String>,
+    //已部署函数的版本号,直接执行代码时为 null
+    pub function_version: Option<u64>,
+    //语言标识,如 python、js
+    pub language: String,
+    //执行截止时间(Unix 时间戳,秒)
+    pub deadline: f64,
+    //声明的环境变量
+    pub env: BTreeMap<String, String>,
+}
+
+impl InvocationContext {
+    /// 根据执行选项生成一次调用的上下文,每次调用的 `invocation_id` 不同
+    pub fn new(language: &str, options: &RunOptions) -> Self {
+        Self {
+            invocation_id: next_invocation_id(),
+            function_name: options.function.as_ref().map(|f| f.name.clone()),
+            function_version: options.function.as_ref().map(|f| f.version),
+            language: language.to_string(),
+            deadline: options.deadline(),
+            env: options.env.clone(),
+        }
+    }
+
+    /// 设置子进程的环境变量: 声明的环境变量和 [`CONTEXT_ENV`]
+    pub fn apply(&self, command: &mut Command) {
+        command.envs(&self.env).env(
+            CONTEXT_ENV,
+            serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string()),
+        );
+    }
+}
+
+/// 校验声明的环境变量名,必须是合法的标识符,且不能与运行器使用的环境变量冲突
+pub fn validate_env(env: &BTreeMap<String, String>) -> Result<()> {
+    for name in env.keys() {
+        if !ENV_NAME_RE.is_match(name) {
+            bail!("环境变量名必须由字母、数字和下划线组成且不以数字开头: {name}");
+        }
+        if RESERVED_ENV_NAMES.contains(&name.as_str())
+            || RESERVED_ENV_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        {
+            bail!("环境变量名 {name} 由运行器使用,不能声明");
+        }
+    }
+    Ok(())
+}
+
+/// 解析 `KEY=VALUE` 形式的环境变量声明
+pub fn parse_env_pair(pair: &str) -> Result<(String, String)> {
+    match pair.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => bail!("环境变量需要使用 KEY=VALUE 格式: {pair}"),
+    }
+}
+
+fn next_invocation_id() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let seed = format!(
+        "{}-{now}-{}",
+        std::process::id(),
+        INVOCATION_COUNTER.fetch_add(1, Ordering::SeqCst)
+    );
+    blake3::hash(seed.as_bytes()).to_hex()[..32].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InvocationContext, parse_env_pair, validate_env};
+    use crate::model::RunOptions;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_validate_env() {
+        let env = |name: &str| BTreeMap::from([(name.to_string(), "1".to_string())]);
+        assert!(validate_env(&env("API_URL")).is_ok());
+        assert!(validate_env(&env("_debug2")).is_ok());
+        assert!(validate_env(&env("2FA")).is_err());
+        assert!(validate_env(&env("API-URL")).is_err());
+        assert!(validate_env(&env("INPUT_JSON")).is_err());
+        assert!(validate_env(&env("RUN_CODE_CONTEXT")).is_err());
+        assert!(validate_env(&env("PARAM_NAME")).is_err());
+    }
+
+    #[test]
+    fn test_parse_env_pair() {
+        assert_eq!(
+            parse_env_pair("API_URL=http://a?b=c").unwrap(),
+            ("API_URL".to_string(), "http://a?b=c".to_string())
+        );
+        assert_eq!(
+            parse_env_pair("EMPTY=").unwrap(),
+            ("EMPTY".to_string(), String::new())
+        );
+        assert!(parse_env_pair("NO_VALUE").is_err());
+        assert!(parse_env_pair("=value").is_err());
+    }
+
+    #[test]
+    fn test_invocation_ids_are_unique() {
+        let options = RunOptions::default();
+        let first = InvocationContext::new("python", &options);
+        let second = InvocationContext::new("python", &options);
+        assert_eq!(first.invocation_id.len(), 32);
+        assert_ne!(first.invocation_id, second.invocation_id);
+        assert_eq!(first.function_name, None);
+    }
+}
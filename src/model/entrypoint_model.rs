@@ -0,0 +1,106 @@
+use anyhow::{Result, bail};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// 未指定入口函数时依次查找的函数名,所有语言一致;Go 中为首字母大写的 `Handler`、`Main`
+pub const DEFAULT_ENTRYPOINTS: &[&str] = &["handler", "main"];
+
+/// 模板通过该环境变量获取指定的入口函数名,未设置时按 [`DEFAULT_ENTRYPOINTS`] 查找
+pub const ENTRYPOINT_ENV: &str = "RUN_CODE_ENTRYPOINT";
+
+static IDENTIFIER_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z_$][A-Za-z0-9_$]*$").expect("invalid identifier regex"));
+
+/// 校验入口函数名,必须是合法的标识符
+pub fn validate_entrypoint(entrypoint: &str) -> Result<()> {
+    if !IDENTIFIER_RE.is_match(entrypoint) {
+        bail!("入口函数名必须是合法的标识符: {entrypoint}");
+    }
+    Ok(())
+}
+
+/// 入口函数的查找顺序: 指定入口函数时只查找该函数,否则为 [`DEFAULT_ENTRYPOINTS`]
+pub fn entrypoint_candidates(entrypoint: Option<&str>) -> Vec<&str> {
+    match entrypoint {
+        Some(entrypoint) => vec![entrypoint],
+        None => DEFAULT_ENTRYPOINTS.to_vec(),
+    }
+}
+
+/// 拆分请求中的 `entrypoint`,返回 (入口文件, 入口函数名)
+///
+/// 多文件项目中为 `文件[:函数名]`,如 `main.py:add`;否则整个值为入口函数名
+pub fn split_entrypoint(entrypoint: Option<&str>, has_files: bool) -> (Option<&str>, Option<&str>) {
+    let Some(entrypoint) = entrypoint.map(str::trim).filter(|e| !e.is_empty()) else {
+        return (None, None);
+    };
+    if !has_files {
+        return (None, Some(entrypoint));
+    }
+    match entrypoint.rsplit_once(':') {
+        Some((file, function)) => (Some(file), Some(function).filter(|f| !f.is_empty())),
+        None => (Some(entrypoint), None),
+    }
+}
+
+/// 可用函数列表的展示文本
+pub fn format_available(available: &[String]) -> String {
+    if available.is_empty() {
+        "(none)".to_string()
+    } else {
+        available.join(", ")
+    }
+}
+
+/// JavaScript/TypeScript 模板中按名称查找函数使用的对象字面量,
+/// 代码中没有定义为函数的名称值为 `undefined`
+pub(crate) fn js_function_table(names: &[String]) -> String {
+    let entries = names
+        .iter()
+        .filter(|name| IDENTIFIER_RE.is_match(name))
+        .map(|name| format!("\"{name}\": typeof {name} === 'function' ? {name} : undefined"))
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return "{}".to_string();
+    }
+    format!("{{ {} }}", entries.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{entrypoint_candidates, js_function_table, split_entrypoint, validate_entrypoint};
+
+    #[test]
+    fn test_split_entrypoint() {
+        assert_eq!(split_entrypoint(Some("add"), false), (None, Some("add")));
+        assert_eq!(
+            split_entrypoint(Some("main.py"), true),
+            (Some("main.py"), None)
+        );
+        assert_eq!(
+            split_entrypoint(Some("src/index.ts:sum"), true),
+            (Some("src/index.ts"), Some("sum"))
+        );
+        assert_eq!(split_entrypoint(Some(" "), false), (None, None));
+        assert_eq!(split_entrypoint(None, true), (None, None));
+    }
+
+    #[test]
+    fn test_validate_and_candidates() {
+        assert!(validate_entrypoint("sum_all").is_ok());
+        assert!(validate_entrypoint("$handler").is_ok());
+        assert!(validate_entrypoint("a-b").is_err());
+        assert!(validate_entrypoint("main.py:add").is_err());
+        assert_eq!(entrypoint_candidates(None), vec!["handler", "main"]);
+        assert_eq!(entrypoint_candidates(Some("add")), vec!["add"]);
+    }
+
+    #[test]
+    fn test_js_function_table() {
+        assert_eq!(js_function_table(&[]), "{}");
+        assert_eq!(
+            js_function_table(&["add".to_string()]),
+            "{ \"add\": typeof add === 'function' ? add : undefined }"
+        );
+    }
+}
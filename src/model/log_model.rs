@@ -0,0 +1,79 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// 日志文本中的级别前缀,如 "[INFO] 消息"
+static LEVEL_PREFIX_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\[(DEBUG|INFO|WARNING|ERROR|CRITICAL)\] ").expect("invalid level prefix regex")
+});
+
+// 日志文本中标准错误的前缀
+const STDERR_PREFIX: &str = "[stderr] ";
+
+///日志原本输出到的流
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+///结构化的日志记录,与 `logs` 中的文本一一对应
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    //日志级别: DEBUG、INFO、WARNING、ERROR、CRITICAL,直接打印的内容没有级别
+    pub level: Option<String>,
+    //日志记录器名称,如 Python logging 的 logger 名称;没有时为空
+    pub logger: Option<String>,
+    //日志消息,不包含级别等前缀
+    pub message: String,
+    //日志原本输出到的流,如 console.warn、Python logging 和 warnings 为 stderr
+    pub stream: LogStream,
+}
+
+impl LogEntry {
+    /// 从日志文本解析结构化的日志记录,用于只输出文本日志的语言(Shell、Go)
+    ///
+    /// `[stderr] ` 开头的为标准错误,`[INFO] ` 等级别前缀作为日志级别
+    pub fn from_text(text: &str) -> Self {
+        let (stream, text) = match text.strip_prefix(STDERR_PREFIX) {
+            Some(text) => (LogStream::Stderr, text),
+            None => (LogStream::Stdout, text),
+        };
+        let (level, message) = match LEVEL_PREFIX_RE.captures(text) {
+            Some(captures) => (
+                Some(captures[1].to_string()),
+                &text[captures.get(0).map_or(0, |m| m.end())..],
+            ),
+            None => (None, text),
+        };
+        Self {
+            level,
+            logger: None,
+            message: message.to_string(),
+            stream,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LogEntry, LogStream};
+
+    #[test]
+    fn test_log_entry_from_text() {
+        let entry = LogEntry::from_text("[WARNING] 磁盘空间不足");
+        assert_eq!(entry.level.as_deref(), Some("WARNING"));
+        assert_eq!(entry.message, "磁盘空间不足");
+        assert_eq!(entry.stream, LogStream::Stdout);
+
+        let entry = LogEntry::from_text("[stderr] [ERROR] 失败");
+        assert_eq!(entry.level.as_deref(), Some("ERROR"));
+        assert_eq!(entry.message, "失败");
+        assert_eq!(entry.stream, LogStream::Stderr);
+
+        let entry = LogEntry::from_text("[note] 普通输出");
+        assert_eq!((entry.level, entry.logger), (None, None));
+        assert_eq!(entry.message, "[note] 普通输出");
+    }
+}
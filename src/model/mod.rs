@@ -1,8 +1,37 @@
+mod batch_model;
+mod binary_model;
 mod code_run_model;
+mod context_model;
+mod entrypoint_model;
+mod log_model;
+mod pipeline_model;
+mod project_model;
+mod run_options_model;
+mod schema_model;
 mod tool_params;
+mod type_check_model;
 
+pub use batch_model::{BatchExecutionResult, BatchItemResult};
+pub use binary_model::{BINARY_KEY, BinaryContent, CONTENT_REF_KEY, extract_binaries};
 pub use code_run_model::{
-    CodeExecutor, CodeScriptExecutionResult, CommandExecutor, LanguageScript, RunCode,
+    CHUNK_MARKER, ChunkSink, CodeExecutor, CodeScriptExecutionResult, CommandExecutor,
+    DATAFRAME_ORIENT_ENV, DEADLINE_ENV, DEFAULT_TIMEOUT_SECONDS, DataFrameOrient, JsRuntime,
+    LanguageScript, LogSink, RunCode, RunOptions, TokioHeapSize, output_with_options,
 };
+pub use context_model::{
+    CONTEXT_ENV, FunctionIdentity, InvocationContext, parse_env_pair, validate_env,
+};
+pub use entrypoint_model::{
+    DEFAULT_ENTRYPOINTS, ENTRYPOINT_ENV, entrypoint_candidates, format_available, split_entrypoint,
+    validate_entrypoint,
+};
+pub(crate) use code_run_model::{JS_HARNESS, JS_SERIALIZER, SchemaContract};
+pub(crate) use entrypoint_model::js_function_table;
+pub use log_model::{LogEntry, LogStream};
+pub use pipeline_model::{PipelineExecutionResult, PipelineStep, PipelineStepResult};
+pub use project_model::ProjectFiles;
+pub use run_options_model::RunOptionsRequest;
+pub use schema_model::{CompiledSchema, SchemaViolation, format_violations};
 #[allow(unused_imports)]
 pub use tool_params::RunCodeHttpResult;
+pub use type_check_model::{TypeDiagnostic, USER_CODE_FILE, format_diagnostics};
@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use anyhow::{Result, anyhow, bail};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::model::{CodeExecutor, LogEntry, RunOptionsRequest};
+
+//流水线最多的步骤数
+const MAX_PIPELINE_STEPS: usize = 20;
+
+///流水线中的单个步骤
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PipelineStep {
+    #[schemars(description = "可选的步骤名称,用于日志和结果展示")]
+    #[serde(default)]
+    pub name: Option<String>,
+
+    #[schemars(description = "语言标识,可通过 list_languages 工具获取已注册的语言")]
+    pub language: String,
+
+    #[schemars(description = "要执行的代码,提供 files 时可以省略,使用 files 中的入口文件")]
+    #[serde(default)]
+    pub code: String,
+
+    #[schemars(description = "可选的固定参数,会覆盖上一步结果中的同名参数")]
+    #[serde(default)]
+    pub params: Option<Value>,
+
+    #[schemars(
+        description = "可选的参数映射: 参数名 -> 上一步结果中的JSON Pointer(如 /data/0/name,空字符串表示整个结果);不设置时上一步结果为对象则直接作为参数,否则作为 input 参数"
+    )]
+    #[serde(default)]
+    pub param_mapping: Option<BTreeMap<String, String>>,
+
+    #[schemars(description = "该步骤失败时是否继续执行后续步骤,默认false")]
+    #[serde(default)]
+    pub continue_on_error: bool,
+
+    #[serde(flatten)]
+    pub options: RunOptionsRequest,
+}
+
+impl PipelineStep {
+    /// 根据上一步的结果构建本步骤的参数
+    pub(crate) fn build_params(&self, input: Option<&Value>) -> Result<Value> {
+        let mut params = match &self.param_mapping {
+            Some(mapping) => {
+                let mut params = Map::new();
+                for (name, pointer) in mapping {
+                    let value = input
+                        .and_then(|input| input.pointer(pointer))
+                        .ok_or_else(|| anyhow!("参数映射失败: 上一步结果中不存在 {pointer:?}"))?;
+                    params.insert(name.clone(), value.clone());
+                }
+                params
+            }
+            None => match input {
+                Some(Value::Object(map)) => map.clone(),
+                Some(Value::Null) | None => Map::new(),
+                Some(other) => Map::from_iter([("input".to_string(), other.clone())]),
+            },
+        };
+
+        match &self.params {
+            Some(Value::Object(fixed)) => {
+                params.extend(fixed.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+            Some(Value::Null) | None => {}
+            Some(_) => bail!("步骤参数 params 必须是JSON对象"),
+        }
+        Ok(Value::Object(params))
+    }
+}
+
+///流水线单个步骤的执行结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineStepResult {
+    //步骤下标
+    pub index: usize,
+    //步骤名称
+    pub name: Option<String>,
+    //语言标识
+    pub language: String,
+    //是否执行成功
+    pub success: bool,
+    //执行结果
+    pub result: Option<Value>,
+    //打印的log日志
+    pub logs: Vec<String>,
+    //结构化的日志记录
+    pub log_entries: Vec<LogEntry>,
+    //如果执行错误的话,错误信息
+    pub error: Option<String>,
+    //执行耗时(毫秒)
+    pub duration_ms: u64,
+}
+
+///流水线执行结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineExecutionResult {
+    //流水线是否完整执行,标记了 continue_on_error 的步骤失败不影响该值
+    pub success: bool,
+    //最后一个执行成功的步骤的结果
+    pub result: Option<Value>,
+    //第一个失败的步骤下标
+    pub failed_step: Option<usize>,
+    //已执行步骤的结果,失败后停止时不包含后续步骤
+    pub steps: Vec<PipelineStepResult>,
+    //总耗时(毫秒)
+    pub duration_ms: u64,
+}
+
+impl CodeExecutor {
+    /// 按顺序执行流水线,每个步骤的 `result` 作为下一个步骤的参数
+    ///
+    /// 第一个步骤使用 `params` 作为输入;步骤失败时停止执行,除非该步骤标记了 `continue_on_error`,
+    /// 此时后续步骤使用最近一次成功的结果作为输入
+    pub async fn execute_pipeline(
+        steps: &[PipelineStep],
+        params: Option<Value>,
+    ) -> Result<PipelineExecutionResult> {
+        if steps.is_empty() {
+            bail!("流水线至少需要一个步骤");
+        }
+        if steps.len() > MAX_PIPELINE_STEPS {
+            bail!("流水线步骤数 {} 超过上限 {MAX_PIPELINE_STEPS}", steps.len());
+        }
+        info!("开始执行流水线... 步骤数: {}", steps.len());
+
+        let pipeline_start = Instant::now();
+        let mut input = params;
+        let mut step_results = Vec::with_capacity(steps.len());
+        let mut failed_step = None;
+        let mut stopped = false;
+
+        for (index, step) in steps.iter().enumerate() {
+            let step_name = step.name.clone().unwrap_or_else(|| format!("#{index}"));
+            info!("执行流水线步骤 {step_name} [{}]", step.language);
+
+            let start_time = Instant::now();
+            let execution = match step.build_params(input.as_ref()) {
+                Ok(step_params) => {
+                    CodeExecutor::execute_with_options(
+                        &step.code,
+                        &step.language,
+                        Some(step_params),
+                        &step.options.clone().into_run_options(),
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            };
+            let duration_ms = start_time.elapsed().as_millis() as u64;
+
+            let step_result = match execution {
+                Ok(result) => PipelineStepResult {
+                    index,
+                    name: step.name.clone(),
+                    language: step.language.clone(),
+                    success: result.success,
+                    result: result.result,
+                    logs: result.logs,
+                    log_entries: result.log_entries,
+                    error: result.error,
+                    duration_ms,
+                },
+                Err(e) => PipelineStepResult {
+                    index,
+                    name: step.name.clone(),
+                    language: step.language.clone(),
+                    success: false,
+                    result: None,
+                    logs: vec![],
+                    log_entries: vec![],
+                    error: Some(format!("{e:#}")),
+                    duration_ms,
+                },
+            };
+
+            if step_result.success {
+                input = step_result.result.clone();
+            } else {
+                warn!("流水线步骤 {step_name} 执行失败: {:?}", step_result.error);
+                failed_step.get_or_insert(index);
+                stopped = !step.continue_on_error;
+            }
+            step_results.push(step_result);
+
+            if stopped {
+                break;
+            }
+        }
+
+        let result = step_results
+            .iter()
+            .rev()
+            .find(|step| step.success)
+            .and_then(|step| step.result.clone());
+
+        info!("流水线执行完成,失败步骤: {failed_step:?}");
+        Ok(PipelineExecutionResult {
+            success: !stopped,
+            result,
+            failed_step,
+            steps: step_results,
+            duration_ms: pipeline_start.elapsed().as_millis() as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PipelineStep;
+    use serde_json::json;
+
+    fn step(value: serde_json::Value) -> PipelineStep {
+        serde_json::from_value(value).expect("invalid pipeline step")
+    }
+
+    #[test]
+    fn test_build_params_without_mapping() {
+        let s = step(json!({ "language": "js", "code": "", "params": { "b": 2 } }));
+        assert_eq!(
+            s.build_params(Some(&json!({ "a": 1, "b": 1 }))).unwrap(),
+            json!({ "a": 1, "b": 2 })
+        );
+        assert_eq!(
+            s.build_params(Some(&json!([1, 2]))).unwrap(),
+            json!({ "input": [1, 2], "b": 2 })
+        );
+        assert_eq!(s.build_params(None).unwrap(), json!({ "b": 2 }));
+    }
+
+    #[test]
+    fn test_build_params_with_mapping() {
+        let s = step(json!({
+            "language": "ts",
+            "code": "",
+            "param_mapping": { "rows": "/data", "first": "/data/0/name", "all": "" }
+        }));
+        let input = json!({ "data": [{ "name": "a" }] });
+        assert_eq!(
+            s.build_params(Some(&input)).unwrap(),
+            json!({ "rows": [{ "name": "a" }], "first": "a", "all": input })
+        );
+        assert!(s.build_params(Some(&json!({}))).is_err());
+    }
+}
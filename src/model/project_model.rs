@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::model::split_entrypoint;
+
+///多文件项目: 所有文件写入按内容hash缓存的项目目录,只有入口文件会被包装执行,
+///入口文件中的相对导入(JS/TS)和同目录模块(Python)可以解析到项目中的其他文件
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectFiles {
+    //入口文件的相对路径,如 main.py、src/index.ts
+    pub entrypoint: String,
+    //项目文件: 相对路径 → 文件内容,可以包含入口文件
+    pub files: BTreeMap<String, String>,
+}
+
+impl ProjectFiles {
+    /// 根据请求中的 `files` 和 `entrypoint` 构建项目,未设置 `files` 时不是多文件项目;
+    /// `entrypoint` 为 `文件[:函数名]`,只使用其中的文件部分
+    pub fn from_request(
+        files: Option<&BTreeMap<String, String>>,
+        entrypoint: Option<&str>,
+    ) -> Option<Self> {
+        let files = files?;
+        let (entry_file, _) = split_entrypoint(entrypoint, true);
+        Some(Self {
+            entrypoint: entry_file.unwrap_or_default().to_string(),
+            files: files.clone(),
+        })
+    }
+
+    /// 校验入口文件和所有文件的路径: 必须是不含 `..` 的相对路径
+    pub fn validate(&self) -> Result<()> {
+        if self.entrypoint.trim().is_empty() {
+            bail!("多文件项目必须指定入口文件 entrypoint");
+        }
+        for path in std::iter::once(&self.entrypoint).chain(self.files.keys()) {
+            let valid = !path.is_empty()
+                && Path::new(path)
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+            if !valid {
+                bail!("项目文件路径必须是不含 `..` 的相对路径: {path}");
+            }
+        }
+        Ok(())
+    }
+
+    /// 入口文件的代码: `files` 中包含入口文件时使用其内容,否则使用请求中的 `code`
+    pub fn entry_code<'a>(&'a self, code: &'a str) -> &'a str {
+        self.files
+            .get(&self.entrypoint)
+            .map(String::as_str)
+            .unwrap_or(code)
+    }
+
+    /// 入口文件之外的项目文件
+    pub fn other_files(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.files
+            .iter()
+            .filter(|(path, _)| **path != self.entrypoint)
+    }
+
+    /// 缓存key使用的内容,覆盖入口文件和所有文件的路径及内容
+    pub fn cache_key(&self, entry_code: &str) -> String {
+        let mut key = format!("entrypoint:{}\n{entry_code}", self.entrypoint);
+        for (path, content) in self.other_files() {
+            key.push_str(&format!("\0file:{path}:{}\n{content}", content.len()));
+        }
+        key
+    }
+
+    /// 入口文件所在目录中的 Python 模块名(同目录的 `.py` 文件和子目录),这些导入不需要安装依赖
+    pub fn python_local_modules(&self) -> Vec<String> {
+        let entry_dir = Path::new(&self.entrypoint)
+            .parent()
+            .unwrap_or(Path::new(""));
+        let mut modules: Vec<String> = self
+            .files
+            .keys()
+            .filter_map(|path| Path::new(path).strip_prefix(entry_dir).ok())
+            .filter_map(|relative| {
+                let mut components = relative.components();
+                let first = components.next()?.as_os_str().to_str()?;
+                match components.next() {
+                    Some(_) => Some(first.to_string()),
+                    None => first.strip_suffix(".py").map(String::from),
+                }
+            })
+            .collect();
+        modules.sort();
+        modules.dedup();
+        modules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProjectFiles;
+    use std::collections::BTreeMap;
+
+    fn project(entrypoint: &str, files: &[(&str, &str)]) -> ProjectFiles {
+        ProjectFiles {
+            entrypoint: entrypoint.to_string(),
+            files: files
+                .iter()
+                .map(|(path, content)| (path.to_string(), content.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_validate_paths() {
+        assert!(
+            project("main.py", &[("utils/helper.py", "")])
+                .validate()
+                .is_ok()
+        );
+        assert!(project("", &[("main.py", "")]).validate().is_err());
+        assert!(project("../main.py", &[]).validate().is_err());
+        assert!(
+            project("main.py", &[("/etc/passwd", "")])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            project("main.py", &[("a/../../b.py", "")])
+                .validate()
+                .is_err()
+        );
+        assert!(project("./main.py", &[("./b.py", "")]).validate().is_ok());
+    }
+
+    #[test]
+    fn test_entry_code_and_cache_key() {
+        let with_entry = project("main.js", &[("main.js", "entry"), ("a.js", "a")]);
+        assert_eq!(with_entry.entry_code("ignored"), "entry");
+        assert_eq!(with_entry.other_files().count(), 1);
+
+        let without_entry = project("main.js", &[("a.js", "a")]);
+        assert_eq!(without_entry.entry_code("code"), "code");
+
+        // 任何文件的内容变化都会改变缓存key
+        let changed = project("main.js", &[("main.js", "entry"), ("a.js", "b")]);
+        assert_ne!(with_entry.cache_key("entry"), changed.cache_key("entry"));
+    }
+
+    #[test]
+    fn test_python_local_modules() {
+        let project = project(
+            "app/main.py",
+            &[
+                ("app/main.py", ""),
+                ("app/helper.py", ""),
+                ("app/pkg/__init__.py", ""),
+                ("other/ignored.py", ""),
+                ("app/data.json", ""),
+            ],
+        );
+        assert_eq!(
+            project.python_local_modules(),
+            vec!["helper", "main", "pkg"]
+        );
+    }
+}
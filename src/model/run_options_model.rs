@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::{DataFrameOrient, JsRuntime, ProjectFiles, RunOptions, split_entrypoint};
+
+///请求中的执行选项,各个请求(run_*、run_batch、submit_job、流水线步骤、invoke_function)
+///通过 `#[serde(flatten)]` 共用,请求参数中与其他字段平级
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RunOptionsRequest {
+    #[schemars(
+        description = "可选的超时时间(秒),不设置时使用默认超时时间;调用已部署的函数时默认使用部署时设置的超时时间"
+    )]
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+
+    #[schemars(description = "可选的JavaScript运行时: deno(默认) 或 node,仅对JavaScript有效")]
+    #[serde(default)]
+    pub runtime: Option<JsRuntime>,
+
+    #[schemars(description = "可选的Python版本,如 3.11,仅对Python有效,不指定时使用服务默认版本")]
+    #[serde(default)]
+    pub python_version: Option<String>,
+
+    #[schemars(
+        description = "可选的pandas DataFrame结果格式,与 DataFrame.to_dict 的 orient 一致: records(默认)、split、columns、index、list、tight,仅对Python有效"
+    )]
+    #[serde(default)]
+    pub dataframe_orient: Option<DataFrameOrient>,
+
+    #[schemars(
+        description = "可选的参数JSON Schema,执行前校验参数,不符合时不会执行代码并返回每个失败的位置"
+    )]
+    #[serde(default)]
+    pub input_schema: Option<Value>,
+
+    #[schemars(
+        description = "可选的结果JSON Schema,执行后校验handler的返回值,不符合时作为约定违反(contract violation)返回失败"
+    )]
+    #[serde(default)]
+    pub output_schema: Option<Value>,
+
+    #[schemars(
+        description = "可选的多文件项目: 相对路径 → 文件内容,需要同时指定 entrypoint;只有入口文件会被包装执行,其中的相对导入(JS/TS)和同目录模块(Python)可以解析到这些文件"
+    )]
+    #[serde(default)]
+    pub files: Option<BTreeMap<String, String>>,
+
+    #[schemars(
+        description = "可选的入口函数名,如 add,未指定时依次查找 handler、main;多文件项目中为 入口文件[:函数名],如 main.py、src/index.ts:sum"
+    )]
+    #[serde(default)]
+    pub entrypoint: Option<String>,
+
+    #[schemars(
+        description = "可选,执行前检查代码的类型(deno check),有类型错误时不执行并返回诊断信息,仅对TypeScript有效"
+    )]
+    #[serde(default)]
+    pub type_check: bool,
+}
+
+impl RunOptionsRequest {
+    /// 转换为执行选项,多文件项目的 `entrypoint` 拆分为入口文件和函数名
+    pub fn into_run_options(self) -> RunOptions {
+        let (_, entrypoint) = split_entrypoint(self.entrypoint.as_deref(), self.files.is_some());
+        RunOptions {
+            timeout_seconds: self.timeout_seconds,
+            runtime: self.runtime,
+            python_version: self.python_version,
+            dataframe_orient: self.dataframe_orient,
+            project: ProjectFiles::from_request(self.files.as_ref(), self.entrypoint.as_deref()),
+            entrypoint: entrypoint.map(String::from),
+            input_schema: self.input_schema,
+            output_schema: self.output_schema,
+            type_check: self.type_check,
+            ..Default::default()
+        }
+    }
+}
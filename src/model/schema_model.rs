@@ -0,0 +1,315 @@
+use std::fmt;
+
+use anyhow::{Result, anyhow};
+use jsonschema::error::{TypeKind, ValidationErrorKind};
+use jsonschema::{ValidationError, Validator};
+use serde::Serialize;
+use serde_json::Value;
+
+///JSON Schema 校验失败的位置和原因
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaViolation {
+    //失败位置的 JSON Pointer,根节点为空字符串
+    pub path: String,
+    //失败原因
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{path}: {}", self.message)
+    }
+}
+
+/// 将校验失败列表格式化为一行文本
+pub fn format_violations(violations: &[SchemaViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+///编译后的 JSON Schema,同一个请求中多次校验时只需编译一次
+pub struct CompiledSchema(Validator);
+
+impl fmt::Debug for CompiledSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CompiledSchema").finish()
+    }
+}
+
+impl CompiledSchema {
+    /// 编译 JSON Schema,按 `$schema` 选择草案版本(默认 2020-12)并校验 `format`
+    ///
+    /// 无效的 schema 和无法解析的 `$ref`(只支持同一文档内的引用)返回错误
+    pub fn compile(schema: &Value) -> Result<Self> {
+        jsonschema::options()
+            .should_validate_formats(true)
+            .build(schema)
+            .map(Self)
+            .map_err(|e| anyhow!("无效的JSON Schema: {}", e.masked()))
+    }
+
+    /// 校验数据,返回所有校验失败的位置(按位置排序);校验通过时返回空列表
+    pub fn validate(&self, value: &Value) -> Vec<SchemaViolation> {
+        let mut violations: Vec<_> = self.0.iter_errors(value).flat_map(violations).collect();
+        violations.sort_by(|a, b| a.path.cmp(&b.path));
+        violations
+    }
+}
+
+/// 将校验错误转换为失败位置和原因,缺少的属性和额外属性使用属性自身的位置
+fn violations(error: ValidationError<'_>) -> Vec<SchemaViolation> {
+    let path = error.instance_path().as_str().to_string();
+    let violation = |path: String, message: String| SchemaViolation { path, message };
+    let message = match error.kind() {
+        ValidationErrorKind::Required { property } => {
+            let property = property
+                .as_str()
+                .map_or_else(|| property.to_string(), String::from);
+            return vec![violation(
+                child_path(&path, &property),
+                "缺少必需的属性".to_string(),
+            )];
+        }
+        ValidationErrorKind::AdditionalProperties { unexpected } => {
+            return unexpected
+                .iter()
+                .map(|key| violation(child_path(&path, key), "不允许的额外属性".to_string()))
+                .collect();
+        }
+        ValidationErrorKind::Type { kind } => {
+            let expected = match kind {
+                TypeKind::Single(expected) => expected.to_string(),
+                TypeKind::Multiple(expected) => expected
+                    .iter()
+                    .map(|t| t.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            };
+            format!(
+                "类型应为 {expected}, 实际为 {}",
+                json_type(error.instance())
+            )
+        }
+        ValidationErrorKind::Enum { options } => format!("取值应为 {options} 之一"),
+        ValidationErrorKind::Constant { expected_value } => format!("取值应为 {expected_value}"),
+        ValidationErrorKind::FalseSchema => "不允许出现该值".to_string(),
+        ValidationErrorKind::MinLength { limit } => format!("长度不能小于 {limit}"),
+        ValidationErrorKind::MaxLength { limit } => format!("长度不能大于 {limit}"),
+        ValidationErrorKind::Pattern { pattern } => format!("不匹配正则表达式 {pattern}"),
+        ValidationErrorKind::Format { format } => format!("不符合 {format} 格式"),
+        ValidationErrorKind::Minimum { limit } => format!("不能小于 {limit}"),
+        ValidationErrorKind::Maximum { limit } => format!("不能大于 {limit}"),
+        ValidationErrorKind::ExclusiveMinimum { limit } => format!("必须大于 {limit}"),
+        ValidationErrorKind::ExclusiveMaximum { limit } => format!("必须小于 {limit}"),
+        ValidationErrorKind::MultipleOf { multiple_of } => format!("必须是 {multiple_of} 的倍数"),
+        ValidationErrorKind::MinProperties { limit } => format!("属性数量不能少于 {limit}"),
+        ValidationErrorKind::MaxProperties { limit } => format!("属性数量不能多于 {limit}"),
+        ValidationErrorKind::MinItems { limit } => format!("元素数量不能少于 {limit}"),
+        ValidationErrorKind::MaxItems { limit } => format!("元素数量不能多于 {limit}"),
+        ValidationErrorKind::UniqueItems => "元素不能重复".to_string(),
+        ValidationErrorKind::AnyOf { .. } => "不满足 anyOf 中的任何一个 schema".to_string(),
+        ValidationErrorKind::OneOfNotValid { .. } => {
+            "应恰好满足 oneOf 中的一个 schema, 实际满足 0 个".to_string()
+        }
+        ValidationErrorKind::OneOfMultipleValid { .. } => {
+            "应恰好满足 oneOf 中的一个 schema, 实际满足多个".to_string()
+        }
+        ValidationErrorKind::Not { .. } => "不应满足 not 中的 schema".to_string(),
+        ValidationErrorKind::PropertyNames { error } => {
+            format!("属性名不符合 propertyNames: {}", error.masked())
+        }
+        // 其他关键字(如 dependentRequired、contains、unevaluatedProperties)使用校验器的描述
+        other => format!("不满足 {}: {}", other.keyword(), error.masked()),
+    };
+    vec![violation(path, message)]
+}
+
+/// 拼接子节点的 JSON Pointer,转义 `~` 和 `/`
+fn child_path(path: &str, key: &str) -> String {
+    format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompiledSchema, SchemaViolation, format_violations};
+    use serde_json::json;
+
+    fn validate_json_schema(
+        schema: &serde_json::Value,
+        value: &serde_json::Value,
+    ) -> Vec<SchemaViolation> {
+        CompiledSchema::compile(schema)
+            .expect("invalid schema")
+            .validate(value)
+    }
+
+    fn paths(schema: serde_json::Value, value: serde_json::Value) -> Vec<String> {
+        validate_json_schema(&schema, &value)
+            .into_iter()
+            .map(|v| v.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_object_properties() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "age": { "type": "integer", "minimum": 0 },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["name", "age"],
+            "additionalProperties": false
+        });
+        assert!(
+            paths(
+                schema.clone(),
+                json!({ "name": "a", "age": 1, "tags": ["x"] })
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            paths(
+                schema,
+                json!({ "name": "", "age": -1.5, "tags": ["x", 2], "extra": true })
+            ),
+            // 同一位置不满足多个关键字时(类型和最小值)分别报告
+            vec!["/age", "/age", "/extra", "/name", "/tags/1"]
+        );
+    }
+
+    #[test]
+    fn test_validate_required_and_type() {
+        let schema = json!({ "type": "object", "required": ["a/b"] });
+        let violations = validate_json_schema(&schema, &json!({}));
+        assert_eq!(violations[0].path, "/a~1b");
+        assert_eq!(format_violations(&violations), "/a~1b: 缺少必需的属性");
+
+        let violations = validate_json_schema(&schema, &json!([1]));
+        assert_eq!(
+            format_violations(&violations),
+            "/: 类型应为 object, 实际为 array"
+        );
+        assert!(paths(json!({ "type": ["string", "null"] }), json!(null)).is_empty());
+        assert!(paths(json!({ "type": "integer" }), json!(2.0)).is_empty());
+        assert!(!paths(json!({ "type": "integer" }), json!(2.5)).is_empty());
+    }
+
+    #[test]
+    fn test_validate_refs_and_combinators() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "point": { "$ref": "#/$defs/Point" },
+                "mode": { "enum": ["fast", "slow"] },
+                "id": { "anyOf": [{ "type": "string", "pattern": "^id-" }, { "type": "integer" }] }
+            },
+            "$defs": {
+                "Point": {
+                    "type": "object",
+                    "properties": { "x": { "type": "number" } },
+                    "required": ["x"]
+                }
+            }
+        });
+        assert!(
+            paths(
+                schema.clone(),
+                json!({ "point": { "x": 1 }, "mode": "fast", "id": "id-1" })
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            paths(schema, json!({ "point": {}, "mode": "other", "id": "x" })),
+            vec!["/id", "/mode", "/point/x"]
+        );
+        assert_eq!(
+            paths(
+                json!({ "oneOf": [{ "type": "number" }, { "type": "integer" }] }),
+                json!(1)
+            ),
+            vec![""]
+        );
+        assert!(!paths(json!({ "not": { "type": "string" } }), json!("a")).is_empty());
+        assert!(!paths(json!(false), json!(1)).is_empty());
+    }
+
+    #[test]
+    fn test_validate_full_vocabulary() {
+        // format、patternProperties、propertyNames、dependentRequired 和 if/then/else 都会校验
+        let schema = json!({
+            "type": "object",
+            "properties": { "email": { "type": "string", "format": "email" } },
+            "patternProperties": { "^n_": { "type": "number" } },
+            "propertyNames": { "maxLength": 8 },
+            "dependentRequired": { "card": ["cvv"] },
+            "if": { "properties": { "kind": { "const": "user" } }, "required": ["kind"] },
+            "then": { "required": ["email"] }
+        });
+        assert!(
+            paths(
+                schema.clone(),
+                json!({ "email": "a@example.com", "n_1": 1, "kind": "user" })
+            )
+            .is_empty()
+        );
+        let violations = validate_json_schema(
+            &schema,
+            &json!({ "email": "not-an-email", "n_1": "x", "very_long_name": 1, "card": 1 }),
+        );
+        assert_eq!(
+            violations
+                .iter()
+                .map(|v| v.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["", "/cvv", "/email", "/n_1"]
+        );
+        assert_eq!(violations[1].message, "缺少必需的属性");
+        assert_eq!(violations[2].message, "不符合 email 格式");
+        assert_eq!(paths(schema, json!({ "kind": "user" })), vec!["/email"]);
+    }
+
+    #[test]
+    fn test_compile_refs() {
+        // $ref 中的 JSON Pointer 按百分号编码和 ~ 转义解码
+        let schema = json!({
+            "$defs": { "a b": { "type": "string" }, "x/y": { "type": "integer" } },
+            "properties": {
+                "name": { "$ref": "#/$defs/a%20b" },
+                "count": { "$ref": "#/$defs/x~1y" }
+            }
+        });
+        assert_eq!(
+            paths(schema, json!({ "name": 1, "count": "x" })),
+            vec!["/count", "/name"]
+        );
+
+        // 无法解析的引用和无效的 schema 在编译时报错
+        assert!(CompiledSchema::compile(&json!({ "$ref": "#/$defs/missing" })).is_err());
+        assert!(
+            CompiledSchema::compile(&json!({ "$ref": "https://example.com/schema.json" })).is_err()
+        );
+        assert!(CompiledSchema::compile(&json!({ "type": "unknown" })).is_err());
+    }
+}
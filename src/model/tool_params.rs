@@ -3,6 +3,7 @@ use serde_json::Value;
 use std::collections::HashMap;
 
 ///代码运行请求,mcp调用tool工具时传入的参数
+#[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunCodeMessageRequest {
     //js运行参数
@@ -26,6 +27,7 @@ pub struct RunCodeHttpResult {
 }
 
 /// 代码执行参数
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CodeExecutionParams {
     /// 要执行的代码内容
@@ -37,6 +39,7 @@ pub struct CodeExecutionParams {
 }
 
 /// 代码执行结果
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ToolExecutionResult {
     /// 执行日志
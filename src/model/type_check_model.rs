@@ -0,0 +1,40 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// 类型检查时报告的用户代码文件名,多文件项目使用文件的相对路径
+pub const USER_CODE_FILE: &str = "<user code>";
+
+///TypeScript 类型检查的诊断信息,位置对应用户代码中的行和列
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TypeDiagnostic {
+    //文件: 单文件代码为 `<user code>`,多文件项目为相对路径,外部模块为其URL
+    pub file: String,
+    //行号,从1开始
+    pub line: u32,
+    //列号,从1开始
+    pub column: u32,
+    //错误码,如 TS2322;模块解析、语法错误等没有错误码
+    pub code: Option<String>,
+    //错误信息,多行时包含详细的原因
+    pub message: String,
+}
+
+impl fmt::Display for TypeDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{} - ", self.file, self.line, self.column)?;
+        if let Some(code) = &self.code {
+            write!(f, "{code}: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// 将诊断信息格式化为文本,每个诊断信息一行
+pub fn format_diagnostics(diagnostics: &[TypeDiagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,6 @@
+mod node_dependencies;
+#[allow(clippy::module_inception)]
+mod node_runner;
+
+pub use node_dependencies::parse_node_imports;
+pub use node_runner::NodeRunner;
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use log::debug;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// 静态 import/export ... from 语句,以及无绑定的 import 'x'
+static STATIC_IMPORT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?:^|[;\s])(?:import|export)\s+(?:[^'";]*?\s+from\s+)?['"]([^'"]+)['"]"#)
+        .expect("invalid static import regex")
+});
+
+// require('x') 和动态 import('x')
+static CALL_IMPORT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\b(?:require|import)\s*\(\s*['"]([^'"]+)['"]\s*\)"#)
+        .expect("invalid require regex")
+});
+
+// Node.js 内置模块列表,不需要通过 npm 安装
+static NODE_BUILTIN_MODULES: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "domain",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "sys",
+    "test",
+    "timers",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+/// 解析 JavaScript 代码中的裸模块导入(npm 包),返回需要安装的包名列表
+///
+/// 相对路径、绝对路径、带协议的导入(`node:`、`npm:`、`https:` 等)和 Node.js 内置模块会被忽略,
+/// 子路径导入会归一化为包名,如 `lodash/fp` -> `lodash`,`@scope/pkg/sub` -> `@scope/pkg`
+pub fn parse_node_imports(code: &str) -> Result<Vec<String>> {
+    let mut packages: Vec<String> = Vec::new();
+
+    let specifiers = STATIC_IMPORT_RE
+        .captures_iter(code)
+        .chain(CALL_IMPORT_RE.captures_iter(code))
+        .filter_map(|captures| captures.get(1).map(|m| m.as_str()));
+
+    for package in specifiers.filter_map(package_name) {
+        if !packages.contains(&package) {
+            packages.push(package);
+        }
+    }
+
+    debug!("解析到的npm依赖: {packages:?}");
+    Ok(packages)
+}
+
+/// 从导入说明符中提取 npm 包名,非 npm 包返回 None
+fn package_name(specifier: &str) -> Option<String> {
+    if specifier.is_empty()
+        || specifier.starts_with('.')
+        || specifier.starts_with('/')
+        || specifier.contains(':')
+    {
+        return None;
+    }
+
+    let mut segments = specifier.split('/');
+    let first = segments.next()?;
+    let package = if first.starts_with('@') {
+        format!("{first}/{}", segments.next()?)
+    } else {
+        first.to_string()
+    };
+
+    if NODE_BUILTIN_MODULES.contains(&package.as_str()) {
+        return None;
+    }
+    Some(package)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_node_imports;
+    use anyhow::Result;
+
+    #[test]
+    fn test_parse_esm_imports() -> Result<()> {
+        let code = r#"
+import _ from 'lodash';
+import { format } from "date-fns/format";
+import {
+    a,
+    b,
+} from '@scope/pkg/sub';
+import 'dotenv/config';
+import fs from 'fs';
+import path from "node:path";
+import local from './local.js';
+import remote from "https://esm.sh/uuid";
+export { c } from 'zod';
+const dynamic = await import('chalk');
+"#;
+        let packages = parse_node_imports(code)?;
+        assert_eq!(
+            packages,
+            vec!["lodash", "date-fns", "@scope/pkg", "dotenv", "zod", "chalk"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_commonjs_requires() -> Result<()> {
+        let code = r#"
+const axios = require('axios');
+const { promisify } = require("util");
+const fsp = require('fs/promises');
+const lodash = require('lodash'), again = require('axios');
+"#;
+        let packages = parse_node_imports(code)?;
+        assert_eq!(packages, vec!["axios", "lodash"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+//通过 node 命令,来运行 js 脚本
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cache::{CodeFileCache, PROJECT_CACHE_DIR},
+    deno_runner::JsRunner,
+    model::{
+        CodeExecutor, CodeScriptExecutionResult, CommandExecutor, ENTRYPOINT_ENV, InvocationContext,
+        JS_HARNESS, JS_SERIALIZER, LanguageScript, ProjectFiles, RunCode, RunOptions,
+        js_function_table, output_with_options,
+    },
+    node_runner::parse_node_imports,
+    signature::ts_function_names,
+};
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use log::{debug, error, info};
+use once_cell::sync::Lazy;
+use tokio::{fs, process::Command, sync::Mutex};
+
+#[derive(Default)]
+pub struct NodeRunner;
+
+//node 项目缓存子目录,脚本和 node_modules 都放在该目录下,保证 CommonJS 和 ESM 都能解析到依赖
+const NODE_PROJECT_DIR: &str = "node";
+
+//定义国内npm加速地址
+const NPM_REGISTRY_ADDRESS: &str = "https://registry.npmmirror.com";
+
+// npm install 不能在同一个目录并发执行
+static NPM_INSTALL_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[async_trait]
+impl RunCode for NodeRunner {
+    async fn run_with_params(
+        &self,
+        code: &str,
+        params: Option<serde_json::Value>,
+        timeout_seconds: Option<u64>,
+    ) -> Result<CodeScriptExecutionResult> {
+        self.run_with_options(code, params, &RunOptions::with_timeout(timeout_seconds))
+            .await
+    }
+
+    async fn run_with_options(
+        &self,
+        code: &str,
+        params: Option<serde_json::Value>,
+        options: &RunOptions,
+    ) -> Result<CodeScriptExecutionResult> {
+        debug!("开始使用Node.js执行脚本...,执行参数: {params:?}");
+
+        let (project_dir, script_path) = match &options.project {
+            Some(project) => self.prepare_project(code, project).await?,
+            None => self.prepare_script(code).await?,
+        };
+
+        let params_json = match params {
+            Some(p) => serde_json::to_string(&p)?,
+            None => "{}".to_string(),
+        };
+
+        let mut execute_command = Command::new("node");
+        execute_command
+            .arg("--max-old-space-size=512")
+            .arg(&script_path)
+            .current_dir(&project_dir)
+            .env("INPUT_JSON", &params_json)
+            .env(ENTRYPOINT_ENV, options.entrypoint.as_deref().unwrap_or_default())
+            .kill_on_drop(true);
+        // 调用上下文和声明的环境变量
+        InvocationContext::new(LanguageScript::Js.id(), options).apply(&mut execute_command);
+
+        info!("执行命令: {:?}", &execute_command);
+
+        let executor = output_with_options(&mut execute_command, options);
+
+        let output = match executor.await {
+            Ok(cmd_result) => match cmd_result {
+                Ok(output) => output,
+                Err(e) => {
+                    error!("Node命令执行失败: {e:?}");
+                    return Err(e.into());
+                }
+            },
+            Err(e) => {
+                error!("Node任务执行异常: {e:?}");
+                return Err(e.into());
+            }
+        };
+        debug!("标准输出:\n{}", String::from_utf8_lossy(&output.stdout));
+        debug!("错误输出:\n{}", String::from_utf8_lossy(&output.stderr));
+
+        CodeExecutor::parse_execution_output(&output.stdout, &output.stderr).await
+    }
+
+    async fn prepare(&self, code: &str, options: &RunOptions) -> Result<()> {
+        match &options.project {
+            Some(project) => self.prepare_project(code, project).await?,
+            None => self.prepare_script(code).await?,
+        };
+        Ok(())
+    }
+
+    fn supports_project(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        "使用Node.js执行JavaScript代码,支持CommonJS require和npm包".to_string()
+    }
+}
+
+impl NodeRunner {
+    /// 生成脚本缓存并安装缺失的npm包,返回项目目录和脚本路径
+    async fn prepare_script(&self, code: &str) -> Result<(PathBuf, PathBuf)> {
+        let project_dir = Self::ensure_node_project().await?;
+
+        // ESM 使用 .mjs, CommonJS 使用 .cjs, 不依赖 package.json 的 type 字段
+        let is_esm = JsRunner::is_esm(code);
+        let hash = CodeFileCache::obtain_script_hash(code);
+        let file_name = format!("{hash}{}", if is_esm { ".mjs" } else { ".cjs" });
+        let script_path = project_dir.join(&file_name);
+
+        if fs::try_exists(&script_path).await.unwrap_or(false) {
+            debug!("从缓存中读取代码:hash值 {:?}", &hash);
+        } else {
+            let wrapped_code = self.prepare_node_code(code, is_esm, true);
+            CodeFileCache::save_cache_sub_file(NODE_PROJECT_DIR, &file_name, &wrapped_code)
+                .await?;
+            debug!("创建脚本缓存:hash值 {:?}", &hash);
+        }
+
+        // 安装代码中导入但本地缓存中还不存在的npm包
+        Self::install_missing_packages(code, &project_dir).await?;
+        Ok((project_dir, script_path))
+    }
+
+    /// 将多文件项目写入 node 项目目录下的子目录(已缓存时直接使用),共用已安装的 node_modules;
+    /// 未提供 package.json 时按入口文件的模块格式生成,保证 `.js` 文件之间的导入方式一致。
+    /// 返回项目目录和包装后的入口文件路径
+    async fn prepare_project(
+        &self,
+        code: &str,
+        project: &ProjectFiles,
+    ) -> Result<(PathBuf, PathBuf)> {
+        let node_dir = Self::ensure_node_project().await?;
+        let is_esm = JsRunner::is_esm(code);
+        let mut project = project.clone();
+        project
+            .files
+            .entry("package.json".to_string())
+            .or_insert_with(|| {
+                let module_type = if is_esm { "module" } else { "commonjs" };
+                format!(r#"{{ "private": true, "type": "{module_type}" }}"#)
+            });
+        let cache = CodeFileCache::save_project_cache(
+            &format!("{NODE_PROJECT_DIR}/{PROJECT_CACHE_DIR}"),
+            &project,
+            code,
+            "node",
+            |c| self.prepare_node_code(c, is_esm, true),
+        )
+        .await?;
+
+        // 安装所有 JavaScript 文件中导入但本地缓存中还不存在的npm包
+        let mut sources = vec![code];
+        sources.extend(
+            project
+                .other_files()
+                .filter(|(path, _)| Self::is_js_file(path))
+                .map(|(_, content)| content.as_str()),
+        );
+        Self::install_missing_packages(&sources.join("\n"), &node_dir).await?;
+        Ok((cache.dir, cache.entry_path))
+    }
+
+    /// 是否为 JavaScript 源文件
+    fn is_js_file(path: &str) -> bool {
+        [".js", ".mjs", ".cjs", ".jsx"]
+            .iter()
+            .any(|suffix| path.ends_with(suffix))
+    }
+
+    /// 准备Node.js代码，根据模块格式选择CommonJS或ESM模板
+    fn prepare_node_code(&self, code: &str, is_esm: bool, show_logs: bool) -> String {
+        let template = if is_esm {
+            include_str!("../templates/node_template_esm.mjs")
+        } else {
+            include_str!("../templates/node_template_cjs.js")
+        };
+
+        template
+            .replace("{{JS_HARNESS}}", JS_HARNESS)
+            .replace("{{JSON_SERIALIZER}}", JS_SERIALIZER)
+            .replace("{{USER_FUNCTIONS}}", &js_function_table(&ts_function_names(code)))
+            .replace("{{USER_CODE}}", code)
+            .replace("{{SHOW_LOGS}}", &show_logs.to_string())
+    }
+
+    /// 确保 node 项目目录及 package.json 存在
+    async fn ensure_node_project() -> Result<PathBuf> {
+        let project_dir = CodeFileCache::ensure_cache_sub_dir(NODE_PROJECT_DIR).await?;
+        let package_json = project_dir.join("package.json");
+        if !fs::try_exists(&package_json).await.unwrap_or(false) {
+            fs::write(
+                &package_json,
+                r#"{ "name": "run-code-node-cache", "private": true }"#,
+            )
+            .await
+            .with_context(|| format!("无法创建 {}", package_json.display()))?;
+        }
+        Ok(project_dir)
+    }
+
+    /// 使用 npm install 安装本地 node_modules 中缺失的包
+    async fn install_missing_packages(code: &str, project_dir: &Path) -> Result<()> {
+        let packages = parse_node_imports(code)?;
+        if packages.is_empty() {
+            return Ok(());
+        }
+
+        let _guard = NPM_INSTALL_LOCK.lock().await;
+
+        let mut missing = Vec::new();
+        for package in packages {
+            let package_dir = project_dir.join("node_modules").join(&package);
+            if !fs::try_exists(&package_dir).await.unwrap_or(false) {
+                missing.push(package);
+            }
+        }
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        info!("正在安装npm依赖: {missing:?}");
+        let mut cmd = Command::new("npm");
+        cmd.arg("install")
+            .arg("--no-audit")
+            .arg("--no-fund")
+            .arg("--registry")
+            .arg(NPM_REGISTRY_ADDRESS)
+            .args(&missing)
+            .current_dir(project_dir)
+            .kill_on_drop(true);
+        info!("npm命令: {cmd:?}");
+
+        match CommandExecutor::with_timeout(cmd.output(), 600).await {
+            Ok(Ok(output)) => {
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    error!(
+                        "安装npm依赖失败，状态码: {}, stderr: {stderr}",
+                        output.status
+                    );
+                    bail!(
+                        "安装npm依赖 {missing:?} 失败，状态码: {}: {}",
+                        output.status,
+                        stderr.trim()
+                    );
+                }
+                Ok(())
+            }
+            Ok(Err(e)) => {
+                error!("安装npm依赖失败: {e:?}");
+                Err(e).context("Failed to install dependencies with npm")
+            }
+            Err(e) => {
+                error!("安装npm依赖超时: {e:?}");
+                Err(e).context("Timed out installing dependencies with npm")
+            }
+        }
+    }
+}
@@ -202,6 +202,20 @@ pub fn parse_import(code: &str) -> Result<Vec<String>> {
     Ok(imports)
 }
 
+/// 依赖声明中的包名,按 PEP 503 规范化(小写,`_`、`.` 视为 `-`),
+/// 如 `Requests[socks]>=2` 为 `requests`,用于判断导入的模块是否已声明
+pub fn requirement_name(requirement: &str) -> String {
+    requirement
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .map(|c| match c {
+            '_' | '.' => '-',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
 // 解析Python代码中的import语句
 fn parse_python_imports(python_code: &str) -> Result<Vec<String>> {
     let input = if python_code.ends_with('\n') {
@@ -276,7 +290,7 @@ fn is_standard_library(module: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_import;
+    use super::{parse_import, requirement_name};
     use anyhow::Result;
     use log::{LevelFilter, info};
     use std::sync::Once;
@@ -286,9 +300,10 @@ mod tests {
 
     fn setup() {
         INIT.call_once(|| {
-            env_logger::builder()
+            let _ = env_logger::builder()
                 .filter_level(LevelFilter::Debug)
-                .init();
+                .is_test(true)
+                .try_init();
         });
     }
 
@@ -403,4 +418,12 @@ def main(args: dict) -> dict:
         assert_eq!(imported_modules, vec!["pandas", "numpy"]);
         Ok(())
     }
+
+    #[test]
+    fn test_requirement_name() {
+        assert_eq!(requirement_name("requests<3"), "requests");
+        assert_eq!(requirement_name("Requests[socks] >= 2"), "requests");
+        assert_eq!(requirement_name("ruamel.yaml==0.18"), "ruamel-yaml");
+        assert_eq!(requirement_name("typing_extensions"), "typing-extensions");
+    }
 }
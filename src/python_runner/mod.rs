@@ -1,5 +1,8 @@
 mod dependencies;
+#[allow(clippy::module_inception)]
 mod python_runner;
+mod python_version;
 
-pub use dependencies::parse_import;
-pub use python_runner::PythonRunner;
+pub use dependencies::{parse_import, requirement_name};
+pub use python_runner::{PYTHON_VERSION_ENV, PythonRunner};
+pub use python_version::{ensure_python_version_installed, installed_python_versions};
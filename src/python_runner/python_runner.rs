@@ -1,19 +1,45 @@
 //通过 uv 命令,来运行 python脚本
 use crate::{
-    cache::CodeFileCache,
-    model::{CodeExecutor, CodeScriptExecutionResult, CommandExecutor, LanguageScript, RunCode},
-    python_runner::parse_import,
+    cache::{CodeFileCache, PROJECT_CACHE_DIR},
+    model::{
+        CodeExecutor, CodeScriptExecutionResult, DATAFRAME_ORIENT_ENV, DEADLINE_ENV,
+        ENTRYPOINT_ENV, InvocationContext, LanguageScript, ProjectFiles, RunCode, RunOptions,
+        output_with_options,
+    },
+    python_runner::{ensure_python_version_installed, parse_import, requirement_name},
 };
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
 use log::{debug, error, info, warn};
-use tokio::process::Command;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::{fs, process::Command};
 
-#[derive(Default)]
-pub struct PythonRunner;
+#[derive(Debug, Clone)]
+pub struct PythonRunner {
+    //默认Python版本,请求未指定版本时使用;为空时由uv自动选择解释器
+    default_python_version: Option<String>,
+}
 
 //定义国内python加速地址: https://mirrors.aliyun.com/pypi/simple
 const PYTHON_ACCELERATION_ADDRESS: &str = "https://mirrors.aliyun.com/pypi/simple";
 
+//默认Python版本的环境变量,如 RUN_CODE_PYTHON_VERSION=3.13
+pub const PYTHON_VERSION_ENV: &str = "RUN_CODE_PYTHON_VERSION";
+
+impl Default for PythonRunner {
+    fn default() -> Self {
+        let default_python_version = std::env::var(PYTHON_VERSION_ENV)
+            .ok()
+            .map(|version| version.trim().to_string())
+            .filter(|version| !version.is_empty());
+        Self {
+            default_python_version,
+        }
+    }
+}
+
+#[async_trait]
 impl RunCode for PythonRunner {
     async fn run_with_params(
         &self,
@@ -21,76 +47,60 @@ impl RunCode for PythonRunner {
         params: Option<serde_json::Value>,
         timeout_seconds: Option<u64>,
     ) -> Result<CodeScriptExecutionResult> {
-        debug!("开始执行Python脚本...,执行参数: {params:?}");
-        // 根据 code ,获取对应的hash, 对用户脚本代码,使用胶水代码处理后,缓存到文件系统里,下次使用如果hash相同,直接使用
-        let hash = CodeFileCache::obtain_code_hash(code);
-        let cache_exist =
-            CodeFileCache::check_code_file_cache_exisht(&hash, &LanguageScript::Python).await;
+        self.run_with_options(code, params, &RunOptions::with_timeout(timeout_seconds))
+            .await
+    }
 
-        let run_code_script_file_tuple = if cache_exist {
-            // 从缓存中读取代码
-            let cache_code =
-                CodeFileCache::get_code_file_cache(&hash, &LanguageScript::Python).await;
-            debug!("从缓存中读取代码:hash值 {:?}", &hash);
-            cache_code?
-        } else {
-            // 分析用户python代码依赖
-            let dependencies = parse_import(code)?;
-            // 准备Python代码，添加日志捕获和handler函数执行逻辑
-            let wrapped_code = self.prepare_python_code(code, true);
-            // 缓存代码
-            CodeFileCache::save_code_file_cache(&hash, &wrapped_code, &LanguageScript::Python)
-                .await?;
+    async fn run_with_options(
+        &self,
+        code: &str,
+        params: Option<serde_json::Value>,
+        options: &RunOptions,
+    ) -> Result<CodeScriptExecutionResult> {
+        self.run_python(code, params, options).await
+    }
 
-            //保存后，获取文件
-            let code_script_file_tuple =
-                CodeFileCache::get_code_file_cache(&hash, &LanguageScript::Python).await?;
-            let run_code_script_file_path = code_script_file_tuple.1.clone();
-            // 按照 uv命令的规范,添加用户脚本所需的依赖
-            // uv add --script example.py 'requests<3' 'rich'  这是添加依赖的参考命令,dependencies 是解析出来的依赖列表
-
-            // 执行添加依赖命令
-            if !dependencies.is_empty() {
-                info!("正在添加依赖: {dependencies:?}");
-                let mut cmd = Command::new("uv");
-                cmd.arg("add")
-                    .arg("--script")
-                    .arg(&run_code_script_file_path);
-                //添加 python加速地址
-                cmd.arg("--default-index").arg(PYTHON_ACCELERATION_ADDRESS);
-
-                // 为每个依赖添加一个参数
-                for dep in &dependencies {
-                    cmd.arg(dep);
-                }
+    async fn prepare(&self, code: &str, options: &RunOptions) -> Result<()> {
+        self.prepare_script(code, self.python_version(options), options)
+            .await?;
+        Ok(())
+    }
 
-                // 打印 cmd 命令,可以直接复制执行的命令字符串
-                let cmd_str = format!("{:?}", &cmd);
-                info!("uv命令字符串: {cmd_str}");
-
-                let cmd_output = match cmd.kill_on_drop(true).output().await {
-                    Ok(output) => output,
-                    Err(e) => {
-                        error!("安装Python依赖失败: {e:?}");
-                        error!("失败的命令: {cmd:?}");
-                        return Err(e).context("Failed to add dependencies with uv");
-                    }
-                };
-
-                let stdout = String::from_utf8_lossy(&cmd_output.stdout).to_string();
-                let stderr = String::from_utf8_lossy(&cmd_output.stderr).to_string();
-                info!("添加依赖结果 - stdout: {stdout}");
-                info!("添加依赖结果 - stderr: {stderr}");
-
-                if !cmd_output.status.success() {
-                    warn!("添加依赖失败，状态码: {}", cmd_output.status);
-                }
-            }
-            debug!("创建脚本缓存:hash值 {:?}", &hash);
-            code_script_file_tuple
-        };
+    fn supports_project(&self) -> bool {
+        true
+    }
 
-        let temp_path = run_code_script_file_tuple.1;
+    fn description(&self) -> String {
+        "使用uv执行Python代码".to_string()
+    }
+}
+
+impl PythonRunner {
+    /// 指定默认Python版本的运行器,如 `PythonRunner::with_python_version("3.11")`
+    pub fn with_python_version(version: impl Into<String>) -> Self {
+        Self {
+            default_python_version: Some(version.into()),
+        }
+    }
+
+    /// 请求指定的Python版本,未指定时使用默认版本
+    fn python_version<'a>(&'a self, options: &'a RunOptions) -> Option<&'a str> {
+        options
+            .python_version
+            .as_deref()
+            .or(self.default_python_version.as_deref())
+    }
+
+    /// 使用 `uv run` 执行Python脚本,指定版本时通过 `-p` 传给uv
+    async fn run_python(
+        &self,
+        code: &str,
+        params: Option<serde_json::Value>,
+        options: &RunOptions,
+    ) -> Result<CodeScriptExecutionResult> {
+        let python_version = self.python_version(options);
+        debug!("开始执行Python脚本...,执行参数: {params:?},Python版本: {python_version:?}");
+        let temp_path = self.prepare_script(code, python_version, options).await?;
 
         // 将参数序列化为JSON字符串
         let params_json = match params {
@@ -98,19 +108,30 @@ impl RunCode for PythonRunner {
             None => "{}".to_string(),
         };
 
+        // 调用上下文,截止时间与异步handler的超时控制一致
+        let context = InvocationContext::new(LanguageScript::Python.id(), options);
+
         // 使用uv run命令执行Python脚本，提供隔离环境
         let mut execute_command = Command::new("uv");
         //还需要指定国内镜像地址,参考示例: uv run -s -p 3.13 d5ebe48b7d9da8cb835af6ef77b212921f9a44881fb232837b4dcc6ebecf9401.py --default-index https://mirrors.aliyun.com/pypi/simple
+        execute_command.arg("run").arg("-s"); // 明确指定作为脚本运行
+        if let Some(version) = python_version {
+            execute_command.arg("-p").arg(version); // 指定Python解释器版本,如 3.11
+        }
         execute_command
-            .arg("run")
-            .arg("-s") // 明确指定作为脚本运行
-            // .arg("-p")
-            // .arg("3.13") // 指定Python解释器版本3.13
             .arg("--default-index")
             .arg(PYTHON_ACCELERATION_ADDRESS)
             .env("INPUT_JSON", &params_json) // 通过环境变量传递参数
+            .env(ENTRYPOINT_ENV, options.entrypoint.as_deref().unwrap_or_default()) // 入口函数名
+            .env(DEADLINE_ENV, context.deadline.to_string()) // 异步handler的截止时间
+            .env(
+                DATAFRAME_ORIENT_ENV,
+                options.dataframe_orient.unwrap_or_default().as_str(),
+            ) // DataFrame 结果的JSON格式
+            .env("PYTHONUNBUFFERED", "1") // 不缓冲输出,日志可以实时读取
             .arg(&temp_path)
             .kill_on_drop(true);
+        context.apply(&mut execute_command); // 调用上下文和声明的环境变量
 
         info!("执行命令: {:?}", &execute_command);
 
@@ -121,10 +142,7 @@ impl RunCode for PythonRunner {
         //     .await;
 
         //限制command 的执行超时时间
-        let executor = match timeout_seconds {
-            Some(timeout) => CommandExecutor::with_timeout(execute_command.output(), timeout),
-            None => CommandExecutor::default(execute_command.output()),
-        };
+        let executor = output_with_options(&mut execute_command, options);
 
         let executor_result = executor.await;
         let output = match executor_result {
@@ -149,9 +167,193 @@ impl RunCode for PythonRunner {
         // 解析输出
         CodeExecutor::parse_execution_output(&output.stdout, &output.stderr).await
     }
-}
 
-impl PythonRunner {
+    /// 校验Python版本,生成脚本缓存并添加依赖(已缓存时直接使用),返回脚本路径;
+    /// 多文件项目写入项目目录,返回包装后的入口文件路径
+    async fn prepare_script(
+        &self,
+        code: &str,
+        python_version: Option<&str>,
+        options: &RunOptions,
+    ) -> Result<PathBuf> {
+        if let Some(version) = python_version {
+            ensure_python_version_installed(version).await?;
+        }
+        let declared = &options.dependencies;
+        if let Some(project) = &options.project {
+            return self
+                .prepare_project(code, python_version, declared, project)
+                .await;
+        }
+
+        // 根据 code、Python版本和声明的依赖,获取对应的hash, 对用户脚本代码,使用胶水代码处理后,缓存到文件系统里,下次使用如果hash相同,直接使用
+        // 不同Python版本解析出的依赖可能不同,需要分别缓存
+        let hash =
+            CodeFileCache::obtain_script_hash(&Self::cache_key(code, python_version, declared));
+        let cache_exist =
+            CodeFileCache::check_code_file_cache_exisht(&hash, &LanguageScript::Python).await;
+
+        let run_code_script_file_tuple = if cache_exist {
+            // 从缓存中读取代码
+            let cache_code =
+                CodeFileCache::get_code_file_cache(&hash, &LanguageScript::Python).await;
+            debug!("从缓存中读取代码:hash值 {:?}", &hash);
+            cache_code?
+        } else {
+            // 分析用户python代码依赖
+            let dependencies = parse_import(code)?;
+            // 准备Python代码，添加日志捕获和handler函数执行逻辑
+            let wrapped_code = self.prepare_python_code(code, true);
+            // 缓存代码
+            CodeFileCache::save_code_file_cache(&hash, &wrapped_code, &LanguageScript::Python)
+                .await?;
+
+            //保存后，获取文件
+            let code_script_file_tuple =
+                CodeFileCache::get_code_file_cache(&hash, &LanguageScript::Python).await?;
+            let run_code_script_file_path = code_script_file_tuple.1.clone();
+            Self::add_dependencies(
+                &run_code_script_file_path,
+                declared,
+                dependencies,
+                python_version,
+            )
+            .await?;
+            debug!("创建脚本缓存:hash值 {:?}", &hash);
+            code_script_file_tuple
+        };
+
+        Ok(run_code_script_file_tuple.1)
+    }
+
+    /// 将多文件项目写入项目目录(已缓存时直接使用),依赖从所有 `.py` 文件中解析,
+    /// 项目中的本地模块不作为依赖安装
+    async fn prepare_project(
+        &self,
+        code: &str,
+        python_version: Option<&str>,
+        declared: &[String],
+        project: &ProjectFiles,
+    ) -> Result<PathBuf> {
+        let cache = CodeFileCache::save_project_cache(
+            PROJECT_CACHE_DIR,
+            project,
+            code,
+            &Self::cache_key("python", python_version, declared),
+            |c| self.prepare_python_code(c, true),
+        )
+        .await?;
+        if cache.created {
+            let local_modules = project.python_local_modules();
+            let mut dependencies = parse_import(code)?;
+            for (path, content) in project.other_files() {
+                if path.ends_with(".py") {
+                    dependencies.extend(parse_import(content)?);
+                }
+            }
+            dependencies.retain(|dependency| !local_modules.contains(dependency));
+            dependencies.sort();
+            dependencies.dedup();
+            Self::add_dependencies(&cache.entry_path, declared, dependencies, python_version)
+                .await?;
+        }
+        Ok(cache.entry_path)
+    }
+
+    /// 为新生成的脚本添加依赖: 声明的依赖必须添加成功,失败时删除脚本,避免之后使用缺少依赖的缓存;
+    /// 从导入语句推断的依赖(已声明的包除外)添加失败时只记录警告
+    async fn add_dependencies(
+        script_path: &Path,
+        declared: &[String],
+        inferred: Vec<String>,
+        python_version: Option<&str>,
+    ) -> Result<()> {
+        if let Err(e) = Self::add_script_dependencies(script_path, declared, python_version).await {
+            if let Err(remove_error) = fs::remove_file(script_path).await {
+                warn!("删除脚本 {} 失败: {remove_error}", script_path.display());
+            }
+            return Err(e).context("添加声明的依赖失败");
+        }
+        let declared_names: HashSet<String> =
+            declared.iter().map(|dep| requirement_name(dep)).collect();
+        let inferred: Vec<String> = inferred
+            .into_iter()
+            .filter(|dep| !declared_names.contains(&requirement_name(dep)))
+            .collect();
+        if let Err(e) = Self::add_script_dependencies(script_path, &inferred, python_version).await
+        {
+            warn!("添加依赖失败: {e:#}");
+        }
+        Ok(())
+    }
+
+    /// 按照 uv 的规范为脚本添加依赖,如 `uv add --script example.py 'requests<3' 'rich'`
+    async fn add_script_dependencies(
+        script_path: &Path,
+        dependencies: &[String],
+        python_version: Option<&str>,
+    ) -> Result<()> {
+        if dependencies.is_empty() {
+            return Ok(());
+        }
+        info!("正在添加依赖: {dependencies:?}");
+        let mut cmd = Command::new("uv");
+        cmd.arg("add")
+            .arg("--script")
+            .arg(script_path);
+        if let Some(version) = python_version {
+            cmd.arg("-p").arg(version);
+        }
+        //添加 python加速地址
+        cmd.arg("--default-index").arg(PYTHON_ACCELERATION_ADDRESS);
+
+        // 为每个依赖添加一个参数
+        for dep in dependencies {
+            cmd.arg(dep);
+        }
+
+        // 打印 cmd 命令,可以直接复制执行的命令字符串
+        let cmd_str = format!("{:?}", &cmd);
+        info!("uv命令字符串: {cmd_str}");
+
+        let cmd_output = match cmd.kill_on_drop(true).output().await {
+            Ok(output) => output,
+            Err(e) => {
+                error!("安装Python依赖失败: {e:?}");
+                error!("失败的命令: {cmd:?}");
+                return Err(e).with_context(|| {
+                    format!("Failed to add dependencies {dependencies:?} with uv")
+                });
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&cmd_output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&cmd_output.stderr).to_string();
+        info!("添加依赖结果 - stdout: {stdout}");
+        info!("添加依赖结果 - stderr: {stderr}");
+
+        if !cmd_output.status.success() {
+            bail!(
+                "添加依赖 {dependencies:?} 失败，状态码: {}: {}",
+                cmd_output.status,
+                stderr.trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// 计算缓存key使用的内容: 代码、指定的Python版本和声明的依赖
+    fn cache_key(code: &str, python_version: Option<&str>, dependencies: &[String]) -> String {
+        let mut key = code.to_string();
+        if let Some(version) = python_version {
+            key.push_str(&format!("\n# python-version: {version}"));
+        }
+        if !dependencies.is_empty() {
+            key.push_str(&format!("\n# dependencies: {}", dependencies.join(", ")));
+        }
+        key
+    }
+
     /// 准备Python代码，添加日志捕获和handler函数执行逻辑
     fn prepare_python_code(&self, code: &str, show_logs: bool) -> String {
         let show_logs_value = if show_logs { "True" } else { "False" };
@@ -0,0 +1,135 @@
+use anyhow::{Result, bail};
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tokio::{process::Command, sync::RwLock};
+
+use crate::model::CommandExecutor;
+
+// 允许的Python版本格式: 3 / 3.11 / 3.11.9
+static VERSION_FORMAT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\d+(\.\d+){0,2}$").expect("invalid version format regex"));
+
+// uv python list 输出的每一行,如: cpython-3.13.1-linux-x86_64-gnu    /path/to/python3.13
+static UV_PYTHON_LIST_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^\s*[A-Za-z]+-(\d+\.\d+\.\d+)\S*\s").expect("invalid uv python list regex")
+});
+
+// 已安装的Python版本缓存,避免每次执行都调用 uv python list
+static INSTALLED_VERSIONS: Lazy<RwLock<Option<Vec<String>>>> = Lazy::new(|| RwLock::new(None));
+
+/// 校验Python版本格式,只允许数字版本号,如 `3.11`
+pub(crate) fn validate_version_format(version: &str) -> Result<()> {
+    if !VERSION_FORMAT_RE.is_match(version) {
+        bail!("无效的Python版本: {version}, 示例: 3.11 或 3.13.1");
+    }
+    Ok(())
+}
+
+/// 解析 `uv python list --only-installed` 的输出,返回去重后的版本号列表
+pub(crate) fn parse_installed_versions(output: &str) -> Vec<String> {
+    let mut versions: Vec<String> = Vec::new();
+    for captures in UV_PYTHON_LIST_RE.captures_iter(output) {
+        let version = captures[1].to_string();
+        if !versions.contains(&version) {
+            versions.push(version);
+        }
+    }
+    versions
+}
+
+/// 请求的版本是否与已安装的版本匹配,`3.11` 可以匹配 `3.11.9`
+pub(crate) fn version_matches(requested: &str, installed: &str) -> bool {
+    installed == requested
+        || installed
+            .strip_prefix(requested)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// 获取uv可用的已安装Python版本,refresh 为 true 时重新查询
+pub async fn installed_python_versions(refresh: bool) -> Result<Vec<String>> {
+    let cached = if refresh {
+        None
+    } else {
+        INSTALLED_VERSIONS.read().await.clone()
+    };
+    if let Some(versions) = cached {
+        return Ok(versions);
+    }
+
+    let mut cmd = Command::new("uv");
+    cmd.arg("python")
+        .arg("list")
+        .arg("--only-installed")
+        .kill_on_drop(true);
+    let output = CommandExecutor::with_timeout(cmd.output(), 30).await??;
+    if !output.status.success() {
+        bail!(
+            "获取已安装的Python版本失败: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let versions = parse_installed_versions(&String::from_utf8_lossy(&output.stdout));
+    debug!("已安装的Python版本: {versions:?}");
+    *INSTALLED_VERSIONS.write().await = Some(versions.clone());
+    Ok(versions)
+}
+
+/// 确认请求的Python版本已安装,缓存中没有时会重新查询一次(可能刚通过预热安装)
+pub async fn ensure_python_version_installed(version: &str) -> Result<()> {
+    validate_version_format(version)?;
+
+    let installed = installed_python_versions(false).await?;
+    if installed.iter().any(|v| version_matches(version, v)) {
+        return Ok(());
+    }
+
+    let installed = installed_python_versions(true).await?;
+    if installed.iter().any(|v| version_matches(version, v)) {
+        info!("Python {version} 已安装");
+        return Ok(());
+    }
+    bail!(
+        "Python {version} 未安装, 已安装的版本: {}",
+        installed.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_version_format() {
+        assert!(validate_version_format("3").is_ok());
+        assert!(validate_version_format("3.11").is_ok());
+        assert!(validate_version_format("3.13.1").is_ok());
+        assert!(validate_version_format("").is_err());
+        assert!(validate_version_format("3.11 --help").is_err());
+        assert!(validate_version_format("python3").is_err());
+    }
+
+    #[test]
+    fn test_parse_installed_versions() {
+        let output = "\
+cpython-3.13.1-linux-x86_64-gnu     /root/.local/share/uv/python/cpython-3.13.1-linux-x86_64-gnu/bin/python3.13
+cpython-3.13.1+freethreaded-linux-x86_64-gnu    /root/.local/share/uv/python/cpython-3.13.1+freethreaded-linux-x86_64-gnu/bin/python3.13t
+cpython-3.11.2-linux-x86_64-gnu     /usr/bin/python3.11
+pypy-3.10.14-linux-x86_64-gnu       /opt/pypy/bin/pypy3
+";
+        assert_eq!(
+            parse_installed_versions(output),
+            vec!["3.13.1", "3.11.2", "3.10.14"]
+        );
+    }
+
+    #[test]
+    fn test_version_matches() {
+        assert!(version_matches("3.11", "3.11.2"));
+        assert!(version_matches("3.11.2", "3.11.2"));
+        assert!(version_matches("3", "3.13.1"));
+        assert!(!version_matches("3.1", "3.11.2"));
+        assert!(!version_matches("3.12", "3.11.2"));
+    }
+}
@@ -0,0 +1,3 @@
+mod runner_registry;
+
+pub use runner_registry::RunnerRegistry;
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use log::info;
+use once_cell::sync::Lazy;
+
+use crate::deno_runner::{JsRunner, TsRunner};
+use crate::go_runner::GoRunner;
+use crate::model::{LanguageScript, RunCode};
+use crate::node_runner::NodeRunner;
+use crate::python_runner::PythonRunner;
+use crate::shell_runner::ShellRunner;
+
+/// 全局运行器注册表,默认注册 js/ts/python/shell/go 以及 node
+static GLOBAL_REGISTRY: Lazy<RwLock<RunnerRegistry>> =
+    Lazy::new(|| RwLock::new(RunnerRegistry::with_defaults()));
+
+///语言运行器注册表,语言标识 -> 运行器
+#[derive(Default, Clone)]
+pub struct RunnerRegistry {
+    runners: BTreeMap<String, Arc<dyn RunCode>>,
+}
+
+impl RunnerRegistry {
+    /// 创建空的注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建注册了默认运行器(JsRunner/TsRunner/PythonRunner/ShellRunner/GoRunner/NodeRunner)的注册表
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(LanguageScript::Js.id(), Arc::new(JsRunner));
+        registry.register(LanguageScript::Ts.id(), Arc::new(TsRunner));
+        registry.register(LanguageScript::Python.id(), Arc::new(PythonRunner::default()));
+        registry.register(LanguageScript::Shell.id(), Arc::new(ShellRunner));
+        registry.register(LanguageScript::Go.id(), Arc::new(GoRunner));
+        registry.register("node", Arc::new(NodeRunner));
+        registry
+    }
+
+    /// 注册运行器,语言标识统一转为小写;已存在同名语言时覆盖,并返回旧的运行器
+    pub fn register(
+        &mut self,
+        language_id: &str,
+        runner: Arc<dyn RunCode>,
+    ) -> Option<Arc<dyn RunCode>> {
+        self.runners.insert(language_id.to_lowercase(), runner)
+    }
+
+    /// 移除运行器
+    pub fn unregister(&mut self, language_id: &str) -> Option<Arc<dyn RunCode>> {
+        self.runners.remove(&language_id.to_lowercase())
+    }
+
+    /// 根据语言标识获取运行器,内置语言的别名(如 javascript/py)也可以识别
+    pub fn get(&self, language_id: &str) -> Option<Arc<dyn RunCode>> {
+        let language_id = language_id.to_lowercase();
+        self.runners
+            .get(&language_id)
+            .or_else(|| {
+                LanguageScript::from_id(&language_id).and_then(|lang| self.runners.get(lang.id()))
+            })
+            .cloned()
+    }
+
+    /// 是否注册了该语言
+    pub fn contains(&self, language_id: &str) -> bool {
+        self.get(language_id).is_some()
+    }
+
+    /// 已注册的语言标识列表(按字母排序)
+    pub fn language_ids(&self) -> Vec<String> {
+        self.runners.keys().cloned().collect()
+    }
+
+    /// 已注册的语言标识及其描述
+    pub fn describe(&self) -> Vec<(String, String)> {
+        self.runners
+            .iter()
+            .map(|(id, runner)| (id.clone(), runner.description()))
+            .collect()
+    }
+
+    /// 获取全局注册表
+    pub fn global() -> &'static RwLock<RunnerRegistry> {
+        &GLOBAL_REGISTRY
+    }
+
+    /// 向全局注册表注册运行器
+    pub fn register_global(language_id: &str, runner: Arc<dyn RunCode>) {
+        info!("注册语言运行器: {language_id}");
+        Self::global()
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .register(language_id, runner);
+    }
+
+    /// 从全局注册表获取运行器
+    pub fn global_runner(language_id: &str) -> Option<Arc<dyn RunCode>> {
+        Self::global()
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(language_id)
+    }
+
+    /// 全局注册表中已注册的语言标识列表
+    pub fn global_language_ids() -> Vec<String> {
+        Self::global()
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .language_ids()
+    }
+
+    /// 全局注册表中已注册的语言标识及其描述
+    pub fn global_describe() -> Vec<(String, String)> {
+        Self::global()
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .describe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::CodeScriptExecutionResult;
+    use anyhow::Result;
+    use async_trait::async_trait;
+
+    struct EchoRunner;
+
+    #[async_trait]
+    impl RunCode for EchoRunner {
+        async fn run_with_params(
+            &self,
+            code: &str,
+            params: Option<serde_json::Value>,
+            _timeout_seconds: Option<u64>,
+        ) -> Result<CodeScriptExecutionResult> {
+            Ok(CodeScriptExecutionResult {
+                result: Some(serde_json::json!({ "code": code, "params": params })),
+                logs: vec![],
+                log_entries: vec![],
+                success: true,
+                error: None,
+            })
+        }
+
+        fn description(&self) -> String {
+            "echo".to_string()
+        }
+    }
+
+    #[test]
+    fn test_default_registrations() {
+        let registry = RunnerRegistry::with_defaults();
+        assert_eq!(
+            registry.language_ids(),
+            vec!["go", "js", "node", "python", "shell", "ts"]
+        );
+        assert!(registry.contains("javascript"));
+        assert!(registry.contains("PY"));
+        assert!(registry.contains("bash"));
+        assert!(registry.contains("golang"));
+        assert!(!registry.contains("ruby"));
+    }
+
+    #[tokio::test]
+    async fn test_register_custom_runner() -> Result<()> {
+        let mut registry = RunnerRegistry::new();
+        assert!(registry.register("Echo", Arc::new(EchoRunner)).is_none());
+        assert_eq!(registry.describe(), vec![("echo".to_string(), "echo".to_string())]);
+
+        let runner = registry.get("echo").expect("echo 运行器应已注册");
+        let result = runner
+            .run_with_params("hello", Some(serde_json::json!({"a": 1})), None)
+            .await?;
+        assert_eq!(result.result.unwrap()["code"], "hello");
+
+        assert!(registry.unregister("echo").is_some());
+        assert!(registry.get("echo").is_none());
+        Ok(())
+    }
+}
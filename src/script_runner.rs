@@ -1,17 +1,17 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
 use anyhow::{Context, Result};
 use clap::Parser;
 use log::{error, info};
 use rmcp::ServiceExt;
 use tokio::io::{stdin, stdout};
 
-mod app_error;
-mod cache;
-mod deno_runner;
-mod mcp;
-mod model;
-mod python_runner;
-
-use mcp::CodeRunnerService;
+use run_code_rmcp::{
+    CodeRunnerService, DEFAULT_FUNCTION_STORE_DIR, FunctionStore, JobManager, JobManagerConfig,
+    job_router,
+};
 
 /// MCP脚本运行器 - 通过MCP协议执行JavaScript、TypeScript和Python代码
 #[derive(Parser)]
@@ -23,6 +23,37 @@ struct Cli {
     /// 启用详细日志输出
     #[arg(short, long)]
     verbose: bool,
+
+    /// 同时在该地址上提供异步任务的REST接口,如 127.0.0.1:8080
+    #[arg(long)]
+    http: Option<SocketAddr>,
+
+    /// 异步任务记录的存储目录
+    #[arg(long, default_value = "/tmp/run_code_jobs")]
+    job_store_dir: PathBuf,
+
+    /// 同时执行的异步任务数上限
+    #[arg(long, default_value_t = 4)]
+    max_concurrent_jobs: usize,
+
+    /// 已结束的异步任务保留时间(小时)
+    #[arg(long, default_value_t = 24)]
+    job_retention_hours: u64,
+
+    /// 已部署函数的存储目录
+    #[arg(long, default_value = DEFAULT_FUNCTION_STORE_DIR)]
+    function_store_dir: PathBuf,
+}
+
+/// 启动异步任务的REST接口
+async fn start_http_server(addr: SocketAddr) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("无法监听地址: {addr}"))?;
+    info!("异步任务REST接口已启动: http://{addr}");
+    axum::serve(listener, job_router())
+        .await
+        .context("REST接口服务出错")
 }
 
 /// 启动MCP服务器
@@ -32,7 +63,7 @@ async fn start_mcp_server(verbose: bool) -> Result<()> {
     }
 
     // 创建服务实例
-    let service = CodeRunnerService::default();
+    let service = CodeRunnerService;
 
     // 使用标准输入输出作为传输方式
     let transport = (stdin(), stdout());
@@ -62,6 +93,22 @@ async fn main() -> Result<()> {
     // 解析命令行参数
     let cli = Cli::parse();
 
+    FunctionStore::init_global(&cli.function_store_dir)?;
+    JobManager::init_global(JobManagerConfig {
+        store_dir: cli.job_store_dir,
+        max_concurrent_jobs: cli.max_concurrent_jobs,
+        retention: Duration::from_secs(cli.job_retention_hours * 60 * 60),
+        ..Default::default()
+    })?;
+
+    if let Some(addr) = cli.http {
+        tokio::spawn(async move {
+            if let Err(err) = start_http_server(addr).await {
+                error!("{err:#}");
+            }
+        });
+    }
+
     // 启动MCP服务
     start_mcp_server(cli.verbose).await
 }
@@ -89,7 +136,7 @@ mod tests {
         // 在单独的任务中启动服务器
         let server_task = tokio::spawn(async move {
             // 创建服务实例
-            let service = CodeRunnerService::default();
+            let service = CodeRunnerService;
             
             // 创建服务Future并pin
             let service_fut = service.serve((server_read, server_write));
@@ -125,7 +172,7 @@ mod tests {
         println!("服务器信息: {:?}", server_info);
         
         // 验证服务器信息
-        assert!(!server_info.unwrap().instructions.is_none(), "服务器应该提供说明");
+        assert!(server_info.unwrap().instructions.is_some(), "服务器应该提供说明");
         
         // 测试执行JavaScript代码
         let js_code = "function handler(input) { return {success: true, message: 'JavaScript测试成功'}; }";
@@ -0,0 +1,4 @@
+#[allow(clippy::module_inception)]
+mod shell_runner;
+
+pub use shell_runner::ShellRunner;
@@ -0,0 +1,256 @@
+//通过 bash 命令,来运行 shell 脚本
+use std::path::PathBuf;
+
+use crate::{
+    cache::CodeFileCache,
+    model::{
+        CHUNK_MARKER, CodeScriptExecutionResult, ENTRYPOINT_ENV, InvocationContext,
+        LanguageScript, LogEntry, RunCode, RunOptions, TokioHeapSize, output_with_options,
+    },
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use log::{debug, error, info};
+use serde_json::Value;
+use tokio::process::Command;
+
+#[derive(Default)]
+pub struct ShellRunner;
+
+#[async_trait]
+impl RunCode for ShellRunner {
+    async fn run_with_params(
+        &self,
+        code: &str,
+        params: Option<Value>,
+        timeout_seconds: Option<u64>,
+    ) -> Result<CodeScriptExecutionResult> {
+        self.run_with_options(code, params, &RunOptions::with_timeout(timeout_seconds))
+            .await
+    }
+
+    async fn run_with_options(
+        &self,
+        code: &str,
+        params: Option<Value>,
+        options: &RunOptions,
+    ) -> Result<CodeScriptExecutionResult> {
+        debug!("开始执行Shell脚本...,执行参数: {params:?}");
+
+        let temp_path = self.prepare_script(code).await?;
+
+        let params = params.unwrap_or_else(|| Value::Object(Default::default()));
+        let params_json = serde_json::to_string(&params)?;
+
+        // 结果通道,handler 将结果JSON写入该文件
+        let result_file = tempfile::NamedTempFile::new().context("无法创建结果通道文件")?;
+
+        let mut execute_command = Command::new("bash");
+        execute_command
+            .arg(&temp_path)
+            .env("INPUT_JSON", &params_json)
+            .env("RUN_CODE_RESULT_FILE", result_file.path())
+            .env(ENTRYPOINT_ENV, options.entrypoint.as_deref().unwrap_or_default())
+            .envs(Self::flatten_params(&params))
+            .kill_on_drop(true);
+        // 调用上下文和声明的环境变量
+        InvocationContext::new(LanguageScript::Shell.id(), options).apply(&mut execute_command);
+
+        // 限制脚本进程的内存
+        TokioHeapSize::default()
+            .with_heap_limit(&mut execute_command)
+            .await;
+
+        info!("执行命令: {:?}", &execute_command);
+
+        let executor = output_with_options(&mut execute_command, options);
+
+        let output = match executor.await {
+            Ok(cmd_result) => match cmd_result {
+                Ok(output) => output,
+                Err(e) => {
+                    error!("Shell命令执行失败: {e:?}");
+                    return Err(e.into());
+                }
+            },
+            Err(e) => {
+                error!("Shell任务执行异常: {e:?}");
+                return Err(e.into());
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        debug!("Shell stdout: {stdout}");
+        debug!("Shell stderr: {stderr}");
+
+        // 标准输出和标准错误都作为日志,数据块除外
+        let mut logs: Vec<String> = stdout
+            .lines()
+            .filter(|line| !line.starts_with(CHUNK_MARKER))
+            .map(String::from)
+            .collect();
+        logs.extend(stderr.lines().map(|line| format!("[stderr] {line}")));
+
+        let result_content = tokio::fs::read_to_string(result_file.path())
+            .await
+            .unwrap_or_default();
+        let result = Self::parse_result(&result_content);
+
+        let error = if output.status.success() {
+            None
+        } else {
+            let last_stderr = stderr.lines().last().unwrap_or_default();
+            Some(format!(
+                "Shell脚本执行失败, 退出码: {:?} {last_stderr}",
+                output.status.code()
+            ))
+        };
+
+        Ok(CodeScriptExecutionResult {
+            result,
+            log_entries: logs.iter().map(|line| LogEntry::from_text(line)).collect(),
+            logs,
+            success: error.is_none(),
+            error,
+        })
+    }
+
+    async fn prepare(&self, code: &str, _options: &RunOptions) -> Result<()> {
+        self.prepare_script(code).await?;
+        Ok(())
+    }
+
+    fn description(&self) -> String {
+        "使用bash执行Shell脚本".to_string()
+    }
+}
+
+impl ShellRunner {
+    /// 生成脚本缓存(已存在时直接使用),返回脚本路径
+    async fn prepare_script(&self, code: &str) -> Result<PathBuf> {
+        let lang = LanguageScript::Shell;
+
+        let hash = CodeFileCache::obtain_script_hash(code);
+        let cache_exist = CodeFileCache::check_code_file_cache_exisht(&hash, &lang).await;
+
+        let run_code_script_file_tuple = if cache_exist {
+            let cache_code = CodeFileCache::get_code_file_cache(&hash, &lang).await;
+            debug!("从缓存中读取代码:hash值 {:?}", &hash);
+            cache_code?
+        } else {
+            let wrapped_code = self.prepare_shell_code(code);
+            CodeFileCache::save_code_file_cache(&hash, &wrapped_code, &lang).await?;
+            let code_script_file_tuple = CodeFileCache::get_code_file_cache(&hash, &lang).await?;
+            debug!("创建脚本缓存:hash值 {:?}", &hash);
+            code_script_file_tuple
+        };
+
+        Ok(run_code_script_file_tuple.1)
+    }
+
+    /// 准备Shell代码，添加参数、结果通道和handler函数执行逻辑
+    fn prepare_shell_code(&self, code: &str) -> String {
+        let template = include_str!("../templates/shell_template.sh");
+
+        template.replace("{{USER_CODE}}", code)
+    }
+
+    /// 解析结果通道中的内容,优先按JSON解析,否则作为字符串
+    fn parse_result(content: &str) -> Option<Value> {
+        let content = content.trim();
+        if content.is_empty() {
+            return None;
+        }
+        match serde_json::from_str::<Value>(content) {
+            Ok(value) => Some(value),
+            Err(_) => Some(Value::String(content.to_string())),
+        }
+    }
+
+    /// 将参数扁平化为 `PARAM_*` 环境变量,如 `{"user": {"name": "a"}}` -> `PARAM_USER_NAME=a`,
+    /// 数组同时提供 JSON 形式和按下标展开的形式(`PARAM_TAGS`、`PARAM_TAGS_0`...)
+    pub(crate) fn flatten_params(params: &Value) -> Vec<(String, String)> {
+        let mut vars = Vec::new();
+        if let Value::Object(map) = params {
+            for (key, value) in map {
+                Self::flatten_value(&format!("PARAM_{}", Self::env_key(key)), value, &mut vars);
+            }
+        }
+        vars
+    }
+
+    fn flatten_value(prefix: &str, value: &Value, vars: &mut Vec<(String, String)>) {
+        match value {
+            Value::Object(map) => {
+                vars.push((prefix.to_string(), value.to_string()));
+                for (key, value) in map {
+                    Self::flatten_value(&format!("{prefix}_{}", Self::env_key(key)), value, vars);
+                }
+            }
+            Value::Array(items) => {
+                vars.push((prefix.to_string(), value.to_string()));
+                for (index, item) in items.iter().enumerate() {
+                    Self::flatten_value(&format!("{prefix}_{index}"), item, vars);
+                }
+            }
+            Value::String(s) => vars.push((prefix.to_string(), s.clone())),
+            Value::Null => vars.push((prefix.to_string(), String::new())),
+            other => vars.push((prefix.to_string(), other.to_string())),
+        }
+    }
+
+    /// 转换为合法的环境变量名: 大写,非字母数字替换为下划线
+    fn env_key(key: &str) -> String {
+        key.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShellRunner;
+    use serde_json::json;
+
+    #[test]
+    fn test_flatten_params() {
+        let params = json!({
+            "name": "User",
+            "count": 3,
+            "user-info": { "city": "Beijing", "active": true },
+            "tags": ["a", "b"],
+            "empty": null
+        });
+        let vars = ShellRunner::flatten_params(&params);
+        let get = |key: &str| {
+            vars.iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+
+        assert_eq!(get("PARAM_NAME"), Some("User"));
+        assert_eq!(get("PARAM_COUNT"), Some("3"));
+        assert_eq!(get("PARAM_USER_INFO_CITY"), Some("Beijing"));
+        assert_eq!(get("PARAM_USER_INFO_ACTIVE"), Some("true"));
+        assert_eq!(get("PARAM_TAGS"), Some(r#"["a","b"]"#));
+        assert_eq!(get("PARAM_TAGS_1"), Some("b"));
+        assert_eq!(get("PARAM_EMPTY"), Some(""));
+    }
+
+    #[test]
+    fn test_parse_result() {
+        assert_eq!(ShellRunner::parse_result("  \n"), None);
+        assert_eq!(
+            ShellRunner::parse_result(r#"{"ok": true}"#),
+            Some(json!({"ok": true}))
+        );
+        assert_eq!(ShellRunner::parse_result("done\n"), Some(json!("done")));
+    }
+}
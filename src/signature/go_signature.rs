@@ -0,0 +1,463 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+use crate::app_error::AppError;
+use crate::model::entrypoint_candidates;
+
+use super::signature_model::{
+    FunctionSignature, Property, balanced, join_description, nullable, object_schema,
+    split_top_level, with_description,
+};
+
+/// 命名类型展开的最大深度,防止递归类型无限展开
+const MAX_TYPE_DEPTH: usize = 8;
+
+static FUNC_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^func\s+([A-Za-z_]\w*)\s*(?:\[[^\]]*\]\s*)?\(").expect("invalid func regex")
+});
+
+static TYPE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^type\s+(\w+)\s*(?:\[[^\]]*\])?\s+(struct\s*\{|[^\n{]+$)")
+        .expect("invalid type regex")
+});
+
+static JSON_TAG_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"json:"([^"]*)""#).expect("invalid json tag regex"));
+
+///代码中声明的类型
+#[derive(Debug, Clone)]
+struct TypeDef {
+    //结构体的字段列表或类型表达式
+    expr: String,
+    description: Option<String>,
+}
+
+///代码中声明的类型,用于展开命名类型
+struct TypeContext {
+    types: HashMap<String, TypeDef>,
+}
+
+/// 包级函数名(不包括方法和模板使用的 main 函数),按声明顺序去重
+pub(crate) fn go_function_names(code: &str) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for caps in FUNC_RE.captures_iter(code) {
+        let name = caps[1].to_string();
+        if name != "main" && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// 查找入口函数: 按模板一致的顺序,每个名称依次匹配原名和首字母大写的名称
+/// (如 `handler` 匹配 `handler` 或 `Handler`);`main` 由模板使用,不能作为入口函数
+pub(crate) fn resolve_go_entrypoint(
+    code: &str,
+    entrypoint: Option<&str>,
+) -> Result<String, AppError> {
+    let available = go_function_names(code);
+    let candidates = entrypoint_candidates(entrypoint);
+    candidates
+        .iter()
+        .flat_map(|name| [name.to_string(), capitalize(name)])
+        .find(|name| available.contains(name))
+        .ok_or_else(|| AppError::HandlerNotFound {
+            entrypoint: candidates.join(" or "),
+            available,
+        })
+}
+
+/// 入口函数是否声明了调用上下文参数(`ctx *InvocationContext`)
+pub(crate) fn go_handler_takes_context(code: &str, handler: &str) -> bool {
+    FUNC_RE
+        .captures_iter(code)
+        .filter(|caps| &caps[1] == handler)
+        .filter_map(|caps| caps.get(0))
+        .filter_map(|found| balanced(code, found.end() - 1))
+        .any(|(params, _)| params.contains("InvocationContext"))
+}
+
+/// 首字母大写
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// 从 Go 代码推断入口函数(默认依次查找 Handler、Main)的参数结构
+pub(crate) fn infer_go_signature(code: &str, entrypoint: Option<&str>) -> FunctionSignature {
+    let Ok(name) = resolve_go_entrypoint(code, entrypoint) else {
+        return FunctionSignature::unknown(None, None);
+    };
+    let Some(found) = FUNC_RE
+        .captures_iter(code)
+        .filter(|caps| caps[1] == name)
+        .find_map(|caps| caps.get(0))
+    else {
+        return FunctionSignature::unknown(None, None);
+    };
+    let handler = Some(name);
+    let description = line_comments_before(code, found.start());
+    let Some((params, _)) = balanced(code, found.end() - 1) else {
+        return FunctionSignature::unknown(handler, description);
+    };
+    let Some(param) = split_top_level(params, &[',']).into_iter().next() else {
+        return FunctionSignature::unknown(handler, description);
+    };
+    // `input Input` 或只有类型的 `Input`
+    let type_expr = match param.split_once(char::is_whitespace) {
+        Some((_, type_expr)) => type_expr.trim(),
+        None => param.as_str(),
+    };
+    let context = TypeContext::collect(code);
+    let input_schema = context.schema(type_expr, &mut vec![]);
+    if input_schema.get("properties").is_none() {
+        return FunctionSignature::unknown(handler, description);
+    }
+    FunctionSignature {
+        handler,
+        description,
+        input_schema,
+        inferred: true,
+    }
+}
+
+impl TypeContext {
+    /// 收集代码中顶层声明的结构体和类型定义
+    fn collect(code: &str) -> Self {
+        let mut types = HashMap::new();
+        for caps in TYPE_RE.captures_iter(code) {
+            let whole = caps.get(0).expect("regex match");
+            let definition = caps.get(2).expect("regex match");
+            let expr = if definition.as_str().starts_with("struct") {
+                match balanced(code, definition.end() - 1) {
+                    Some((body, _)) => format!("struct {{{body}}}"),
+                    None => continue,
+                }
+            } else {
+                strip_line_comment(definition.as_str()).trim().to_string()
+            };
+            types.insert(
+                caps[1].to_string(),
+                TypeDef {
+                    expr,
+                    description: line_comments_before(code, whole.start()),
+                },
+            );
+        }
+        Self { types }
+    }
+
+    /// 将 Go 类型转换为 JSON Schema
+    fn schema(&self, type_expr: &str, stack: &mut Vec<String>) -> Value {
+        let type_expr = type_expr.trim();
+        if let Some(inner) = type_expr.strip_prefix('*') {
+            return nullable(self.schema(inner, stack));
+        }
+        if type_expr == "[]byte" {
+            return json!({ "type": "string", "contentEncoding": "base64" });
+        }
+        if type_expr.starts_with('[') {
+            let Some((_, end)) = balanced(type_expr, 0) else {
+                return json!({ "type": "array" });
+            };
+            return json!({ "type": "array", "items": self.schema(&type_expr[end..], stack) });
+        }
+        if let Some(rest) = type_expr
+            .strip_prefix("map")
+            .filter(|rest| rest.starts_with('['))
+        {
+            let Some((_, end)) = balanced(rest, 0) else {
+                return json!({ "type": "object" });
+            };
+            return json!({ "type": "object", "additionalProperties": self.schema(&rest[end..], stack) });
+        }
+        let struct_body = type_expr
+            .strip_prefix("struct")
+            .map(str::trim_start)
+            .filter(|rest| rest.starts_with('{'));
+        if let Some(rest) = struct_body {
+            return match balanced(rest, 0) {
+                Some((body, _)) => self.struct_schema(body, stack),
+                None => json!({ "type": "object" }),
+            };
+        }
+        match type_expr {
+            "string" => json!({ "type": "string" }),
+            "bool" => json!({ "type": "boolean" }),
+            "int" | "int8" | "int16" | "int32" | "int64" | "uint" | "uint8" | "uint16"
+            | "uint32" | "uint64" | "byte" | "rune" | "uintptr" => json!({ "type": "integer" }),
+            "float32" | "float64" | "json.Number" => json!({ "type": "number" }),
+            "time.Time" => json!({ "type": "string", "format": "date-time" }),
+            _ => self.named_schema(type_expr, stack),
+        }
+    }
+
+    /// 展开代码中声明的命名类型,未知类型不做限制
+    fn named_schema(&self, name: &str, stack: &mut Vec<String>) -> Value {
+        let name = name.split('[').next().unwrap_or(name);
+        let Some(definition) = self.types.get(name) else {
+            return json!({});
+        };
+        if stack.len() >= MAX_TYPE_DEPTH || stack.iter().any(|n| n == name) {
+            return json!({ "type": "object" });
+        }
+        stack.push(name.to_string());
+        let schema = self.schema(&definition.expr, stack);
+        stack.pop();
+        with_description(schema, definition.description.as_deref())
+    }
+
+    /// 结构体字段: 使用 json 标签中的名称,omitempty 或指针字段为可选
+    fn struct_schema(&self, body: &str, stack: &mut Vec<String>) -> Value {
+        let mut properties: Vec<Property> = vec![];
+        for (field, comment) in struct_fields(body) {
+            let tag = field
+                .find('`')
+                .and_then(|start| {
+                    let end = field[start + 1..].find('`')? + start + 1;
+                    Some(&field[start + 1..end])
+                })
+                .unwrap_or("");
+            let declaration = field.split('`').next().unwrap_or(&field).trim();
+            let json_tag = JSON_TAG_RE.captures(tag).map(|caps| caps[1].to_string());
+            let mut tag_parts = json_tag.as_deref().unwrap_or("").split(',');
+            let tag_name = tag_parts.next().unwrap_or("");
+            let omitempty = tag_parts.any(|option| option == "omitempty" || option == "omitzero");
+            if tag_name == "-" && json_tag.as_deref() == Some("-") {
+                continue;
+            }
+            let Some((names, type_expr)) = split_field(declaration) else {
+                // 嵌入字段,展开其属性
+                let embedded = self.schema(declaration.trim_start_matches('*'), stack);
+                if let Some(embedded_properties) =
+                    embedded.get("properties").and_then(Value::as_object)
+                {
+                    let required = embedded
+                        .get("required")
+                        .and_then(Value::as_array)
+                        .cloned()
+                        .unwrap_or_default();
+                    for (name, schema) in embedded_properties {
+                        properties.retain(|existing| &existing.name != name);
+                        properties.push(Property {
+                            name: name.clone(),
+                            schema: schema.clone(),
+                            required: required.iter().any(|r| r.as_str() == Some(name)),
+                            description: None,
+                        });
+                    }
+                }
+                continue;
+            };
+            for name in names {
+                // 未导出的字段不参与 JSON 解码
+                if !name.starts_with(|c: char| c.is_uppercase()) {
+                    continue;
+                }
+                let property_name = if tag_name.is_empty() {
+                    name.clone()
+                } else {
+                    tag_name.to_string()
+                };
+                properties.retain(|existing| existing.name != property_name);
+                properties.push(Property {
+                    name: property_name,
+                    schema: self.schema(&type_expr, stack),
+                    required: !omitempty && !type_expr.starts_with('*'),
+                    description: comment.clone(),
+                });
+            }
+        }
+        object_schema(properties)
+    }
+}
+
+/// 拆分字段声明中的字段名和类型,嵌入字段返回 None
+fn split_field(declaration: &str) -> Option<(Vec<String>, String)> {
+    let mut names = vec![];
+    let mut rest = declaration.trim();
+    loop {
+        let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_'))?;
+        if end == 0 {
+            return None;
+        }
+        names.push(rest[..end].to_string());
+        let after = rest[end..].trim_start();
+        match after.strip_prefix(',') {
+            Some(next) => rest = next.trim_start(),
+            None if after.is_empty() || after.starts_with('.') => return None,
+            None => return Some((names, after.to_string())),
+        }
+    }
+}
+
+/// 拆分结构体的字段,返回字段声明和前置/行尾注释
+fn struct_fields(body: &str) -> Vec<(String, Option<String>)> {
+    let mut fields = vec![];
+    let mut pending: Vec<&str> = vec![];
+    let mut current = String::new();
+    let mut depth = 0i32;
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if depth == 0 {
+            if let Some(comment) = trimmed.strip_prefix("//") {
+                pending.push(comment);
+                continue;
+            }
+            if trimmed.is_empty() {
+                pending.clear();
+                continue;
+            }
+        }
+        let code = strip_line_comment(trimmed);
+        let trailing = trimmed[code.len()..]
+            .trim_start()
+            .trim_start_matches('/')
+            .trim();
+        current.push_str(code);
+        current.push('\n');
+        depth += code.matches('{').count() as i32 - code.matches('}').count() as i32;
+        if depth > 0 {
+            continue;
+        }
+        depth = 0;
+        let description = if trailing.is_empty() {
+            join_description(&pending)
+        } else {
+            join_description(&[trailing])
+        };
+        for declaration in std::mem::take(&mut current).split(';') {
+            if !declaration.trim().is_empty() {
+                fields.push((declaration.trim().to_string(), description.clone()));
+            }
+        }
+        pending.clear();
+    }
+    fields
+}
+
+/// 去掉行尾的 `//` 注释,忽略字符串和标签中的 `//`
+fn strip_line_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut previous = ' ';
+    for (index, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '`' => quote = Some(c),
+                '/' if previous == '/' => return line[..index - 1].trim_end(),
+                _ => {}
+            },
+        }
+        previous = c;
+    }
+    line
+}
+
+/// 紧挨在 `position` 之前的连续 `//` 注释
+fn line_comments_before(code: &str, position: usize) -> Option<String> {
+    let lines = code[..position]
+        .lines()
+        .rev()
+        .map(str::trim)
+        .take_while(|line| line.starts_with("//"))
+        .map(|line| line.trim_start_matches('/'))
+        .collect::<Vec<_>>();
+    join_description(&lines.into_iter().rev().collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{go_handler_takes_context, infer_go_signature, resolve_go_entrypoint};
+    use serde_json::json;
+
+    #[test]
+    fn test_struct_handler() {
+        let code = r#"
+package main
+
+// Base 公共字段
+type Base struct {
+	ID int `json:"id"`
+}
+
+// Input 是问候参数
+type Input struct {
+	Base
+	// 用户名
+	Name  string   `json:"name"`
+	Times *int     `json:"times"` // 重复次数
+	Tags  []string `json:"tags,omitempty"`
+	Extra map[string]float64 `json:"extra,omitempty"`
+	Skip  string   `json:"-"`
+	internal string
+}
+
+// Handler 生成问候语
+func Handler(input Input) (map[string]any, error) {
+	return nil, nil
+}
+"#;
+        let signature = infer_go_signature(code, None);
+        assert_eq!(signature.handler.as_deref(), Some("Handler"));
+        assert_eq!(signature.description.as_deref(), Some("Handler 生成问候语"));
+        assert_eq!(
+            signature.input_schema,
+            json!({
+                "type": "object",
+                "description": "Input 是问候参数",
+                "properties": {
+                    "id": { "type": "integer" },
+                    "name": { "type": "string", "description": "用户名" },
+                    "times": { "type": ["integer", "null"], "description": "重复次数" },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "extra": { "type": "object", "additionalProperties": { "type": "number" } }
+                },
+                "required": ["id", "name"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_map_handler() {
+        let code = "func Handler(input map[string]any) (any, error) {\n\treturn input, nil\n}";
+        let signature = infer_go_signature(code, None);
+        assert!(!signature.inferred);
+        assert_eq!(signature.input_schema, json!({ "type": "object" }));
+    }
+
+    #[test]
+    fn test_resolve_entrypoint() {
+        let code = "func helper() {}\n\nfunc Add(input Pair) (int, error) {\n\treturn 0, nil\n}\n\nfunc (p Pair) Sum() int { return 0 }\n";
+        assert_eq!(resolve_go_entrypoint(code, Some("Add")).ok().as_deref(), Some("Add"));
+        assert_eq!(resolve_go_entrypoint(code, Some("add")).ok().as_deref(), Some("Add"));
+        let error = resolve_go_entrypoint(code, None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "HandlerNotFound: no function named handler or main; available functions: helper, Add"
+        );
+    }
+
+    #[test]
+    fn test_handler_takes_context() {
+        let code = "func Handler(input Input, ctx *InvocationContext) (any, error) {\n\treturn nil, nil\n}\n\nfunc Main(input Input) (any, error) {\n\treturn nil, nil\n}\n";
+        assert!(go_handler_takes_context(code, "Handler"));
+        assert!(!go_handler_takes_context(code, "Main"));
+        assert!(!go_handler_takes_context(code, "Missing"));
+    }
+
+    #[test]
+    fn test_malformed_struct_field() {
+        // 以 struct、map 开头的类型名不会被当作匿名结构体或 map 解析
+        let code = "type Input struct {\n\tNested structé { A int }\n\tItems mapping\n}\n\nfunc Handler(input Input) (any, error) {\n\treturn nil, nil\n}\n";
+        let signature = infer_go_signature(code, None);
+        assert_eq!(signature.handler.as_deref(), Some("Handler"));
+        assert_eq!(signature.input_schema["type"], "object");
+    }
+}
@@ -0,0 +1,33 @@
+mod go_signature;
+mod python_signature;
+mod signature_model;
+mod ts_signature;
+
+pub use signature_model::FunctionSignature;
+pub(crate) use go_signature::{go_handler_takes_context, resolve_go_entrypoint};
+pub(crate) use ts_signature::ts_function_names;
+
+use crate::model::LanguageScript;
+
+/// 静态分析代码中入口函数的参数类型,推断参数的 JSON Schema 和函数描述;
+/// 未指定 `entrypoint` 时与模板一致依次查找 handler、main
+///
+/// 支持 TypeScript 类型注解/interface、JavaScript 的 JSDoc、Python 的类型提示
+/// (TypedDict、pydantic 模型、dataclass)和文档字符串、Go 的结构体;
+/// 其他语言或无法推断时返回不限制属性的 object schema
+pub fn infer_signature(
+    language_id: &str,
+    code: &str,
+    entrypoint: Option<&str>,
+) -> FunctionSignature {
+    let language_id = language_id.to_lowercase();
+    match LanguageScript::from_id(&language_id) {
+        Some(LanguageScript::Js | LanguageScript::Ts) => {
+            ts_signature::infer_ts_signature(code, entrypoint)
+        }
+        Some(LanguageScript::Python) => python_signature::infer_python_signature(code, entrypoint),
+        Some(LanguageScript::Go) => go_signature::infer_go_signature(code, entrypoint),
+        _ if language_id == "node" => ts_signature::infer_ts_signature(code, entrypoint),
+        _ => FunctionSignature::unknown(None, None),
+    }
+}
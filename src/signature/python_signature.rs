@@ -0,0 +1,948 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+use crate::model::entrypoint_candidates;
+
+use super::signature_model::{
+    FunctionSignature, Property, balanced, describe_properties, enum_schema, join_description,
+    literal_value, nullable, object_schema, split_top_level, unquote, with_description,
+};
+
+/// 命名类型展开的最大深度,防止递归类型无限展开
+const MAX_TYPE_DEPTH: usize = 8;
+
+static FUNCTION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^(?:async\s+)?def\s+([A-Za-z_]\w*)\s*\(").expect("invalid def regex")
+});
+
+static CLASS_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^class\s+(\w+)\s*(?:\(([^)]*)\))?\s*:").expect("invalid class regex")
+});
+
+static DOC_ENTRY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\*{0,2}([\w.]+)\s*(?:\(([^)]*)\))?\s*:\s*(.*)$").expect("invalid arg regex")
+});
+
+static SPHINX_PARAM_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^:param\s+(?:([^:]+)\s+)?([\w.]+)\s*:\s*(.*)$").expect("invalid param regex")
+});
+
+static GENERIC_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)^([\w.]+)\s*\[(.*)\]$").expect("invalid generic regex"));
+
+/// 文档字符串中 Args 段落的标题
+const ARGS_SECTIONS: &[&str] = &[
+    "args",
+    "arguments",
+    "parameters",
+    "params",
+    "attributes",
+    "参数",
+    "属性",
+];
+
+/// 文档字符串中其他段落的标题,出现时结束 Args 段落和描述
+const OTHER_SECTIONS: &[&str] = &[
+    "returns",
+    "return",
+    "yields",
+    "raises",
+    "example",
+    "examples",
+    "note",
+    "notes",
+    "返回",
+    "返回值",
+    "示例",
+];
+
+///类的种类,决定字段是否必填
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClassKind {
+    //TypedDict,total=False 时字段默认可选
+    TypedDict { total: bool },
+    //pydantic 模型或 dataclass,有默认值的字段可选
+    Model,
+    //Enum,成员值组成枚举
+    Enum,
+}
+
+///代码中声明的类
+#[derive(Debug, Clone)]
+struct ClassDef {
+    kind: Option<ClassKind>,
+    bases: Vec<String>,
+    body: String,
+    docstring: Option<String>,
+}
+
+///文档字符串中的一个参数/属性说明
+#[derive(Debug, Clone)]
+struct DocEntry {
+    name: String,
+    type_expr: Option<String>,
+    optional: bool,
+    description: Option<String>,
+}
+
+///解析后的文档字符串
+#[derive(Debug, Default)]
+struct Docstring {
+    description: Option<String>,
+    entries: Vec<DocEntry>,
+}
+
+///代码中声明的类,用于展开命名类型
+struct TypeContext {
+    classes: HashMap<String, ClassDef>,
+}
+
+/// 从 Python 代码推断入口函数(默认依次查找 handler、main)的参数结构
+pub(crate) fn infer_python_signature(code: &str, entrypoint: Option<&str>) -> FunctionSignature {
+    let Some((name, params_start)) = find_handler(code, entrypoint) else {
+        return FunctionSignature::unknown(None, None);
+    };
+    let context = TypeContext::collect(code);
+    let Some((params_text, params_end)) = balanced(code, params_start) else {
+        return FunctionSignature::unknown(Some(name), None);
+    };
+    let docstring = function_docstring(&code[params_end..]).map(|doc| parse_docstring(&doc));
+    let description = docstring.as_ref().and_then(|doc| doc.description.clone());
+
+    let param = split_top_level(params_text, &[','])
+        .into_iter()
+        .find(|param| param != "self" && !param.starts_with('*') && param != "/");
+    let (param_name, annotation) = match param.as_deref().map(split_param) {
+        Some((param_name, annotation)) => (Some(param_name), annotation),
+        None => (None, None),
+    };
+    let entries = docstring
+        .map(|doc| doc.entries)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|mut entry| {
+            // `args.a` 形式的说明去掉参数名前缀
+            if let Some((prefix, property)) = entry.name.split_once('.') {
+                if Some(prefix) != param_name.as_deref() || property.contains('.') {
+                    return None;
+                }
+                entry.name = property.to_string();
+            }
+            (Some(entry.name.as_str()) != param_name.as_deref()).then_some(entry)
+        })
+        .collect::<Vec<_>>();
+
+    let schema = annotation
+        .map(|annotation| context.schema(&annotation, &mut vec![]))
+        .filter(|schema| schema.get("properties").is_some());
+    let schema = match schema {
+        Some(mut schema) => {
+            describe_properties(
+                &mut schema,
+                entries
+                    .iter()
+                    .filter_map(|entry| Some((entry.name.as_str(), entry.description.as_deref()?))),
+            );
+            Some(schema)
+        }
+        None if !entries.is_empty() => Some(entries_schema(&entries, &context)),
+        None => None,
+    };
+    match schema {
+        Some(input_schema) => FunctionSignature {
+            handler: Some(name),
+            description,
+            input_schema,
+            inferred: true,
+        },
+        None => FunctionSignature::unknown(Some(name), description),
+    }
+}
+
+/// 查找顶层定义的入口函数,与模板的查找顺序一致;返回函数名和参数列表开括号的位置
+fn find_handler(code: &str, entrypoint: Option<&str>) -> Option<(String, usize)> {
+    let candidates = FUNCTION_RE
+        .captures_iter(code)
+        .filter_map(|caps| Some((caps[1].to_string(), caps.get(0)?.end() - 1)))
+        .collect::<Vec<_>>();
+    entrypoint_candidates(entrypoint)
+        .into_iter()
+        .find_map(|wanted| candidates.iter().find(|(name, _)| name == wanted).cloned())
+}
+
+/// 拆分 `name: annotation = default` 中的参数名和类型注解
+fn split_param(param: &str) -> (String, Option<String>) {
+    let param = split_default(param).0;
+    match param.split_once(':') {
+        Some((name, annotation)) => (name.trim().to_string(), Some(annotation.trim().to_string())),
+        None => (param.trim().to_string(), None),
+    }
+}
+
+/// 拆分顶层的 `=` 默认值
+fn split_default(text: &str) -> (&str, Option<&str>) {
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let chars = text.char_indices().collect::<Vec<_>>();
+    for (position, (index, c)) in chars.iter().enumerate() {
+        if let Some(q) = quote {
+            if *c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(*c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            '=' if depth == 0 => {
+                let previous = position.checked_sub(1).map(|p| chars[p].1);
+                let next = chars.get(position + 1).map(|(_, c)| *c);
+                let comparison =
+                    matches!(previous, Some('=' | '!' | '<' | '>')) || next == Some('=');
+                if !comparison {
+                    return (&text[..*index], Some(text[index + 1..].trim()));
+                }
+            }
+            _ => {}
+        }
+    }
+    (text, None)
+}
+
+/// 根据文档字符串中的参数说明生成 object schema
+fn entries_schema(entries: &[DocEntry], context: &TypeContext) -> Value {
+    object_schema(
+        entries
+            .iter()
+            .map(|entry| Property {
+                name: entry.name.clone(),
+                schema: entry
+                    .type_expr
+                    .as_deref()
+                    .map(|type_expr| context.schema(type_expr, &mut vec![]))
+                    .unwrap_or_else(|| json!({})),
+                required: !entry.optional,
+                description: entry.description.clone(),
+            })
+            .collect(),
+    )
+}
+
+impl TypeContext {
+    /// 收集代码中顶层声明的 TypedDict、pydantic 模型、dataclass 和 Enum
+    fn collect(code: &str) -> Self {
+        let mut classes = HashMap::new();
+        for caps in CLASS_RE.captures_iter(code) {
+            let whole = caps.get(0).expect("regex match");
+            let bases = caps
+                .get(2)
+                .map(|bases| split_top_level(bases.as_str(), &[',']))
+                .unwrap_or_default();
+            let is_dataclass = code[..whole.start()]
+                .lines()
+                .rev()
+                .map(str::trim)
+                .take_while(|line| line.starts_with('@'))
+                .any(|decorator| decorator.contains("dataclass"));
+            let body = class_body(&code[whole.end()..]);
+            let docstring = leading_string(&body);
+            classes.insert(
+                caps[1].to_string(),
+                ClassDef {
+                    kind: is_dataclass.then_some(ClassKind::Model),
+                    bases,
+                    body,
+                    docstring,
+                },
+            );
+        }
+        // 根据基类确定种类,继承自代码中其他类的沿用父类的种类;其他普通类不参与推断
+        let kinds = classes
+            .keys()
+            .map(|name| (name.clone(), class_kind(name, &classes, 0)))
+            .collect::<Vec<_>>();
+        for (name, kind) in kinds {
+            if let Some(class) = classes.get_mut(&name) {
+                class.kind = kind;
+            }
+        }
+        classes.retain(|_, class| class.kind.is_some());
+        Self { classes }
+    }
+
+    /// 将类型注解转换为 JSON Schema
+    fn schema(&self, expr: &str, stack: &mut Vec<String>) -> Value {
+        let expr = expr.trim();
+        if let Some(inner) = unquote(expr) {
+            return self.schema(&inner, stack);
+        }
+        let union = split_top_level(expr, &['|']);
+        if union.len() > 1 {
+            return self.union_schema(&union, stack);
+        }
+        if let Some(caps) = GENERIC_RE.captures(expr) {
+            let name = caps[1].rsplit('.').next().unwrap_or(&caps[1]).to_string();
+            return self.generic_schema(&name, &caps[2], stack);
+        }
+        let name = expr.rsplit('.').next().unwrap_or(expr);
+        match name {
+            "int" => json!({ "type": "integer" }),
+            "float" | "Decimal" => json!({ "type": "number" }),
+            "str" | "bytes" => json!({ "type": "string" }),
+            "bool" => json!({ "type": "boolean" }),
+            "None" | "NoneType" => json!({ "type": "null" }),
+            "dict" | "Dict" | "Mapping" | "MutableMapping" => json!({ "type": "object" }),
+            "list" | "List" | "tuple" | "Tuple" | "set" | "Set" | "Sequence" => {
+                json!({ "type": "array" })
+            }
+            "datetime" => json!({ "type": "string", "format": "date-time" }),
+            "date" => json!({ "type": "string", "format": "date" }),
+            "UUID" => json!({ "type": "string", "format": "uuid" }),
+            _ => self.class_schema(name, stack),
+        }
+    }
+
+    /// 联合类型,包含 None 时转换为可空
+    fn union_schema(&self, parts: &[String], stack: &mut Vec<String>) -> Value {
+        let (nulls, members): (Vec<_>, Vec<_>) = parts
+            .iter()
+            .partition(|part| matches!(part.as_str(), "None" | "NoneType"));
+        let schema = if members.len() == 1 {
+            self.schema(members[0], stack)
+        } else {
+            json!({
+                "anyOf": members.iter().map(|member| self.schema(member, stack)).collect::<Vec<_>>()
+            })
+        };
+        if nulls.is_empty() {
+            schema
+        } else {
+            nullable(schema)
+        }
+    }
+
+    /// 泛型类型: List[T]、Dict[str, T]、Optional[T]、Literal[...]、Annotated[T, ...] 等
+    fn generic_schema(&self, name: &str, args: &str, stack: &mut Vec<String>) -> Value {
+        let args = split_top_level(args, &[',']);
+        let arg = |index: usize, stack: &mut Vec<String>| {
+            args.get(index)
+                .map(|arg| self.schema(arg, stack))
+                .unwrap_or_else(|| json!({}))
+        };
+        match name {
+            "Optional" => nullable(arg(0, stack)),
+            "Union" => self.union_schema(&args, stack),
+            "List" | "list" | "Sequence" | "MutableSequence" | "Iterable" | "Collection"
+            | "Set" | "set" | "FrozenSet" | "frozenset" => {
+                json!({ "type": "array", "items": arg(0, stack) })
+            }
+            "Tuple" | "tuple" if args.len() == 2 && args[1] == "..." => {
+                json!({ "type": "array", "items": arg(0, stack) })
+            }
+            "Tuple" | "tuple" => {
+                let items = (0..args.len()).map(|i| arg(i, stack)).collect::<Vec<_>>();
+                json!({ "type": "array", "prefixItems": items, "minItems": items.len() })
+            }
+            "Dict" | "dict" | "Mapping" | "MutableMapping" => {
+                json!({ "type": "object", "additionalProperties": arg(1, stack) })
+            }
+            "Literal" => {
+                let values = args.iter().filter_map(|arg| literal_value(arg)).collect();
+                enum_schema(values)
+            }
+            "Annotated" => {
+                let description = args.iter().skip(1).find_map(|metadata| {
+                    unquote(metadata).or_else(|| keyword_argument(metadata, "description"))
+                });
+                let schema = field_constraints(arg(0, stack), args.iter().skip(1));
+                with_description(schema, description.as_deref())
+            }
+            "Required" | "NotRequired" | "ReadOnly" | "Final" | "ClassVar" => arg(0, stack),
+            _ => self.class_schema(name, stack),
+        }
+    }
+
+    /// 展开代码中声明的类,未知类型不做限制
+    fn class_schema(&self, name: &str, stack: &mut Vec<String>) -> Value {
+        let Some(class) = self.classes.get(name) else {
+            return json!({});
+        };
+        if stack.len() >= MAX_TYPE_DEPTH || stack.iter().any(|n| n == name) {
+            return json!({ "type": "object" });
+        }
+        stack.push(name.to_string());
+        let schema = match class.kind.unwrap_or(ClassKind::Model) {
+            ClassKind::Enum => enum_schema(
+                self.class_statements(name)
+                    .iter()
+                    .filter_map(|(statement, _)| literal_value(split_default(statement).1?))
+                    .collect(),
+            ),
+            kind => {
+                let mut properties: Vec<Property> = vec![];
+                for statement in self.class_statements(name) {
+                    let Some(property) = self.field(&statement.0, statement.1.clone(), kind, stack)
+                    else {
+                        continue;
+                    };
+                    properties.retain(|existing| existing.name != property.name);
+                    properties.push(property);
+                }
+                let mut schema = object_schema(properties);
+                if let Some(doc) = class.docstring.as_deref().map(parse_docstring) {
+                    describe_properties(
+                        &mut schema,
+                        doc.entries
+                            .iter()
+                            .filter_map(|e| Some((e.name.as_str(), e.description.as_deref()?))),
+                    );
+                }
+                schema
+            }
+        };
+        stack.pop();
+        let description = class
+            .docstring
+            .as_deref()
+            .and_then(|doc| parse_docstring(doc).description);
+        with_description(schema, description.as_deref())
+    }
+
+    /// 类及其代码中声明的父类的语句,父类在前
+    fn class_statements(&self, name: &str) -> Vec<(String, Option<String>)> {
+        let mut statements = vec![];
+        self.collect_statements(name, &mut statements, 0);
+        statements
+    }
+
+    fn collect_statements(
+        &self,
+        name: &str,
+        statements: &mut Vec<(String, Option<String>)>,
+        depth: usize,
+    ) {
+        let Some(class) = self.classes.get(name).filter(|_| depth < MAX_TYPE_DEPTH) else {
+            return;
+        };
+        for base in &class.bases {
+            self.collect_statements(base, statements, depth + 1);
+        }
+        statements.extend(field_statements(&class.body));
+    }
+
+    /// 解析字段语句 `name: annotation = default`
+    fn field(
+        &self,
+        statement: &str,
+        docstring: Option<String>,
+        kind: ClassKind,
+        stack: &mut Vec<String>,
+    ) -> Option<Property> {
+        let (declaration, default) = split_default(statement);
+        let (name, annotation) = declaration.split_once(':')?;
+        let name = name.trim();
+        if !name.chars().all(|c| c.is_alphanumeric() || c == '_') || name.starts_with('_') {
+            return None;
+        }
+        let annotation = annotation.trim();
+        if annotation.starts_with("ClassVar") {
+            return None;
+        }
+        let wrapper = GENERIC_RE
+            .captures(annotation)
+            .map(|caps| caps[1].rsplit('.').next().unwrap_or(&caps[1]).to_string());
+        let field_call = default
+            .filter(|default| default.starts_with("Field("))
+            .and_then(|default| balanced(default, "Field".len()))
+            .map(|(inner, _)| inner.to_string());
+        let required = match kind {
+            ClassKind::TypedDict { total } => match wrapper.as_deref() {
+                Some("Required") => true,
+                Some("NotRequired") => false,
+                _ => total,
+            },
+            _ => match (&field_call, default) {
+                (Some(call), _) => {
+                    let args = split_top_level(call, &[',']);
+                    let has_default = args.iter().any(|arg| {
+                        arg.starts_with("default=") || arg.starts_with("default_factory=")
+                    });
+                    let positional = args.first().filter(|arg| !arg.contains('='));
+                    !has_default && positional.is_none_or(|arg| arg == "...")
+                }
+                (None, default) => default.is_none(),
+            },
+        };
+        let mut schema = self.schema(annotation, stack);
+        let mut description = docstring;
+        if let Some(call) = field_call {
+            let args = split_top_level(&call, &[',']);
+            schema = field_constraints(schema, args.iter());
+            description = keyword_argument(&call, "description").or(description);
+        }
+        Some(Property {
+            name: name.to_string(),
+            schema,
+            required,
+            description,
+        })
+    }
+}
+
+/// 根据基类确定类的种类
+fn class_kind(name: &str, classes: &HashMap<String, ClassDef>, depth: usize) -> Option<ClassKind> {
+    let class = classes.get(name)?;
+    if class.kind.is_some() {
+        return class.kind;
+    }
+    for base in &class.bases {
+        let base_name = base.rsplit('.').next().unwrap_or(base);
+        let kind = match base_name {
+            "TypedDict" => Some(ClassKind::TypedDict {
+                total: !class
+                    .bases
+                    .iter()
+                    .any(|b| b.replace(' ', "") == "total=False"),
+            }),
+            "BaseModel" => Some(ClassKind::Model),
+            "Enum" | "IntEnum" | "StrEnum" => Some(ClassKind::Enum),
+            _ if depth < MAX_TYPE_DEPTH => class_kind(base_name, classes, depth + 1),
+            _ => None,
+        };
+        if kind.is_some() {
+            return kind;
+        }
+    }
+    None
+}
+
+/// 类定义之后缩进的类体
+fn class_body(rest: &str) -> String {
+    let mut lines = rest.lines();
+    // class 行冒号之后的剩余部分
+    let first = lines.next().unwrap_or("").trim();
+    let mut body = vec![];
+    if !first.is_empty() && !first.starts_with('#') {
+        body.push(first);
+    }
+    for line in lines {
+        if !line.trim().is_empty() && !line.starts_with([' ', '\t']) {
+            break;
+        }
+        body.push(line);
+    }
+    body.join("\n")
+}
+
+/// 类体中的字段语句和它们的说明(行尾 `#` 注释或字段之后的字符串)
+fn field_statements(body: &str) -> Vec<(String, Option<String>)> {
+    let indent = body
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .map_or(0, indentation);
+    let mut statements: Vec<(String, Option<String>)> = vec![];
+    let mut current = String::new();
+    let mut comment = None;
+    let mut depth = 0i32;
+    // 上一个语句是否是字段,用于识别字段文档字符串
+    let mut after_field = false;
+    for line in body.lines() {
+        if current.is_empty() && (line.trim().is_empty() || indentation(line) != indent) {
+            continue;
+        }
+        let (code, line_comment) = split_comment(line);
+        if line_comment.is_some() {
+            comment = line_comment;
+        }
+        current.push_str(code.trim());
+        current.push(' ');
+        depth += bracket_delta(code);
+        let open_string = ["\"\"\"", "\'\'\'"]
+            .iter()
+            .any(|q| current.matches(q).count() % 2 == 1);
+        if depth > 0 || open_string {
+            if open_string {
+                current.push('\n');
+            }
+            continue;
+        }
+        let statement = std::mem::take(&mut current).trim().to_string();
+        let line_comment = comment.take();
+        depth = 0;
+        if let Some(text) = leading_string(&statement).filter(|_| {
+            statement.starts_with(['"', '\'']) || statement.starts_with(['r', 'u', 'R', 'U'])
+        }) {
+            let last = statements
+                .last_mut()
+                .filter(|last| after_field && last.1.is_none());
+            if let Some(last) = last {
+                last.1 = join_description(&text.lines().collect::<Vec<_>>());
+            }
+            after_field = false;
+            continue;
+        }
+        let skip = ["def ", "async ", "@", "class ", "pass", "...", "return"]
+            .iter()
+            .any(|prefix| statement.starts_with(prefix));
+        after_field = !skip;
+        if !skip {
+            statements.push((statement, line_comment));
+        }
+    }
+    statements
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// 一行代码中括号的净增量,忽略字符串中的括号
+fn bracket_delta(code: &str) -> i32 {
+    let mut delta = 0;
+    let mut quote: Option<char> = None;
+    for c in code.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '(' | '[' | '{' => delta += 1,
+                ')' | ']' | '}' => delta -= 1,
+                _ => {}
+            },
+        }
+    }
+    delta
+}
+
+/// 拆分行尾的 `#` 注释
+fn split_comment(line: &str) -> (&str, Option<String>) {
+    let mut quote: Option<char> = None;
+    for (index, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '#' => {
+                    let comment = line[index + 1..].trim();
+                    return (
+                        &line[..index],
+                        (!comment.is_empty()).then(|| comment.to_string()),
+                    );
+                }
+                _ => {}
+            },
+        }
+    }
+    (line, None)
+}
+
+/// 文本开头的字符串字面量(文档字符串)
+fn leading_string(text: &str) -> Option<String> {
+    let text = text.trim_start();
+    let text = match text.strip_prefix(['r', 'R', 'u', 'U']) {
+        Some(rest) if rest.starts_with(['"', '\'']) => rest,
+        _ => text,
+    };
+    for quote in ["\"\"\"", "\'\'\'"] {
+        if let Some(rest) = text.strip_prefix(quote) {
+            return rest.find(quote).map(|end| rest[..end].to_string());
+        }
+    }
+    let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let line = text.lines().next()?;
+    line[1..]
+        .find(quote)
+        .map(|end| line[1..end + 1].to_string())
+}
+
+/// 函数定义之后的文档字符串
+fn function_docstring(after_params: &str) -> Option<String> {
+    let colon = after_params.find(':')?;
+    let line_end = after_params[colon..].find('\n')? + colon;
+    leading_string(&after_params[line_end + 1..])
+}
+
+/// 解析 Google/NumPy/Sphinx 风格的文档字符串
+fn parse_docstring(doc: &str) -> Docstring {
+    let lines = doc.lines().collect::<Vec<_>>();
+    let mut description = vec![];
+    let mut entries: Vec<DocEntry> = vec![];
+    // 当前所在段落: None 为描述,Some(true) 为参数段落,Some(false) 为其他段落
+    let mut section: Option<bool> = None;
+    let mut seen_section = false;
+    let mut numpy = false;
+    let mut entry_indent = None;
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim();
+        index += 1;
+        let next_is_rule = lines
+            .get(index)
+            .is_some_and(|next| !next.trim().is_empty() && next.trim().chars().all(|c| c == '-'));
+        let heading = trimmed.trim_end_matches([':', ':']).to_lowercase();
+        let is_heading = trimmed.ends_with([':', ':']) || next_is_rule;
+        if is_heading && ARGS_SECTIONS.contains(&heading.as_str()) {
+            section = Some(true);
+            seen_section = true;
+            numpy = next_is_rule;
+            entry_indent = None;
+            if next_is_rule {
+                index += 1;
+            }
+            continue;
+        }
+        if is_heading && OTHER_SECTIONS.contains(&heading.as_str()) {
+            section = Some(false);
+            seen_section = true;
+            if next_is_rule {
+                index += 1;
+            }
+            continue;
+        }
+        if let Some(caps) = SPHINX_PARAM_RE.captures(trimmed) {
+            seen_section = true;
+            entries.push(DocEntry {
+                name: caps[2].to_string(),
+                type_expr: caps.get(1).map(|t| t.as_str().to_string()),
+                optional: false,
+                description: join_description(&[&caps[3]]),
+            });
+            continue;
+        }
+        if let Some(rest) = trimmed.strip_prefix(":type ") {
+            if let Some((name, type_expr)) = rest.split_once(':') {
+                let name = name.trim();
+                if let Some(entry) = entries.iter_mut().find(|entry| entry.name == name) {
+                    entry.type_expr = Some(type_expr.trim().to_string());
+                }
+            }
+            continue;
+        }
+        if trimmed.starts_with(':') {
+            seen_section = true;
+            continue;
+        }
+        match section {
+            None if !seen_section => description.push(trimmed),
+            Some(true) if !trimmed.is_empty() => {
+                let indent = indentation(line);
+                let entry_level = *entry_indent.get_or_insert(indent);
+                if indent > entry_level {
+                    if let Some(entry) = entries.last_mut() {
+                        let text = entry.description.take().unwrap_or_default();
+                        entry.description = join_description(&[&text, trimmed]);
+                    }
+                    continue;
+                }
+                let entry = if numpy {
+                    let (name, type_expr) = trimmed.split_once(':').unwrap_or((trimmed, ""));
+                    Some((
+                        name.trim().to_string(),
+                        Some(type_expr.trim().to_string()),
+                        None,
+                    ))
+                } else {
+                    DOC_ENTRY_RE.captures(trimmed).map(|caps| {
+                        (
+                            caps[1].to_string(),
+                            caps.get(2).map(|t| t.as_str().to_string()),
+                            join_description(&[&caps[3]]),
+                        )
+                    })
+                };
+                if let Some((name, type_expr, description)) = entry {
+                    let (type_expr, optional) = doc_type(type_expr);
+                    entries.push(DocEntry {
+                        name,
+                        type_expr,
+                        optional,
+                        description,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Docstring {
+        description: join_description(&description),
+        entries,
+    }
+}
+
+/// 文档中的类型说明,如 `int, optional`
+fn doc_type(type_expr: Option<String>) -> (Option<String>, bool) {
+    let Some(type_expr) = type_expr.filter(|t| !t.trim().is_empty()) else {
+        return (None, false);
+    };
+    let parts = split_top_level(&type_expr, &[',']);
+    let optional = parts.iter().skip(1).any(|part| part == "optional");
+    (parts.into_iter().next(), optional)
+}
+
+/// 调用参数中的关键字参数字符串值,如 `Field(description="...")`
+fn keyword_argument(text: &str, key: &str) -> Option<String> {
+    let inner = match text.find('(') {
+        Some(open) => balanced(text, open)?.0,
+        None => text,
+    };
+    split_top_level(inner, &[',']).iter().find_map(|arg| {
+        let (name, value) = arg.split_once('=')?;
+        (name.trim() == key).then(|| unquote(value))?
+    })
+}
+
+/// pydantic Field 的约束参数转换为 JSON Schema 关键字
+fn field_constraints<'a>(mut schema: Value, args: impl Iterator<Item = &'a String>) -> Value {
+    for arg in args {
+        let field_call = arg
+            .strip_prefix("Field")
+            .filter(|rest| rest.starts_with('('))
+            .and_then(|rest| balanced(rest, 0));
+        if let Some((inner, _)) = field_call {
+            schema = field_constraints(schema, split_top_level(inner, &[',']).iter());
+            continue;
+        }
+        let Some((key, value)) = arg.split_once('=') else {
+            continue;
+        };
+        let is_array = schema.get("type") == Some(&json!("array"));
+        let keyword = match key.trim() {
+            "ge" => "minimum",
+            "gt" => "exclusiveMinimum",
+            "le" => "maximum",
+            "lt" => "exclusiveMaximum",
+            "min_length" if is_array => "minItems",
+            "max_length" if is_array => "maxItems",
+            "min_length" => "minLength",
+            "max_length" => "maxLength",
+            "pattern" => "pattern",
+            _ => continue,
+        };
+        let value = value.trim();
+        let value = if keyword == "pattern" {
+            unquote(value.trim_start_matches(['r', 'R'])).map(Value::String)
+        } else {
+            literal_value(value)
+        };
+        if let (Some(value), Value::Object(object)) = (value, &mut schema) {
+            object.insert(keyword.to_string(), value);
+        }
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::infer_python_signature;
+    use serde_json::json;
+
+    #[test]
+    fn test_typed_dict_and_docstring() {
+        let code = r#"
+from typing import Literal, NotRequired, Optional, TypedDict
+
+class Order(TypedDict):
+    """订单参数"""
+    item: str  # 商品名
+    quantity: int
+    mode: Literal["fast", "slow"]
+    note: NotRequired[Optional[str]]
+
+def helper(x):
+    return x
+
+def handler(args: Order) -> dict:
+    """
+    创建订单
+
+    Args:
+        args.quantity: 购买数量
+    """
+    return args
+"#;
+        let signature = infer_python_signature(code, None);
+        assert_eq!(signature.handler.as_deref(), Some("handler"));
+        assert_eq!(signature.description.as_deref(), Some("创建订单"));
+        assert_eq!(
+            signature.input_schema,
+            json!({
+                "type": "object",
+                "description": "订单参数",
+                "properties": {
+                    "item": { "type": "string", "description": "商品名" },
+                    "quantity": { "type": "integer", "description": "购买数量" },
+                    "mode": { "type": "string", "enum": ["fast", "slow"] },
+                    "note": { "type": ["string", "null"] }
+                },
+                "required": ["item", "quantity", "mode"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_pydantic_model() {
+        let code = r#"
+from pydantic import BaseModel, Field
+
+class Point(BaseModel):
+    x: float = Field(..., ge=0, description="横坐标")
+    y: float = 0
+    """纵坐标"""
+    tags: list[str] = Field(default_factory=list)
+
+def main(point: Point):
+    return point.x + point.y
+"#;
+        let signature = infer_python_signature(code, None);
+        assert_eq!(signature.handler.as_deref(), Some("main"));
+        assert_eq!(
+            signature.input_schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "x": { "type": "number", "minimum": 0, "description": "横坐标" },
+                    "y": { "type": "number", "description": "纵坐标" },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["x"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_untyped_handler_with_google_args() {
+        let code = r#"
+def main(args):
+    """两数相加
+
+    Args:
+        a (int): 第一个数
+        b (int, optional): 第二个数,
+            默认为 0
+    """
+    return args["a"] + args.get("b", 0)
+"#;
+        let signature = infer_python_signature(code, None);
+        assert_eq!(signature.description.as_deref(), Some("两数相加"));
+        assert_eq!(
+            signature.input_schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "a": { "type": "integer", "description": "第一个数" },
+                    "b": { "type": "integer", "description": "第二个数, 默认为 0" }
+                },
+                "required": ["a"]
+            })
+        );
+
+        let signature = infer_python_signature("def handler(args):\n    return args\n", None);
+        assert!(!signature.inferred);
+    }
+}
@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+///从handler签名静态分析得到的函数描述
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FunctionSignature {
+    //找到的handler函数名,如 handler、main、Handler
+    pub handler: Option<String>,
+    //从docstring/JSDoc/注释中提取的函数描述
+    pub description: Option<String>,
+    //参数的JSON Schema,无法推断时为不限制属性的object
+    pub input_schema: Value,
+    //是否从类型声明或文档中推断出了参数结构
+    pub inferred: bool,
+}
+
+impl FunctionSignature {
+    /// 未能推断参数结构时的签名
+    pub(crate) fn unknown(handler: Option<String>, description: Option<String>) -> Self {
+        Self {
+            handler,
+            description,
+            input_schema: json!({ "type": "object" }),
+            inferred: false,
+        }
+    }
+}
+
+///对象的一个属性
+#[derive(Debug, Clone)]
+pub(crate) struct Property {
+    pub name: String,
+    pub schema: Value,
+    pub required: bool,
+    pub description: Option<String>,
+}
+
+/// 根据属性列表生成 object 类型的 schema
+pub(crate) fn object_schema(properties: Vec<Property>) -> Value {
+    let mut schema_properties = Map::new();
+    let mut required = vec![];
+    for property in properties {
+        if property.required {
+            required.push(Value::String(property.name.clone()));
+        }
+        schema_properties.insert(
+            property.name,
+            with_description(property.schema, property.description.as_deref()),
+        );
+    }
+    let mut schema = json!({ "type": "object", "properties": schema_properties });
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    schema
+}
+
+/// 为 schema 添加描述,已有描述时保留原描述
+pub(crate) fn with_description(mut schema: Value, description: Option<&str>) -> Value {
+    let description = description.map(str::trim).filter(|d| !d.is_empty());
+    if let (Some(description), Value::Object(object)) = (description, &mut schema) {
+        object
+            .entry("description")
+            .or_insert_with(|| Value::String(description.to_string()));
+    }
+    schema
+}
+
+/// 为 object schema 中已存在的属性补充描述
+pub(crate) fn describe_properties<'a>(
+    schema: &mut Value,
+    descriptions: impl IntoIterator<Item = (&'a str, &'a str)>,
+) {
+    let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for (name, description) in descriptions {
+        if let Some(property) = properties.get_mut(name) {
+            *property = with_description(property.take(), Some(description));
+        }
+    }
+}
+
+/// 允许为 null 的 schema
+pub(crate) fn nullable(schema: Value) -> Value {
+    match schema.get("type").cloned() {
+        Some(Value::String(schema_type)) if schema_type != "null" => {
+            let mut schema = schema;
+            schema["type"] = json!([schema_type, "null"]);
+            schema
+        }
+        Some(_) => schema,
+        None if schema.as_object().is_some_and(|o| o.is_empty()) => schema,
+        None => json!({ "anyOf": [schema, { "type": "null" }] }),
+    }
+}
+
+/// 多个字面量组成的枚举,类型相同时带上 type
+pub(crate) fn enum_schema(values: Vec<Value>) -> Value {
+    let schema_type = match values.first() {
+        Some(Value::String(_)) if values.iter().all(Value::is_string) => Some("string"),
+        Some(Value::Number(_)) if values.iter().all(Value::is_number) => Some("number"),
+        Some(Value::Bool(_)) if values.iter().all(Value::is_boolean) => Some("boolean"),
+        _ => None,
+    };
+    match schema_type {
+        Some(schema_type) => json!({ "type": schema_type, "enum": values }),
+        None => json!({ "enum": values }),
+    }
+}
+
+/// 从 `open` 位置的开括号(`(`、`[`、`{` 或 `<`)开始,返回括号内的内容和闭括号之后的位置;
+/// 会跳过字符串中的括号,`open` 处不是开括号时返回 `None`
+pub(crate) fn balanced(text: &str, open: usize) -> Option<(&str, usize)> {
+    let rest = text.get(open..)?;
+    // 尖括号只在以 `<` 开始时计入,避免 `=>`、比较运算符等干扰
+    let angle = match rest.chars().next()? {
+        '(' | '[' | '{' => false,
+        '<' => true,
+        _ => return None,
+    };
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut previous = None;
+    for (offset, c) in rest.char_indices() {
+        let index = open + offset;
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' | '`' => quote = Some(c),
+                '(' | '[' | '{' => depth += 1,
+                '<' if angle => depth += 1,
+                c if matches!(c, ')' | ']' | '}')
+                    || (angle && c == '>' && previous != Some('=')) =>
+                {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some((&text[open + 1..index], index + 1));
+                    }
+                }
+                _ => {}
+            },
+        }
+        previous = Some(c);
+    }
+    None
+}
+
+/// 按顶层(不在括号、尖括号和字符串中)的分隔符拆分
+pub(crate) fn split_top_level(text: &str, separators: &[char]) -> Vec<String> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut previous = ' ';
+    let mut escaped = false;
+    for c in text.chars() {
+        if let Some(q) = quote {
+            current.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            previous = c;
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '(' | '[' | '{' | '<' => depth += 1,
+            // 箭头函数类型中的 => 不是尖括号
+            '>' if previous == '=' => {}
+            ')' | ']' | '}' | '>' => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 && separators.contains(&c) {
+            parts.push(std::mem::take(&mut current));
+        } else {
+            current.push(c);
+        }
+        previous = c;
+    }
+    parts.push(current);
+    parts
+        .into_iter()
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// 去掉最外层的引号
+pub(crate) fn unquote(text: &str) -> Option<String> {
+    let text = text.trim();
+    let first = text.chars().next()?;
+    if text.len() >= 2 && matches!(first, '"' | '\'' | '`') && text.ends_with(first) {
+        Some(text[1..text.len() - 1].to_string())
+    } else {
+        None
+    }
+}
+
+/// 将字面量文本转换为JSON值: 字符串、数字、布尔值
+pub(crate) fn literal_value(text: &str) -> Option<Value> {
+    let text = text.trim();
+    if let Some(s) = unquote(text) {
+        return Some(Value::String(s));
+    }
+    match text {
+        "true" | "True" => return Some(Value::Bool(true)),
+        "false" | "False" => return Some(Value::Bool(false)),
+        _ => {}
+    }
+    serde_json::from_str::<serde_json::Number>(text)
+        .ok()
+        .map(Value::Number)
+}
+
+/// 合并多行描述,去掉空行
+pub(crate) fn join_description(lines: &[&str]) -> Option<String> {
+    let text = lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{balanced, nullable, split_top_level};
+    use serde_json::json;
+
+    #[test]
+    fn test_balanced_and_split() {
+        let text = "f(a: { x: string, y: \")\" }, b: number) rest";
+        let (inner, end) = balanced(text, 1).unwrap();
+        assert_eq!(inner, "a: { x: string, y: \")\" }, b: number");
+        assert_eq!(&text[end..], " rest");
+        assert_eq!(
+            split_top_level(inner, &[',']),
+            vec!["a: { x: string, y: \")\" }", "b: number"]
+        );
+        assert_eq!(
+            balanced("Map<K, Array<V>> rest", 3),
+            Some(("K, Array<V>", 16))
+        );
+        assert_eq!(balanced("(é: \"é)\") rest", 0), Some(("é: \"é)\"", 11)));
+        // 不是开括号、位置不在字符边界上时不会 panic
+        assert_eq!(balanced("}input.name", 0), None);
+        assert_eq!(balanced("é { A int }", 0), None);
+        assert_eq!(balanced("é { A int }", 1), None);
+        assert_eq!(
+            split_top_level("Map<string, number> | (a: A) => B", &['|']),
+            vec!["Map<string, number>", "(a: A) => B"]
+        );
+    }
+
+    #[test]
+    fn test_nullable() {
+        assert_eq!(
+            nullable(json!({ "type": "string" })),
+            json!({ "type": ["string", "null"] })
+        );
+        assert_eq!(nullable(json!({})), json!({}));
+    }
+}
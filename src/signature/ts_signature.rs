@@ -0,0 +1,930 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+use crate::model::entrypoint_candidates;
+
+use super::signature_model::{
+    FunctionSignature, Property, balanced, describe_properties, enum_schema, join_description,
+    literal_value, nullable, object_schema, split_top_level, unquote, with_description,
+};
+
+/// 命名类型展开的最大深度,防止递归类型无限展开
+const MAX_TYPE_DEPTH: usize = 8;
+
+static FUNCTION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?m)^[ \t]*(?:export\s+)?(?:default\s+)?(?:async\s+)?function\s*\*?\s*([A-Za-z_$][\w$]*)\s*(?:<[^>()]*>)?\s*\(",
+    )
+    .expect("invalid function regex")
+});
+
+static ARROW_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?m)^[ \t]*(?:export\s+)?(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*(?::[^=\n]+)?=\s*(?:async\s+)?(?:function\s*\*?\s*\w*\s*)?(?:<[^>()]*>)?\s*\(",
+    )
+    .expect("invalid arrow function regex")
+});
+
+static INTERFACE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?m)^[ \t]*(?:export\s+)?(?:declare\s+)?interface\s+(\w+)\s*(?:<[^>{]*>)?\s*(?:extends\s+([^{]+))?\{",
+    )
+    .expect("invalid interface regex")
+});
+
+static TYPE_ALIAS_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:export\s+)?(?:declare\s+)?type\s+(\w+)\s*(?:<[^>=]*>)?\s*=")
+        .expect("invalid type alias regex")
+});
+
+static JSDOC_BLOCK_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)/\*\*(.*?)\*/").expect("invalid jsdoc regex"));
+
+static GENERIC_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)^([\w$.]+)\s*<(.*)>$").expect("invalid generic regex"));
+
+///命名类型的定义
+#[derive(Debug, Clone)]
+enum NamedType {
+    //类型表达式,interface 会被转换为 `Base & { ... }`
+    Expr {
+        expr: String,
+        description: Option<String>,
+    },
+    //JSDoc @typedef 直接生成的 schema
+    Schema(Value),
+}
+
+///JSDoc 中的一个 @param/@property 标签
+#[derive(Debug, Clone)]
+struct JsDocTag {
+    type_expr: Option<String>,
+    name: String,
+    optional: bool,
+    description: Option<String>,
+}
+
+///解析后的 JSDoc 注释
+#[derive(Debug, Default)]
+struct JsDoc {
+    description: Option<String>,
+    params: Vec<JsDocTag>,
+    typedef: Option<(String, String)>,
+    properties: Vec<JsDocTag>,
+}
+
+///代码中声明的类型,用于展开命名类型
+struct TypeContext {
+    types: HashMap<String, NamedType>,
+}
+
+/// 从 TypeScript/JavaScript 代码推断入口函数(默认依次查找 handler、main)的参数结构
+pub(crate) fn infer_ts_signature(code: &str, entrypoint: Option<&str>) -> FunctionSignature {
+    let Some((name, params_start)) = find_handler(code, entrypoint) else {
+        return FunctionSignature::unknown(None, None);
+    };
+    let context = TypeContext::collect(code);
+    let doc = doc_comment_before(code, params_start).map(|raw| parse_jsdoc(&raw));
+    let description = doc.as_ref().and_then(|doc| doc.description.clone());
+
+    let params_text = balanced(code, params_start)
+        .map(|(inner, _)| inner)
+        .unwrap_or("");
+    let first_param = split_top_level(params_text, &[',']).into_iter().next();
+    let mut schema = first_param
+        .as_deref()
+        .and_then(|param| param_schema(param, &context));
+
+    // JSDoc 中 `@param {type} input.x - 描述` 形式的属性
+    let (root_tags, property_tags): (Vec<_>, Vec<_>) = doc
+        .map(|doc| doc.params)
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|tag| !tag.name.contains('.'));
+    if schema.is_none() {
+        schema = root_tags
+            .first()
+            .and_then(|tag| tag.type_expr.as_deref())
+            .map(|type_expr| context.schema(type_expr, &mut vec![]))
+            .filter(|schema| schema.get("properties").is_some());
+    }
+    let property_tags = property_tags
+        .into_iter()
+        .filter_map(|mut tag| {
+            let property = tag.name.split_once('.')?.1.to_string();
+            (!property.contains('.')).then(|| {
+                tag.name = property;
+                tag
+            })
+        })
+        .collect::<Vec<_>>();
+    let schema = match schema {
+        Some(mut schema) => {
+            merge_tag_types(&mut schema, &property_tags, &context);
+            describe_properties(
+                &mut schema,
+                property_tags
+                    .iter()
+                    .filter_map(|tag| Some((tag.name.as_str(), tag.description.as_deref()?))),
+            );
+            Some(schema)
+        }
+        None if !property_tags.is_empty() => Some(tags_schema(&property_tags, &context)),
+        None => None,
+    };
+
+    match schema {
+        Some(input_schema) => FunctionSignature {
+            handler: Some(name),
+            description,
+            input_schema,
+            inferred: true,
+        },
+        None => FunctionSignature::unknown(Some(name), description),
+    }
+}
+
+/// 代码中声明的函数(包括函数表达式和箭头函数),按声明顺序返回函数名和参数列表开括号的位置
+fn declared_functions(code: &str) -> Vec<(String, usize)> {
+    let mut functions = FUNCTION_RE
+        .captures_iter(code)
+        .chain(ARROW_RE.captures_iter(code))
+        .filter_map(|caps| {
+            let whole = caps.get(0)?;
+            Some((caps[1].to_string(), whole.end() - 1))
+        })
+        .collect::<Vec<_>>();
+    functions.sort_by_key(|(_, position)| *position);
+    functions
+}
+
+/// 代码中声明的函数名,按声明顺序去重
+pub(crate) fn ts_function_names(code: &str) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for (name, _) in declared_functions(code) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// 查找入口函数,与模板的查找顺序一致;返回函数名和参数列表开括号的位置
+fn find_handler(code: &str, entrypoint: Option<&str>) -> Option<(String, usize)> {
+    let functions = declared_functions(code);
+    entrypoint_candidates(entrypoint)
+        .into_iter()
+        .find_map(|wanted| functions.iter().find(|(name, _)| name == wanted).cloned())
+}
+
+/// 第一个参数的 schema: 优先使用类型注解,其次使用解构出的属性名
+fn param_schema(param: &str, context: &TypeContext) -> Option<Value> {
+    let param = match top_level_index(param, '=') {
+        Some(index) => &param[..index],
+        None => param,
+    };
+    let (pattern, type_expr) = match top_level_index(param, ':') {
+        Some(index) => (param[..index].trim(), Some(param[index + 1..].trim())),
+        None => (param.trim(), None),
+    };
+    if let Some(type_expr) = type_expr {
+        return Some(context.schema(type_expr, &mut vec![]));
+    }
+    if !pattern.starts_with('{') {
+        return None;
+    }
+    let (inner, _) = balanced(pattern, 0)?;
+    let properties = split_top_level(inner, &[','])
+        .into_iter()
+        .filter(|item| !item.starts_with("..."))
+        .filter_map(|item| {
+            let has_default = top_level_index(&item, '=').is_some();
+            let name = item.split([':', '=']).next()?.trim().to_string();
+            Some(Property {
+                name,
+                schema: json!({}),
+                required: !has_default,
+                description: None,
+            })
+        })
+        .collect();
+    Some(object_schema(properties))
+}
+
+/// 使用 JSDoc 标签中的类型补充没有类型的属性(如解构参数)
+fn merge_tag_types(schema: &mut Value, tags: &[JsDocTag], context: &TypeContext) {
+    let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for tag in tags {
+        let Some(type_expr) = tag.type_expr.as_deref() else {
+            continue;
+        };
+        let untyped = properties
+            .get(&tag.name)
+            .is_some_and(|property| property.as_object().is_some_and(|o| o.is_empty()));
+        if untyped {
+            properties.insert(tag.name.clone(), context.schema(type_expr, &mut vec![]));
+        }
+    }
+}
+
+/// 根据 JSDoc 标签生成 object schema
+fn tags_schema(tags: &[JsDocTag], context: &TypeContext) -> Value {
+    object_schema(
+        tags.iter()
+            .map(|tag| Property {
+                name: tag.name.clone(),
+                schema: tag
+                    .type_expr
+                    .as_deref()
+                    .map(|type_expr| context.schema(type_expr, &mut vec![]))
+                    .unwrap_or_else(|| json!({})),
+                required: !tag.optional,
+                description: tag.description.clone(),
+            })
+            .collect(),
+    )
+}
+
+impl TypeContext {
+    /// 收集代码中的 interface、type 别名和 JSDoc @typedef
+    fn collect(code: &str) -> Self {
+        let mut types = HashMap::new();
+        for caps in INTERFACE_RE.captures_iter(code) {
+            let whole = caps.get(0).expect("regex match");
+            let Some((body, _)) = balanced(code, whole.end() - 1) else {
+                continue;
+            };
+            let mut parts = caps
+                .get(2)
+                .map(|extends| split_top_level(extends.as_str(), &[',']))
+                .unwrap_or_default();
+            parts.push(format!("{{{body}}}"));
+            types.insert(
+                caps[1].to_string(),
+                NamedType::Expr {
+                    expr: parts.join(" & "),
+                    description: doc_comment_before(code, whole.start())
+                        .and_then(|raw| parse_jsdoc(&raw).description),
+                },
+            );
+        }
+        for caps in TYPE_ALIAS_RE.captures_iter(code) {
+            let whole = caps.get(0).expect("regex match");
+            let expr = type_alias_expr(&code[whole.end()..]);
+            types.insert(
+                caps[1].to_string(),
+                NamedType::Expr {
+                    expr,
+                    description: doc_comment_before(code, whole.start())
+                        .and_then(|raw| parse_jsdoc(&raw).description),
+                },
+            );
+        }
+        let mut context = Self { types };
+        for caps in JSDOC_BLOCK_RE.captures_iter(code) {
+            let doc = parse_jsdoc(&caps[1]);
+            let Some((type_expr, name)) = doc.typedef.clone() else {
+                continue;
+            };
+            let definition = if doc.properties.is_empty() {
+                NamedType::Expr {
+                    expr: type_expr,
+                    description: doc.description.clone(),
+                }
+            } else {
+                let schema = tags_schema(&doc.properties, &context);
+                NamedType::Schema(with_description(schema, doc.description.as_deref()))
+            };
+            context.types.insert(name, definition);
+        }
+        context
+    }
+
+    /// 将类型表达式转换为 JSON Schema
+    fn schema(&self, expr: &str, stack: &mut Vec<String>) -> Value {
+        let expr = strip_comments(expr);
+        let expr = expr.trim().trim_end_matches([';', ',']).trim();
+        if expr.is_empty() {
+            return json!({});
+        }
+        let union = split_top_level(expr, &['|']);
+        if union.len() > 1 {
+            return self.union_schema(&union, stack);
+        }
+        let intersection = split_top_level(expr, &['&']);
+        if intersection.len() > 1 {
+            let schemas = intersection
+                .iter()
+                .map(|part| self.schema(part, stack))
+                .collect::<Vec<_>>();
+            return merge_objects(schemas);
+        }
+        if expr.starts_with('(') && balanced(expr, 0).is_some_and(|(_, end)| end == expr.len()) {
+            return self.schema(&expr[1..expr.len() - 1], stack);
+        }
+        if let Some(item) = expr.strip_suffix("[]") {
+            return json!({ "type": "array", "items": self.schema(item, stack) });
+        }
+        if let Some(value) = literal_value(expr) {
+            return enum_schema(vec![value]);
+        }
+        if expr.starts_with('{') {
+            return match balanced(expr, 0) {
+                Some((body, _)) => self.object_literal(body, stack),
+                None => json!({ "type": "object" }),
+            };
+        }
+        if expr.starts_with('[') {
+            let items = balanced(expr, 0)
+                .map(|(inner, _)| split_top_level(inner, &[',']))
+                .unwrap_or_default()
+                .iter()
+                .map(|item| {
+                    let item = item.rsplit_once(':').map_or(item.as_str(), |(_, t)| t);
+                    self.schema(item.trim_end_matches('?'), stack)
+                })
+                .collect::<Vec<_>>();
+            return json!({ "type": "array", "prefixItems": items, "minItems": items.len() });
+        }
+        if let Some(caps) = GENERIC_RE.captures(expr) {
+            return self.generic_schema(&caps[1], &caps[2], stack);
+        }
+        match expr {
+            "string" | "String" => json!({ "type": "string" }),
+            "number" | "Number" | "bigint" => json!({ "type": "number" }),
+            "boolean" | "Boolean" => json!({ "type": "boolean" }),
+            "null" | "undefined" | "void" => json!({ "type": "null" }),
+            "object" | "Object" => json!({ "type": "object" }),
+            "Array" => json!({ "type": "array" }),
+            "Date" => json!({ "type": "string", "format": "date-time" }),
+            _ => self.named_schema(expr, stack),
+        }
+    }
+
+    /// 联合类型: 字面量联合转换为 enum,null/undefined 转换为可空
+    fn union_schema(&self, parts: &[String], stack: &mut Vec<String>) -> Value {
+        let (nulls, members): (Vec<_>, Vec<_>) = parts
+            .iter()
+            .partition(|part| matches!(part.as_str(), "null" | "undefined" | "void"));
+        let literals = members
+            .iter()
+            .map(|member| literal_value(member))
+            .collect::<Option<Vec<_>>>();
+        let schema = match literals {
+            Some(values) if !values.is_empty() => enum_schema(values),
+            _ if members.len() == 1 => self.schema(members[0], stack),
+            _ => json!({
+                "anyOf": members.iter().map(|member| self.schema(member, stack)).collect::<Vec<_>>()
+            }),
+        };
+        if nulls.is_empty() {
+            schema
+        } else {
+            nullable(schema)
+        }
+    }
+
+    /// 泛型类型: Array<T>、Record<K, V>、Partial<T> 等
+    fn generic_schema(&self, name: &str, args: &str, stack: &mut Vec<String>) -> Value {
+        let args = split_top_level(args, &[',']);
+        let arg = |index: usize, stack: &mut Vec<String>| {
+            args.get(index)
+                .map(|arg| self.schema(arg, stack))
+                .unwrap_or_else(|| json!({}))
+        };
+        match name {
+            "Array" | "ReadonlyArray" | "Set" | "ReadonlySet" => {
+                json!({ "type": "array", "items": arg(0, stack) })
+            }
+            "Record" | "Map" | "ReadonlyMap" => {
+                json!({ "type": "object", "additionalProperties": arg(1, stack) })
+            }
+            "Promise" | "Readonly" | "Awaited" => arg(0, stack),
+            "Required" | "Partial" => {
+                let mut schema = arg(0, stack);
+                if let Some(object) = schema.as_object_mut() {
+                    let keys = object
+                        .get("properties")
+                        .and_then(Value::as_object)
+                        .map(|properties| properties.keys().cloned().map(Value::String).collect());
+                    match (name, keys) {
+                        ("Required", Some(keys)) => {
+                            object.insert("required".to_string(), Value::Array(keys));
+                        }
+                        _ => {
+                            object.remove("required");
+                        }
+                    }
+                }
+                schema
+            }
+            "Pick" | "Omit" => {
+                let mut schema = arg(0, stack);
+                let keys = args
+                    .get(1)
+                    .map(|keys| {
+                        split_top_level(keys, &['|'])
+                            .iter()
+                            .filter_map(|key| unquote(key))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                let keep = |key: &str| (name == "Pick") == keys.iter().any(|k| k == key);
+                if let Some(properties) =
+                    schema.get_mut("properties").and_then(Value::as_object_mut)
+                {
+                    properties.retain(|key, _| keep(key));
+                }
+                if let Some(required) = schema.get_mut("required").and_then(Value::as_array_mut) {
+                    required.retain(|key| key.as_str().is_some_and(keep));
+                }
+                schema
+            }
+            _ => self.named_schema(name, stack),
+        }
+    }
+
+    /// 展开代码中声明的命名类型,未知类型不做限制
+    fn named_schema(&self, name: &str, stack: &mut Vec<String>) -> Value {
+        let Some(definition) = self.types.get(name) else {
+            return json!({});
+        };
+        if stack.len() >= MAX_TYPE_DEPTH || stack.iter().any(|n| n == name) {
+            return json!({ "type": "object" });
+        }
+        match definition {
+            NamedType::Schema(schema) => schema.clone(),
+            NamedType::Expr { expr, description } => {
+                stack.push(name.to_string());
+                let schema = self.schema(expr, stack);
+                stack.pop();
+                with_description(schema, description.as_deref())
+            }
+        }
+    }
+
+    /// 对象字面量类型 `{ a: number; b?: string }`
+    fn object_literal(&self, body: &str, stack: &mut Vec<String>) -> Value {
+        let mut properties = vec![];
+        let mut additional = None;
+        for (member, comment) in split_members(body) {
+            let member = member.trim();
+            let member = member.strip_prefix("readonly ").unwrap_or(member).trim();
+            if member.starts_with('[') {
+                // 索引签名 [key: string]: V
+                let Some((_, end)) = balanced(member, 0) else {
+                    continue;
+                };
+                if let Some(value_type) = member[end..].trim().strip_prefix(':') {
+                    additional = Some(self.schema(value_type, stack));
+                }
+                continue;
+            }
+            let (name, rest) = split_member_name(member);
+            let Some(name) = name else {
+                continue;
+            };
+            let (optional, rest) = match rest.strip_prefix('?') {
+                Some(rest) => (true, rest.trim_start()),
+                None => (false, rest),
+            };
+            // 方法签名不是数据属性
+            let Some(type_expr) = rest.strip_prefix(':') else {
+                continue;
+            };
+            let maybe_undefined = split_top_level(type_expr, &['|'])
+                .iter()
+                .any(|part| part == "undefined");
+            properties.push(Property {
+                name,
+                schema: self.schema(type_expr, stack),
+                required: !optional && !maybe_undefined,
+                description: comment,
+            });
+        }
+        let mut schema = object_schema(properties);
+        if let Some(additional) = additional {
+            schema["additionalProperties"] = additional;
+        }
+        schema
+    }
+}
+
+/// 合并 interface extends/交叉类型中的多个 object schema
+fn merge_objects(schemas: Vec<Value>) -> Value {
+    let mut properties = Map::new();
+    let mut required = vec![];
+    let mut others = vec![];
+    for schema in schemas {
+        match schema.get("properties").and_then(Value::as_object) {
+            Some(object_properties) => {
+                properties.extend(object_properties.clone());
+                if let Some(Value::Array(items)) = schema.get("required") {
+                    required.extend(items.iter().cloned());
+                }
+            }
+            None if schema.get("type") == Some(&json!("object")) => {}
+            None => others.push(schema),
+        }
+    }
+    if !others.is_empty() {
+        let mut all_of = others;
+        all_of.push(json!({ "type": "object", "properties": properties, "required": required }));
+        return json!({ "allOf": all_of });
+    }
+    let mut schema = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    schema
+}
+
+/// 拆分属性名和剩余部分,支持带引号的属性名
+fn split_member_name(member: &str) -> (Option<String>, &str) {
+    if let Some(quote) = member.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        return match member[1..].find(quote) {
+            Some(end) => (
+                Some(member[1..end + 1].to_string()),
+                member[end + 2..].trim_start(),
+            ),
+            None => (None, member),
+        };
+    }
+    let end = member
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(member.len());
+    if end == 0 {
+        return (None, member);
+    }
+    (Some(member[..end].to_string()), member[end..].trim_start())
+}
+
+/// 拆分对象类型的成员,并把前置的 JSDoc/行注释和行尾注释作为成员描述
+fn split_members(body: &str) -> Vec<(String, Option<String>)> {
+    let mut members: Vec<(String, Option<String>)> = vec![];
+    let mut current = String::new();
+    let mut pending: Option<String> = None;
+    let mut inline: Option<String> = None;
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut same_line_as_last = false;
+    let chars = body.chars().collect::<Vec<_>>();
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        if let Some(q) = quote {
+            current.push(c);
+            if c == '\\' && index + 1 < chars.len() {
+                current.push(chars[index + 1]);
+                index += 1;
+            } else if c == q {
+                quote = None;
+            }
+            index += 1;
+            continue;
+        }
+        let next = chars.get(index + 1).copied();
+        if c == '/' && (next == Some('/') || next == Some('*')) {
+            let block = next == Some('*');
+            let start = index;
+            let end = if block {
+                find_sequence(&chars, index + 2, &['*', '/']).map_or(chars.len(), |end| end + 2)
+            } else {
+                chars[index..]
+                    .iter()
+                    .position(|c| *c == '\n')
+                    .map_or(chars.len(), |offset| index + offset)
+            };
+            let raw = chars[start..end].iter().collect::<String>();
+            if depth > 0 {
+                current.push_str(&raw);
+            } else {
+                let text = clean_comment(&raw);
+                if !current.trim().is_empty() {
+                    inline = inline.or(text);
+                } else if !block && same_line_as_last {
+                    if let Some(last) = members.last_mut().filter(|last| last.1.is_none()) {
+                        last.1 = text;
+                    }
+                } else {
+                    pending = text;
+                }
+            }
+            index = end;
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '(' | '[' | '{' | '<' => depth += 1,
+            '>' if index > 0 && chars[index - 1] == '=' => {}
+            ')' | ']' | '}' | '>' => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 && matches!(c, ';' | ',' | '\n') {
+            if !current.trim().is_empty() {
+                let description = inline.take().or_else(|| pending.take());
+                members.push((std::mem::take(&mut current), description));
+                same_line_as_last = c != '\n';
+            } else {
+                current.clear();
+                if c == '\n' {
+                    same_line_as_last = false;
+                }
+            }
+        } else {
+            current.push(c);
+        }
+        index += 1;
+    }
+    if !current.trim().is_empty() {
+        let description = inline.take().or_else(|| pending.take());
+        members.push((current, description));
+    }
+    members
+}
+
+fn find_sequence(chars: &[char], from: usize, sequence: &[char]) -> Option<usize> {
+    (from..chars.len()).find(|&index| chars[index..].starts_with(sequence))
+}
+
+/// 去掉注释符号,返回注释文本
+fn clean_comment(raw: &str) -> Option<String> {
+    let text = raw
+        .trim()
+        .trim_start_matches("/**")
+        .trim_start_matches("/*")
+        .trim_start_matches("//")
+        .trim_end_matches("*/");
+    let lines = text
+        .lines()
+        .map(|line| line.trim().trim_start_matches('*'))
+        .collect::<Vec<_>>();
+    join_description(&lines)
+}
+
+/// 去掉类型表达式中的注释
+fn strip_comments(expr: &str) -> String {
+    if !expr.contains("//") && !expr.contains("/*") {
+        return expr.to_string();
+    }
+    split_members(expr)
+        .into_iter()
+        .map(|(member, _)| member)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 查找顶层(不在括号和字符串中)的字符,`=>` 中的 `=` 不算
+fn top_level_index(text: &str, target: char) -> Option<usize> {
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' | ']' | '}' | '>' => depth -= 1,
+            _ if c == target && depth == 0 => {
+                let arrow = c == '=' && chars.peek().is_some_and(|(_, next)| *next == '>');
+                if !arrow {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `type X = ...;` 的类型表达式,到顶层的分号或空行为止
+fn type_alias_expr(rest: &str) -> String {
+    let mut depth = 0i32;
+    let mut previous = ' ';
+    for (index, c) in rest.char_indices() {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            '>' if previous == '=' => {}
+            ')' | ']' | '}' | '>' => depth -= 1,
+            ';' if depth == 0 => return rest[..index].to_string(),
+            '\n' if depth == 0
+                && rest[index + 1..]
+                    .trim_start_matches([' ', '\t'])
+                    .starts_with('\n') =>
+            {
+                return rest[..index].to_string();
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    rest.to_string()
+}
+
+/// 紧挨在 `position` 所在行之前的 JSDoc 块或连续的 `//` 注释
+fn doc_comment_before(code: &str, position: usize) -> Option<String> {
+    let line_start = code[..position].rfind('\n').map_or(0, |index| index + 1);
+    let before = code[..line_start].trim_end();
+    if before.ends_with("*/") {
+        let start = before.rfind("/**")?;
+        return Some(before[start + 3..before.len() - 2].to_string());
+    }
+    let lines = before
+        .lines()
+        .rev()
+        .map(str::trim)
+        .take_while(|line| line.starts_with("//"))
+        .map(|line| line.trim_start_matches('/'))
+        .collect::<Vec<_>>();
+    (!lines.is_empty()).then(|| lines.into_iter().rev().collect::<Vec<_>>().join("\n"))
+}
+
+/// 解析 JSDoc 的描述、@param、@typedef 和 @property 标签
+fn parse_jsdoc(raw: &str) -> JsDoc {
+    let lines = raw
+        .lines()
+        .map(|line| line.trim().trim_start_matches('*').trim())
+        .collect::<Vec<_>>();
+    let mut doc = JsDoc::default();
+    let mut description = vec![];
+    // 标签内容,续行会追加到上一个标签
+    let mut tags: Vec<String> = vec![];
+    for line in lines {
+        if line.starts_with('@') {
+            tags.push(line.to_string());
+        } else if let Some(last) = tags.last_mut() {
+            if !line.is_empty() {
+                last.push(' ');
+                last.push_str(line);
+            }
+        } else {
+            description.push(line);
+        }
+    }
+    doc.description = join_description(&description);
+    for tag in tags {
+        let (kind, rest) = tag.split_once(char::is_whitespace).unwrap_or((&tag, ""));
+        let rest = rest.trim();
+        match kind {
+            "@param" | "@arg" | "@argument" => doc.params.extend(parse_tag(rest)),
+            "@property" | "@prop" => doc.properties.extend(parse_tag(rest)),
+            "@typedef" => {
+                let (type_expr, name) = split_tag_type(rest);
+                if let Some(name) = name.split_whitespace().next() {
+                    doc.typedef = Some((type_expr.unwrap_or_else(|| "object".into()), name.into()));
+                }
+            }
+            "@description" | "@desc" if doc.description.is_none() => {
+                doc.description = join_description(&[rest]);
+            }
+            _ => {}
+        }
+    }
+    doc
+}
+
+/// 拆分标签开头的 `{type}`
+fn split_tag_type(rest: &str) -> (Option<String>, &str) {
+    if !rest.starts_with('{') {
+        return (None, rest);
+    }
+    if let Some((type_expr, end)) = balanced(rest, 0) {
+        return (Some(type_expr.trim().to_string()), rest[end..].trim_start());
+    }
+    (None, rest)
+}
+
+/// 解析 `{type} name - 描述` 或 `{type} [name=default] 描述`
+fn parse_tag(rest: &str) -> Option<JsDocTag> {
+    let (type_expr, rest) = split_tag_type(rest);
+    let (name, optional, description) = if rest.starts_with('[') {
+        let (inner, end) = balanced(rest, 0)?;
+        let name = inner.split('=').next().unwrap_or(inner).trim();
+        (name.to_string(), true, &rest[end..])
+    } else {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        (rest[..end].to_string(), false, &rest[end..])
+    };
+    let description = description.trim().trim_start_matches('-').trim();
+    let (type_expr, optional) = match type_expr {
+        // JSDoc 可选类型 {number=}
+        Some(t) if t.ends_with('=') => (Some(t.trim_end_matches('=').to_string()), true),
+        t => (t, optional),
+    };
+    (!name.is_empty()).then(|| JsDocTag {
+        type_expr,
+        name,
+        optional,
+        description: join_description(&[description]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::infer_ts_signature;
+    use serde_json::json;
+
+    #[test]
+    fn test_interface_with_comments() {
+        let code = r#"
+/** 问候的参数 */
+interface Input extends Base {
+    /** 用户名 */
+    name: string;
+    times?: number; // 重复次数
+    mode: "short" | "long";
+    tags: string[];
+    extra: Record<string, number> | null;
+}
+interface Base { id: number }
+
+/**
+ * 生成问候语
+ * @param input.id - 用户ID
+ */
+export async function handler(input: Input): Promise<object> {
+    return {};
+}
+"#;
+        let signature = infer_ts_signature(code, None);
+        assert_eq!(signature.handler.as_deref(), Some("handler"));
+        assert_eq!(signature.description.as_deref(), Some("生成问候语"));
+        assert!(signature.inferred);
+        assert_eq!(
+            signature.input_schema,
+            json!({
+                "type": "object",
+                "description": "问候的参数",
+                "properties": {
+                    "id": { "type": "number", "description": "用户ID" },
+                    "name": { "type": "string", "description": "用户名" },
+                    "times": { "type": "number", "description": "重复次数" },
+                    "mode": { "type": "string", "enum": ["short", "long"] },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "extra": {
+                        "type": ["object", "null"],
+                        "additionalProperties": { "type": "number" }
+                    }
+                },
+                "required": ["id", "name", "mode", "tags", "extra"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_jsdoc_destructured_params() {
+        let code = r#"
+/**
+ * 计算两数之和
+ * @param {Object} params
+ * @param {number} params.a - 第一个数
+ * @param {number} [params.b=0] 第二个数
+ */
+const main = async ({ a, b = 0 }) => a + b;
+"#;
+        let signature = infer_ts_signature(code, None);
+        assert_eq!(signature.handler.as_deref(), Some("main"));
+        assert_eq!(
+            signature.input_schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "a": { "type": "number", "description": "第一个数" },
+                    "b": { "type": "number", "description": "第二个数" }
+                },
+                "required": ["a"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_untyped_handler() {
+        let signature = infer_ts_signature("function handler(input) { return input; }", None);
+        assert_eq!(signature.handler.as_deref(), Some("handler"));
+        assert!(!signature.inferred);
+        assert_eq!(signature.input_schema, json!({ "type": "object" }));
+    }
+
+    #[test]
+    fn test_malformed_jsdoc_tag() {
+        // 标签类型不以 { 开头时不会 panic
+        let code = r#"
+/**
+ * @param }input.name 用户名
+ */
+function handler(input) { return input; }
+"#;
+        let signature = infer_ts_signature(code, None);
+        assert_eq!(signature.handler.as_deref(), Some("handler"));
+        assert_eq!(signature.input_schema["type"], "object");
+    }
+}
@@ -0,0 +1,195 @@
+// Go 运行模板,用户代码需要在同一个 main 包中定义入口函数(默认依次查找 Handler、Main):
+// func Handler(input map[string]any) (any, error)
+// 参数也可以是结构体等任意可由 JSON 解码的类型,如 func Handler(input Input) (Output, error)
+// 需要调用上下文时声明第二个参数: func Handler(input Input, ctx *InvocationContext) (Output, error)
+package main
+
+import (
+	"bufio"
+	"encoding/json"
+	"fmt"
+	"log"
+	"os"
+	"strings"
+	"sync"
+	"time"
+)
+
+// 调用上下文,入口函数的第二个参数为 *InvocationContext 时传入
+type InvocationContext struct {
+	// 本次调用的唯一标识
+	InvocationID string
+	// 已部署函数的名称和版本,直接执行代码时为空
+	FunctionName    string
+	FunctionVersion uint64
+	// 语言标识
+	Language string
+	// 执行截止时间
+	Deadline time.Time
+	// 带级别的日志记录器
+	Logger InvocationLogger
+	// 声明的环境变量
+	env map[string]string
+}
+
+// 距离截止时间的剩余时间
+func (c *InvocationContext) RemainingTime() time.Duration {
+	if remaining := time.Until(c.Deadline); remaining > 0 {
+		return remaining
+	}
+	return 0
+}
+
+// 读取声明的环境变量
+func (c *InvocationContext) Env(name string) (string, bool) {
+	value, ok := c.env[name]
+	return value, ok
+}
+
+// 声明的环境变量的副本,修改不会影响调用上下文
+func (c *InvocationContext) Environ() map[string]string {
+	env := make(map[string]string, len(c.env))
+	for name, value := range c.env {
+		env[name] = value
+	}
+	return env
+}
+
+// 带级别的日志记录器,日志格式与其他语言一致,如 "[INFO] 消息"
+type InvocationLogger struct{}
+
+func (InvocationLogger) Debug(args ...any) { runCodeLogLevel("DEBUG", args) }
+func (InvocationLogger) Info(args ...any)  { runCodeLogLevel("INFO", args) }
+func (InvocationLogger) Warn(args ...any)  { runCodeLogLevel("WARNING", args) }
+func (InvocationLogger) Error(args ...any) { runCodeLogLevel("ERROR", args) }
+
+func runCodeLogLevel(level string, args []any) {
+	fmt.Println("[" + level + "] " + strings.TrimSuffix(fmt.Sprintln(args...), "\n"))
+}
+
+// 根据 RUN_CODE_CONTEXT 环境变量创建调用上下文
+func runCodeNewContext() *InvocationContext {
+	var info struct {
+		InvocationID    string            `json:"invocation_id"`
+		FunctionName    *string           `json:"function_name"`
+		FunctionVersion *uint64           `json:"function_version"`
+		Language        string            `json:"language"`
+		Deadline        float64           `json:"deadline"`
+		Env             map[string]string `json:"env"`
+	}
+	if err := json.Unmarshal([]byte(os.Getenv("RUN_CODE_CONTEXT")), &info); err != nil {
+		runCodeLogLevel("WARNING", []any{"解析调用上下文失败:", err})
+	}
+	ctx := &InvocationContext{
+		InvocationID: info.InvocationID,
+		Language:     info.Language,
+		Deadline:     time.UnixMilli(int64(info.Deadline * 1000)),
+		env:          info.Env,
+	}
+	if info.FunctionName != nil {
+		ctx.FunctionName = *info.FunctionName
+	}
+	if info.FunctionVersion != nil {
+		ctx.FunctionVersion = *info.FunctionVersion
+	}
+	return ctx
+}
+
+// 第二个参数为调用上下文的入口函数转换为只有参数的函数
+func runCodeWithContext[T any, R any](handler func(T, *InvocationContext) (R, error)) func(T) (R, error) {
+	return func(input T) (R, error) {
+		return handler(input, runCodeNewContext())
+	}
+}
+
+// 执行结果,字段顺序需要与其他语言模板保持一致: logs, result, error
+type runCodeEnvelope struct {
+	Logs   []string `json:"logs"`
+	Result any      `json:"result"`
+	Error  *string  `json:"error"`
+}
+
+// 按 Handler 的参数类型解码输入参数并调用,将 panic 转换为错误
+func runCodeInvokeHandler[T any, R any](handler func(T) (R, error), inputJson string) (result any, err error) {
+	defer func() {
+		if r := recover(); r != nil {
+			err = fmt.Errorf("panic: %v", r)
+		}
+	}()
+	var input T
+	if inputJson == "" {
+		inputJson = "{}"
+	}
+	if err := json.Unmarshal([]byte(inputJson), &input); err != nil {
+		return nil, fmt.Errorf("解析输入参数失败: %w", err)
+	}
+	output, err := handler(input)
+	return output, err
+}
+
+func main() {
+	originalStdout := os.Stdout
+	showLogs := {{SHOW_LOGS}}
+	logs := []string{}
+
+	// 替换 os.Stdout 和 log 的输出以捕获日志
+	reader, writer, err := os.Pipe()
+	if err != nil {
+		panic(err)
+	}
+	os.Stdout = writer
+	log.SetOutput(writer)
+	log.SetFlags(0)
+
+	var wg sync.WaitGroup
+	wg.Add(1)
+	go func() {
+		defer wg.Done()
+		bufReader := bufio.NewReader(reader)
+		for {
+			line, readErr := bufReader.ReadString('\n')
+			if len(line) > 0 {
+				trimmed := line
+				if trimmed[len(trimmed)-1] == '\n' {
+					trimmed = trimmed[:len(trimmed)-1]
+				}
+				logs = append(logs, trimmed)
+				if showLogs {
+					fmt.Fprintln(originalStdout, trimmed)
+				}
+			}
+			// 写端关闭后读取到 io.EOF,日志读取结束
+			if readErr != nil {
+				return
+			}
+		}
+	}()
+
+	// 从环境变量获取输入参数
+	inputJson := os.Getenv("INPUT_JSON")
+	if inputJson != "" {
+		fmt.Println("接收到的参数:", inputJson)
+	}
+
+	result, handlerErr := runCodeInvokeHandler({{HANDLER}}, inputJson)
+
+	// 恢复标准输出,等待日志读取完成
+	writer.Close()
+	wg.Wait()
+	os.Stdout = originalStdout
+
+	envelope := runCodeEnvelope{Logs: logs}
+	if handlerErr != nil {
+		message := handlerErr.Error()
+		envelope.Error = &message
+	} else {
+		envelope.Result = result
+	}
+
+	encoder := json.NewEncoder(originalStdout)
+	encoder.SetEscapeHTML(false)
+	if err := encoder.Encode(envelope); err != nil {
+		message := fmt.Sprintf("序列化结果失败: %v", err)
+		encoder.Encode(runCodeEnvelope{Logs: logs, Error: &message})
+	}
+}
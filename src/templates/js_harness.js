@@ -0,0 +1,222 @@
+// 日志捕获与执行结果输出,JavaScript/TypeScript 的模板共用:
+// - console 的所有方法都会被捕获,console.log 等直接记录消息,其他方法在消息前加上级别,
+//   如 "[INFO] ..."、"[DEBUG] ..."、"[WARNING] ..."、"[ERROR] ..."
+// - 每条日志同时记录为结构化的 { level, logger, message, stream },stream 与 Node.js 一致:
+//   console.warn/error/trace/assert 为 stderr,其他为 stdout
+// - 未捕获的异常和未处理的 Promise 拒绝立即结束执行,错误为 "UncaughtError: ..." / "UnhandledRejection: ..."
+// - handler 返回后等待一轮事件循环再输出结果并退出进程,之后才触发的定时器不会再执行
+// - handler 的第二个参数为调用上下文,只声明一个参数的 handler 不受影响
+// - 入口函数指定 RUN_CODE_ENTRYPOINT 时按名称查找,否则依次查找 handler、main
+// - 生成器 handler 产生的数据块逐个输出,每块一行 "__RUN_CODE_CHUNK__<JSON>"
+
+// 保存原始console.log,用于输出日志、数据块和最终结果
+const originalConsoleLog = console.log;
+let logs = [];
+let runCodeLogEntries = [];
+let runCodeFinished = false;
+let runCodeGroupIndent = '';
+const runCodeCounters = new Map();
+const runCodeTimers = new Map();
+
+// 日志参数转换为字符串,对象转换为JSON(BigInt、Map、Set 和循环引用不会导致失败)
+function runCodeFormatLogArg(arg) {
+    if (typeof arg === 'string') {
+        return arg;
+    }
+    if (arg instanceof Error) {
+        return arg.stack || String(arg);
+    }
+    if (typeof arg !== 'object' || arg === null) {
+        return String(arg);
+    }
+    const ancestors = [];
+    try {
+        return JSON.stringify(arg, function (key, value) {
+            if (typeof value === 'bigint') {
+                return `${value}n`;
+            }
+            if (typeof value !== 'object' || value === null) {
+                return value;
+            }
+            // this 为包含当前值的对象,祖先列表中它之后的对象已经处理完毕
+            const holder = ancestors.indexOf(this);
+            ancestors.length = holder + 1;
+            if (ancestors.includes(value)) {
+                return '[Circular]';
+            }
+            ancestors.push(value);
+            if (value instanceof Map) {
+                return Object.fromEntries([...value].map(([k, v]) => [String(k), v]));
+            }
+            if (value instanceof Set) {
+                return [...value];
+            }
+            return value;
+        });
+    } catch (error) {
+        return String(arg);
+    }
+}
+
+// 记录一条日志,level 为空时不加前缀
+function runCodeLog(level, args, stream = 'stdout') {
+    const text = args.map(runCodeFormatLogArg).join(' ');
+    const indented = runCodeGroupIndent ? text.split('\n').map(line => runCodeGroupIndent + line).join('\n') : text;
+    const message = level ? `[${level}] ${indented}` : indented;
+    logs.push(message);
+    runCodeLogEntries.push({ level: level, logger: null, message: indented, stream: stream });
+
+    // 如果显示日志，也输出到原始控制台
+    if ({{SHOW_LOGS}}) {
+        originalConsoleLog(message);
+    }
+}
+
+console.log = (...args) => runCodeLog(null, args);
+console.info = (...args) => runCodeLog('INFO', args);
+console.debug = (...args) => runCodeLog('DEBUG', args);
+console.warn = (...args) => runCodeLog('WARNING', args, 'stderr');
+console.error = (...args) => runCodeLog('ERROR', args, 'stderr');
+console.dir = (value) => runCodeLog(null, [value]);
+console.dirxml = (...args) => runCodeLog(null, args);
+console.table = (data) => runCodeLog(null, [data]);
+console.trace = (...args) => {
+    const stack = (new Error().stack || '').split('\n').slice(2).join('\n');
+    runCodeLog('DEBUG', ['Trace:', ...args, ...(stack ? ['\n' + stack] : [])], 'stderr');
+};
+console.assert = (condition, ...args) => {
+    if (!condition) {
+        runCodeLog('ERROR', args.length ? ['Assertion failed:', ...args] : ['Assertion failed'], 'stderr');
+    }
+};
+console.count = (label = 'default') => {
+    const count = (runCodeCounters.get(label) || 0) + 1;
+    runCodeCounters.set(label, count);
+    runCodeLog(null, [`${label}: ${count}`]);
+};
+console.countReset = (label = 'default') => runCodeCounters.delete(label);
+console.time = (label = 'default') => runCodeTimers.set(label, Date.now());
+console.timeLog = (label = 'default', ...args) => {
+    if (runCodeTimers.has(label)) {
+        runCodeLog(null, [`${label}: ${Date.now() - runCodeTimers.get(label)}ms`, ...args]);
+    }
+};
+console.timeEnd = (label = 'default') => {
+    console.timeLog(label);
+    runCodeTimers.delete(label);
+};
+console.group = (...args) => {
+    if (args.length) {
+        runCodeLog(null, args);
+    }
+    runCodeGroupIndent += '  ';
+};
+console.groupCollapsed = console.group;
+console.groupEnd = () => {
+    runCodeGroupIndent = runCodeGroupIndent.slice(2);
+};
+
+// 调用上下文: 调用ID、截止时间、函数名称和版本、语言、带级别的日志记录器和声明的环境变量(只读)
+function runCodeCreateContext() {
+    const contextJson = typeof Deno !== 'undefined' ? Deno.env.get('RUN_CODE_CONTEXT') : process.env.RUN_CODE_CONTEXT;
+    let info = {};
+    try {
+        info = JSON.parse(contextJson || '{}');
+    } catch (error) {
+        runCodeLog('WARNING', ['解析调用上下文失败:', error], 'stderr');
+    }
+    // 截止时间为Unix时间戳(毫秒),与 Date.now() 一致
+    const deadline = typeof info.deadline === 'number' ? Math.floor(info.deadline * 1000) : null;
+    return Object.freeze({
+        invocationId: info.invocation_id || null,
+        functionName: info.function_name ?? null,
+        functionVersion: info.function_version ?? null,
+        language: info.language || null,
+        deadline: deadline,
+        getRemainingTimeInMillis: () => deadline === null ? null : Math.max(0, deadline - Date.now()),
+        logger: Object.freeze({
+            debug: (...args) => runCodeLog('DEBUG', args),
+            info: (...args) => runCodeLog('INFO', args),
+            warn: (...args) => runCodeLog('WARNING', args, 'stderr'),
+            error: (...args) => runCodeLog('ERROR', args, 'stderr'),
+        }),
+        env: Object.freeze({ ...(info.env || {}) }),
+    });
+}
+
+// 输出最终结果并退出进程,只有第一次调用有效
+function runCodeFinish(result, error) {
+    if (runCodeFinished) {
+        return;
+    }
+    runCodeFinished = true;
+    const output = JSON.stringify({ logs: logs, result: result, error: error, log_entries: runCodeLogEntries });
+    if (typeof Deno !== 'undefined') {
+        // Deno 的 console.log 同步写入标准输出
+        originalConsoleLog(output);
+        Deno.exit(0);
+    } else {
+        // 输出写入完成后再退出,避免管道中的内容被截断
+        process.stdout.write(output + '\n', () => process.exit(0));
+    }
+}
+
+// 未捕获的异常和未处理的 Promise 拒绝作为执行错误
+function runCodeFail(kind, reason) {
+    runCodeFinish(null, `${kind}: ${reason instanceof Error ? reason.stack || String(reason) : runCodeFormatLogArg(reason)}`);
+}
+
+if (typeof Deno !== 'undefined') {
+    globalThis.addEventListener('unhandledrejection', (event) => {
+        event.preventDefault();
+        runCodeFail('UnhandledRejection', event.reason);
+    });
+    globalThis.addEventListener('error', (event) => {
+        event.preventDefault();
+        runCodeFail('UncaughtError', event.error !== undefined ? event.error : event.message);
+    });
+} else {
+    process.on('unhandledRejection', (reason) => runCodeFail('UnhandledRejection', reason));
+    process.on('uncaughtException', (error) => runCodeFail('UncaughtError', error));
+}
+
+// handler 执行完成后等待一轮事件循环,让已经被拒绝但没有处理的 Promise 报告错误,然后输出结果
+async function runCodeSettle() {
+    await new Promise(resolve => setTimeout(resolve, 0));
+}
+
+// 如果handler是生成器函数(function*/async function*),逐个输出产生的数据块;
+// 结果为生成器的返回值,没有返回值时为所有数据块组成的列表
+async function runCodeCollectChunks(value) {
+    const isIterator = value !== null && typeof value === 'object' && typeof value.next === 'function' &&
+        (typeof value[Symbol.asyncIterator] === 'function' || typeof value[Symbol.iterator] === 'function');
+    if (!isIterator) {
+        return value;
+    }
+    const chunks = [];
+    while (true) {
+        const { value: chunk, done } = await value.next();
+        if (done) {
+            return chunk !== undefined ? chunk : chunks;
+        }
+        const data = await runCodeToJsonable(chunk === undefined ? null : chunk);
+        chunks.push(data);
+        originalConsoleLog('__RUN_CODE_CHUNK__' + JSON.stringify(data));
+    }
+}
+
+// 查找入口函数: 指定 RUN_CODE_ENTRYPOINT 时按名称查找,否则依次查找 handler、main;
+// 找不到时抛出 HandlerNotFound 错误并列出代码中定义的函数
+function runCodeResolveEntrypoint(functions) {
+    const entrypoint = typeof Deno !== 'undefined' ? Deno.env.get('RUN_CODE_ENTRYPOINT') : process.env.RUN_CODE_ENTRYPOINT;
+    const candidates = entrypoint ? [entrypoint] : ['handler', 'main'];
+    for (const name of candidates) {
+        if (typeof functions[name] === 'function') {
+            return functions[name];
+        }
+    }
+    const available = Object.keys(functions).filter(name => typeof functions[name] === 'function');
+    const error = new Error(`no function named ${candidates.join(' or ')}; available functions: ${available.join(', ') || '(none)'}`);
+    error.name = 'HandlerNotFound';
+    throw error;
+}
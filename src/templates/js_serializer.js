@@ -0,0 +1,136 @@
+// 结果转换为可以JSON序列化的值,JavaScript/TypeScript 的模板共用:
+// - BigInt 转换为十进制字符串(handler 直接返回 BigInt 时结果也为字符串),NaN/Infinity 转换为 null
+// - Date 转换为ISO格式字符串(无效日期为 null),RegExp 转换为字符串,Error 转换为 {name, message}
+// - Map 的键都是字符串、数字或布尔值时转换为对象,否则转换为 [键, 值] 数组;Set 转换为数组
+// - Uint8Array(含 Node.js Buffer)/Uint8ClampedArray/ArrayBuffer/DataView/Blob/Response 编码为
+//   {"$binary": base64, "mime": 类型},mime 为空时由服务端根据内容识别;其他类型化数组转换为数组
+// - undefined、函数和 Symbol 在对象中省略,在数组中为 null
+// - 带 toJSON 方法的对象使用其返回值,其他对象(包括类实例)转换为自有可枚举属性组成的对象
+// - 循环引用转换为 {"$ref": 路径},路径为被引用的外层对象的位置,如 "$"、"$.items[0]"
+async function runCodeToJsonable(value, path = "$", ancestors = new Map()) {
+    switch (typeof value) {
+        case 'bigint':
+            return value.toString();
+        case 'number':
+            return Number.isFinite(value) ? value : null;
+        case 'string':
+        case 'boolean':
+            return value;
+        case 'object':
+            break;
+        default:
+            // undefined、函数和 Symbol
+            return undefined;
+    }
+    if (value === null) {
+        return null;
+    }
+    if (ancestors.has(value)) {
+        return { $ref: ancestors.get(value) };
+    }
+    const binary = await runCodeEncodeBinary(value);
+    if (binary !== null) {
+        return binary;
+    }
+    if (value instanceof Date) {
+        return Number.isNaN(value.getTime()) ? null : value.toISOString();
+    }
+    if (value instanceof RegExp) {
+        return value.toString();
+    }
+    if (value instanceof Error) {
+        return { name: value.name, message: value.message };
+    }
+    if (value instanceof Number || value instanceof String || value instanceof Boolean) {
+        return runCodeToJsonable(value.valueOf(), path, ancestors);
+    }
+    ancestors.set(value, path);
+    try {
+        if (Array.isArray(value) || value instanceof Set || ArrayBuffer.isView(value)) {
+            const items = [];
+            let index = 0;
+            for (const item of value) {
+                const converted = await runCodeToJsonable(item, `${path}[${index++}]`, ancestors);
+                items.push(converted === undefined ? null : converted);
+            }
+            return items;
+        }
+        if (value instanceof Map) {
+            const plainKeys = [...value.keys()].every(key => ['string', 'number', 'boolean'].includes(typeof key));
+            if (!plainKeys) {
+                const entries = [];
+                let index = 0;
+                for (const [key, item] of value) {
+                    const entryPath = `${path}[${index++}]`;
+                    const convertedKey = await runCodeToJsonable(key, `${entryPath}[0]`, ancestors);
+                    const converted = await runCodeToJsonable(item, `${entryPath}[1]`, ancestors);
+                    entries.push([convertedKey === undefined ? null : convertedKey, converted === undefined ? null : converted]);
+                }
+                return entries;
+            }
+            const object = {};
+            for (const [key, item] of value) {
+                const converted = await runCodeToJsonable(item, runCodeJsonPath(path, String(key)), ancestors);
+                if (converted !== undefined) {
+                    object[String(key)] = converted;
+                }
+            }
+            return object;
+        }
+        if (typeof value.toJSON === 'function') {
+            return runCodeToJsonable(value.toJSON(), path, ancestors);
+        }
+        const object = {};
+        for (const [key, item] of Object.entries(value)) {
+            const converted = await runCodeToJsonable(item, runCodeJsonPath(path, key), ancestors);
+            if (converted !== undefined) {
+                object[key] = converted;
+            }
+        }
+        return object;
+    } finally {
+        ancestors.delete(value);
+    }
+}
+
+// 循环引用标记中属性的路径,不是合法标识符的属性名使用 ["键"] 形式
+function runCodeJsonPath(path, key) {
+    return /^[A-Za-z_$][\w$]*$/.test(key) ? `${path}.${key}` : `${path}[${JSON.stringify(key)}]`;
+}
+
+// 二进制数据编码为 {"$binary": base64, "mime": 类型},不是二进制数据时返回 null
+async function runCodeEncodeBinary(value) {
+    let bytes = null;
+    let mime = null;
+    if (typeof Response !== 'undefined' && value instanceof Response) {
+        mime = value.headers.get('content-type');
+        bytes = new Uint8Array(await value.arrayBuffer());
+    } else if (typeof Blob !== 'undefined' && value instanceof Blob) {
+        mime = value.type || null;
+        bytes = new Uint8Array(await value.arrayBuffer());
+    } else if (value instanceof ArrayBuffer) {
+        bytes = new Uint8Array(value);
+    } else if (value instanceof Uint8Array || value instanceof Uint8ClampedArray || value instanceof DataView) {
+        bytes = new Uint8Array(value.buffer, value.byteOffset, value.byteLength);
+    }
+    if (bytes === null) {
+        return null;
+    }
+    let binary = '';
+    for (let i = 0; i < bytes.length; i += 0x8000) {
+        binary += String.fromCharCode(...bytes.subarray(i, i + 0x8000));
+    }
+    return { $binary: btoa(binary), mime: mime };
+}
+
+// handler 的最终结果转换为输出的文本: 对象序列化为JSON,其他值转换为字符串,服务端会解析JSON格式的文本;
+// 直接返回 BigInt 时序列化为JSON字符串,避免十进制字符串被服务端解析为数字而丢失精度
+function runCodeResultText(original, converted) {
+    if (converted === undefined) {
+        return null;
+    }
+    if (typeof converted === 'object' || typeof original === 'bigint') {
+        return JSON.stringify(converted);
+    }
+    return String(converted);
+}
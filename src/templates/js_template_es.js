@@ -1,25 +1,7 @@
 // @ts-nocheck
 // ES模块格式，支持import/export语句
 
-// 保存原始console.log
-const originalConsoleLog = console.log;
-let logs = [];
-
-// 替换console.log以捕获日志
-console.log = function() {
-    // 将参数转换为字符串并连接它们
-    const message = Array.from(arguments).map(arg =>
-        typeof arg === 'object' && arg !== null ? JSON.stringify(arg) : String(arg)
-    ).join(' ');
-
-    // 存储日志
-    logs.push(message);
-
-    // 如果显示日志，也输出到原始控制台
-    if ({{SHOW_LOGS}}) {
-        originalConsoleLog.apply(console, arguments);
-    }
-};
+{{JS_HARNESS}}
 
 // 从环境变量获取输入参数
 let input = {};
@@ -33,6 +15,8 @@ try {
     console.error("解析输入参数失败:", error);
 }
 
+{{JSON_SERIALIZER}}
+
 // 用户代码
 {{USER_CODE}}
 
@@ -42,37 +26,19 @@ let result = null;
 // 异步立即执行函数
 (async () => {
     try {
-        // 优先检查main函数
-        if (typeof main === 'function') {
-            // 检查main是否是异步函数
-            if (main.constructor.name === 'AsyncFunction') {
-                result = await main(input);
-            } else {
-                result = main(input);
-            }
-        } else if (typeof handler === 'function') {
-            // 如果没有main函数，检查handler
-            if (handler.constructor.name === 'AsyncFunction') {
-                result = await handler(input);
-            } else {
-                result = handler(input);
-            }
-        }else{
-            throw new Error("没有找到main或handler函数");
-        }
+        // 查找并执行入口函数(第二个参数为调用上下文),同步函数的返回值直接使用
+        result = await runCodeResolveEntrypoint({{USER_FUNCTIONS}})(input, runCodeCreateContext());
+
+        // 生成器函数逐个输出产生的数据块
+        result = await runCodeCollectChunks(result);
+        // 转换为可以JSON序列化的值,二进制数据编码为base64
+        const value = await runCodeToJsonable(result);
 
-        // 打印最终输出为JSON
-        originalConsoleLog(JSON.stringify({
-            logs: logs,
-            result: result !== undefined ? (typeof result === 'object' ? JSON.stringify(result) : String(result)) : null,
-            error: null
-        }));
+        // 等待一轮事件循环后打印最终输出为JSON并退出
+        await runCodeSettle();
+        runCodeFinish(runCodeResultText(result, value), null);
     } catch (error) {
         // 处理错误
-        originalConsoleLog(JSON.stringify({
-            logs: logs,
-            result: null,
-            error: error.toString()
-        }));
+        runCodeFinish(null, error.toString());
     }
 })(); 
\ No newline at end of file
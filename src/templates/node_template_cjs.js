@@ -0,0 +1,47 @@
+// Node.js CommonJS格式，支持require和module.exports
+
+{{JS_HARNESS}}
+
+// 从环境变量获取输入参数
+let input = {};
+try {
+    const inputJson = process.env.INPUT_JSON;
+    if (inputJson) {
+        input = JSON.parse(inputJson);
+        console.log("接收到的参数:", JSON.stringify(input));
+    }
+} catch (error) {
+    console.error("解析输入参数失败:", error);
+}
+
+{{JSON_SERIALIZER}}
+
+// 异步立即执行函数
+(async () => {
+    try {
+        // 用户代码开始
+        {{USER_CODE}}
+        // 用户代码结束
+
+        // 查找并执行入口函数,支持直接定义的函数和module.exports导出的函数,直接定义的函数优先
+        const runCodeFunctions = Object.assign({}, module.exports);
+        for (const [name, fn] of Object.entries({{USER_FUNCTIONS}})) {
+            if (typeof fn === 'function') {
+                runCodeFunctions[name] = fn;
+            }
+        }
+        let result = await runCodeResolveEntrypoint(runCodeFunctions)(input, runCodeCreateContext());
+
+        // 生成器函数逐个输出产生的数据块
+        result = await runCodeCollectChunks(result);
+        // 转换为可以JSON序列化的值,二进制数据编码为base64
+        const value = await runCodeToJsonable(result);
+
+        // 等待一轮事件循环后打印最终输出为JSON并退出
+        await runCodeSettle();
+        runCodeFinish(runCodeResultText(result, value), null);
+    } catch (error) {
+        // 处理错误
+        runCodeFinish(null, error.toString());
+    }
+})();
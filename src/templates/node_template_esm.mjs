@@ -0,0 +1,40 @@
+// Node.js ES模块格式，支持import/export语句
+
+{{JS_HARNESS}}
+
+// 从环境变量获取输入参数
+let input = {};
+try {
+    const inputJson = process.env.INPUT_JSON;
+    if (inputJson) {
+        input = JSON.parse(inputJson);
+        console.log("接收到的参数:", JSON.stringify(input));
+    }
+} catch (error) {
+    console.error("解析输入参数失败:", error);
+}
+
+{{JSON_SERIALIZER}}
+
+// 用户代码
+{{USER_CODE}}
+
+// 异步立即执行函数
+(async () => {
+    try {
+        // 查找并执行入口函数(第二个参数为调用上下文),同步函数的返回值直接使用
+        let result = await runCodeResolveEntrypoint({{USER_FUNCTIONS}})(input, runCodeCreateContext());
+
+        // 生成器函数逐个输出产生的数据块
+        result = await runCodeCollectChunks(result);
+        // 转换为可以JSON序列化的值,二进制数据编码为base64
+        const value = await runCodeToJsonable(result);
+
+        // 等待一轮事件循环后打印最终输出为JSON并退出
+        await runCodeSettle();
+        runCodeFinish(runCodeResultText(result, value), null);
+    } catch (error) {
+        // 处理错误
+        runCodeFinish(null, error.toString());
+    }
+})();
@@ -2,96 +2,478 @@ import sys
 import json
 import os
 import logging
-from io import StringIO
+import warnings
 
-# 保存原始的stdout
+# 保存原始的stdout和stderr
 original_stdout = sys.stdout
+_run_code_original_stderr = sys.stderr
 logs = []
+_run_code_log_entries = []
+_run_code_show_logs = {{SHOW_LOGS}}
 
-# 创建一个StringIO对象来捕获输出
+def _run_code_add_log(message, level=None, logger=None, stream="stdout"):
+    """记录一条日志: logs 中为 "[级别] 日志记录器名称: 消息" 格式的文本(根日志记录器省略名称,
+    没有级别的标准错误以 "[stderr] " 开头),log_entries 中为结构化的日志记录;
+    显示日志时同时输出到原始的stdout,保证实时转发的日志按行分隔"""
+    if level:
+        name = f"{logger}: " if logger and logger != "root" else ""
+        text = f"[{level}] {name}{message}"
+    elif stream == "stderr":
+        text = f"[stderr] {message}"
+    else:
+        text = message
+    logs.append(text)
+    _run_code_log_entries.append({
+        'level': level,
+        'logger': logger,
+        'message': message,
+        'stream': stream,
+    })
+    if _run_code_show_logs:
+        original_stdout.write(text + "\n")
+        original_stdout.flush()
+
+# 按行捕获写入的文本: 不以换行结尾的内容先缓存,换行或执行结束时才作为一条日志记录;
+# 一行中有回车(如进度条)时只保留最后一个回车之后的内容,stream 为捕获的流
 class LogCapture:
-    def __init__(self, show_logs=False):
-        self.buffer = StringIO()
-        self.show_logs = show_logs
-    
+    encoding = "utf-8"
+
+    def __init__(self, stream="stdout"):
+        self.stream = stream
+        self.pending = ""
+
     def write(self, text):
-        if text.strip():  # 忽略空行
-            logs.append(text.rstrip())
-            if self.show_logs:
-                original_stdout.write(text)
-    
+        *lines, self.pending = (self.pending + text).split("\n")
+        for line in lines:
+            self._emit(line)
+        return len(text)
+
+    def _emit(self, line):
+        line = line.rstrip("\r").rsplit("\r", 1)[-1].rstrip()
+        if line.strip():  # 忽略空行
+            _run_code_add_log(line, stream=self.stream)
+
     def flush(self):
-        self.buffer.flush()
-        if self.show_logs:
-            original_stdout.flush()
+        # 与终端一致,flush 不会结束当前行
+        pass
+
+    def flush_pending(self):
+        """记录缓存的不完整的行"""
+        pending, self.pending = self.pending, ""
+        self._emit(pending)
+
+    def isatty(self):
+        return False
+
+    def writable(self):
+        return True
+
+def _run_code_restore_output():
+    """记录缓存的不完整的行,恢复原始的stdout和stderr"""
+    for stream in (sys.stdout, sys.stderr):
+        if isinstance(stream, LogCapture):
+            stream.flush_pending()
+    sys.stdout = original_stdout
+    sys.stderr = _run_code_original_stderr
+
+# 替换sys.stdout和sys.stderr为我们的捕获器,标准错误的日志以 "[stderr] " 开头
+sys.stdout = LogCapture()
+sys.stderr = LogCapture(stream="stderr")
 
-# 替换sys.stdout为我们的捕获器
-sys.stdout = LogCapture(show_logs={{SHOW_LOGS}})
+# 配置logging: 日志记录为 "[级别] 日志记录器名称: 消息",根日志记录器省略名称;
+# 消息包括异常信息(调用栈的行号对应用户代码中的行),多行的消息作为一条日志
+class LoggingFormatter(logging.Formatter):
+    def formatException(self, ei):
+        return _run_code_format_exception(ei[1])
 
-# 配置logging，将日志输出重定向到我们的捕获器
 class LoggingHandler(logging.Handler):
     def emit(self, record):
-        msg = self.format(record)
-        print(f"[{record.levelname}] {msg}")
+        try:
+            msg = self.format(record)
+        except Exception:
+            self.handleError(record)
+            return
+        # 与 logging 默认的处理程序一致,日志记录输出到标准错误
+        _run_code_add_log(msg, level=record.levelname, logger=record.name, stream="stderr")
 
 # 配置根日志记录器
 root_logger = logging.getLogger()
 root_logger.setLevel(logging.INFO)
 # 移除所有现有的处理程序
-for handler in root_logger.handlers[:]:
-    root_logger.removeHandler(handler)
-# 添加我们自定义的处理程序
-handler = LoggingHandler()
-handler.setFormatter(logging.Formatter('%(message)s'))
-root_logger.addHandler(handler)
+for _run_code_log_handler in root_logger.handlers[:]:
+    root_logger.removeHandler(_run_code_log_handler)
+# 添加我们自定义的处理程序(不使用 handler 作为变量名,避免与用户的入口函数混淆)
+_run_code_log_handler = LoggingHandler()
+_run_code_log_handler.setFormatter(LoggingFormatter('%(message)s'))
+root_logger.addHandler(_run_code_log_handler)
+
+# warnings 的警告记录为 "[WARNING] 警告类型: 消息",来自用户代码时附加所在的位置
+def _run_code_show_warning(message, category, filename, lineno, file=None, line=None):
+    location = _run_code_location(filename, lineno)
+    suffix = f" ({location})" if location else ""
+    _run_code_add_log(f"{category.__name__}: {message}{suffix}", level="WARNING", stream="stderr")
+
+warnings.showwarning = _run_code_show_warning
 
 # 从环境变量获取输入参数
 args = {}
-has_input = False
 try:
     input_json = os.environ.get('INPUT_JSON')
     if input_json:
         args = json.loads(input_json)
-        has_input = True
         print(f"接收到的参数: {args}")
-        
-        # 确保参数同时可以通过args直接访问，也可以通过args.get("params")访问
-        # 如果args中没有params键，但有其他键，则将整个args作为params的值
-        if "params" not in args and len(args) > 0:
-            args["params"] = args.copy()
-        # 如果params存在但为None，则初始化为空字典
-        elif args.get("params") is None:
-            args["params"] = {}
 except Exception as e:
     print(f"解析输入参数失败: {e}")
 
+def _run_code_convert_args(func, value):
+    """如果函数第一个参数的类型注解是 pydantic 模型,将参数字典转换为模型实例"""
+    model = None
+    try:
+        import inspect
+        import typing
+        parameters = list(inspect.signature(func).parameters.values())
+        if parameters:
+            model = typing.get_type_hints(func).get(parameters[0].name)
+    except Exception:
+        return value
+    if not isinstance(value, dict) or not hasattr(model, "model_validate"):
+        return value
+    return model.model_validate(value)
+
+def _run_code_encode_binary(value):
+    """handler返回的二进制数据(bytes/bytearray/memoryview/io.BytesIO/PIL图片)编码为
+    {"$binary": base64, "mime": 类型},mime 为空时由服务端根据内容识别;嵌套的数据由 _run_code_jsonable 逐个编码"""
+    import base64
+    import io
+    mime = None
+    if isinstance(value, io.BytesIO):
+        value = value.getvalue()
+    elif type(value).__module__.startswith("PIL.") and hasattr(value, "save"):
+        image_format = getattr(value, "format", None) or "PNG"
+        buffer = io.BytesIO()
+        value.save(buffer, format=image_format)
+        mime = "image/" + image_format.lower()
+        value = buffer.getvalue()
+    if isinstance(value, (bytes, bytearray, memoryview)):
+        return {"$binary": base64.b64encode(bytes(value)).decode("ascii"), "mime": mime}
+    return value
+
+# 结果转换使用的标准库,使用别名避免被用户代码中的同名变量覆盖
+import dataclasses as _run_code_dataclasses
+import datetime as _run_code_datetime
+import decimal as _run_code_decimal
+import enum as _run_code_enum
+import io as _run_code_io
+import math as _run_code_math
+import pathlib as _run_code_pathlib
+import uuid as _run_code_uuid
+
+_run_code_encoders = []
+
+def register_encoder(value_type, encoder):
+    """注册自定义类型的JSON转换函数,返回值会继续按内置规则转换;后注册的优先,也可以覆盖内置的转换"""
+    _run_code_encoders.insert(0, (value_type, encoder))
+
+def _run_code_jsonable(value):
+    """把handler的结果转换为可以JSON序列化的值: 日期时间为ISO格式,Decimal为数字,集合为列表,
+    dataclass/pydantic模型为字典,numpy标量和数组为数字和列表,pandas DataFrame 按
+    RUN_CODE_DATAFRAME_ORIENT(默认 records)转换,NaN/Infinity 为 null,其他无法转换的值为 str(value)"""
+    for value_type, encoder in _run_code_encoders:
+        if isinstance(value, value_type):
+            return _run_code_jsonable(encoder(value))
+    if value is None or isinstance(value, (bool, int, str)):
+        return value
+    if isinstance(value, float):
+        return value if _run_code_math.isfinite(value) else None
+    if isinstance(value, dict):
+        return {_run_code_json_key(k): _run_code_jsonable(v) for k, v in value.items()}
+    if isinstance(value, (list, tuple)):
+        return [_run_code_jsonable(v) for v in value]
+    if isinstance(value, (set, frozenset)):
+        items = [_run_code_jsonable(v) for v in value]
+        try:
+            return sorted(items)
+        except TypeError:
+            return items
+    module = type(value).__module__
+    if isinstance(value, (bytes, bytearray, memoryview, _run_code_io.BytesIO)) or (
+        module.startswith("PIL.") and hasattr(value, "save")
+    ):
+        return _run_code_encode_binary(value)
+    if module.startswith("pandas"):
+        return _run_code_pandas_jsonable(value)
+    if isinstance(value, (_run_code_datetime.datetime, _run_code_datetime.date, _run_code_datetime.time)):
+        return value.isoformat()
+    if isinstance(value, _run_code_datetime.timedelta):
+        return value.total_seconds()
+    if isinstance(value, _run_code_decimal.Decimal):
+        if not value.is_finite():
+            return None
+        return int(value) if value == value.to_integral_value() else float(value)
+    if isinstance(value, (_run_code_uuid.UUID, _run_code_pathlib.PurePath)):
+        return str(value)
+    if isinstance(value, _run_code_enum.Enum):
+        return _run_code_jsonable(value.value)
+    if _run_code_dataclasses.is_dataclass(value) and not isinstance(value, type):
+        return {f.name: _run_code_jsonable(getattr(value, f.name)) for f in _run_code_dataclasses.fields(value)}
+    if hasattr(value, "model_dump"):
+        return _run_code_jsonable(value.model_dump())
+    if hasattr(value, "__fields__") and hasattr(value, "dict"):
+        return _run_code_jsonable(value.dict())
+    if module.startswith("numpy") and hasattr(value, "tolist"):
+        return _run_code_jsonable(value.tolist())
+    return str(value)
+
+def _run_code_json_key(key):
+    """字典的键转换为字符串,如 pandas 时间索引转换为ISO格式"""
+    if isinstance(key, str):
+        return key
+    key = _run_code_jsonable(key)
+    return key if isinstance(key, str) else json.dumps(key)
+
+def _run_code_pandas_jsonable(value):
+    """pandas 对象转换为可以JSON序列化的值,DataFrame 的格式与 DataFrame.to_dict(orient) 一致"""
+    type_name = type(value).__name__
+    if type_name in ("NaTType", "NAType"):
+        return None
+    orient = os.environ.get('RUN_CODE_DATAFRAME_ORIENT') or "records"
+    if type_name == "DataFrame":
+        return _run_code_jsonable(value.to_dict(orient=orient))
+    if type_name == "Series":
+        if orient in ("split", "tight"):
+            return _run_code_jsonable({"name": value.name, "index": value.index.tolist(), "data": value.tolist()})
+        if orient in ("records", "list"):
+            return _run_code_jsonable(value.tolist())
+        return _run_code_jsonable(value.to_dict())
+    if hasattr(value, "isoformat"):
+        return value.isoformat()
+    if hasattr(value, "tolist"):
+        return _run_code_jsonable(value.tolist())
+    return str(value)
+
+def _run_code_emit_chunk(chunk):
+    """输出生成器产生的一个数据块"""
+    chunk = _run_code_jsonable(chunk)
+    original_stdout.write("__RUN_CODE_CHUNK__" + json.dumps(chunk, default=str) + "\n")
+    original_stdout.flush()
+
+class _RunCodeTimeout(TimeoutError):
+    """异步handler超过截止时间"""
+
+def _run_code_remaining_seconds():
+    """距离截止时间(RUN_CODE_DEADLINE,Unix时间戳秒)的剩余秒数,未设置时为 None"""
+    import time
+    try:
+        deadline = float(os.environ.get('RUN_CODE_DEADLINE', ''))
+    except ValueError:
+        return None
+    return max(deadline - time.time(), 0)
+
+def _run_code_run_async(awaitable):
+    """在新的事件循环中执行协程,超过截止时间时取消协程并抛出超时错误"""
+    import asyncio
+
+    async def run():
+        timeout = _run_code_remaining_seconds()
+        try:
+            return await asyncio.wait_for(awaitable, timeout)
+        except asyncio.TimeoutError:
+            raise _RunCodeTimeout(f"async handler timed out after {timeout:.1f}s") from None
+
+    return asyncio.run(run())
+
+def _run_code_await_result(value):
+    """async def 定义的handler(或返回协程的函数)返回的协程在事件循环中执行,其他结果原样返回"""
+    import inspect
+    if inspect.isawaitable(value):
+        return _run_code_run_async(value)
+    return value
+
+def _run_code_collect_chunks(value):
+    """如果handler是生成器或异步生成器,逐个输出产生的数据块;
+    结果为生成器的返回值,没有返回值时为所有数据块组成的列表"""
+    import inspect
+    chunks = []
+    if inspect.isasyncgen(value):
+        async def collect():
+            async for chunk in value:
+                _run_code_emit_chunk(chunk)
+                chunks.append(chunk)
+
+        _run_code_run_async(collect())
+        return chunks
+    if not inspect.isgenerator(value):
+        return value
+    while True:
+        try:
+            chunk = next(value)
+        except StopIteration as stop:
+            return stop.value if stop.value is not None else chunks
+        _run_code_emit_chunk(chunk)
+        chunks.append(chunk)
+
+class _RunCodeHandlerNotFound(Exception):
+    """没有找到入口函数"""
+
+def _run_code_resolve_entrypoint():
+    """查找入口函数: 指定 RUN_CODE_ENTRYPOINT 时按名称查找,否则依次查找 handler、main;
+    找不到时列出用户代码中定义的函数"""
+    import inspect
+    entrypoint = os.environ.get('RUN_CODE_ENTRYPOINT')
+    candidates = [entrypoint] if entrypoint else ['handler', 'main']
+    for name in candidates:
+        func = globals().get(name)
+        if callable(func) and not inspect.isclass(func) and not name.startswith('_run_code'):
+            return name, func
+    available = [
+        name for name, value in globals().items()
+        if inspect.isfunction(value) and value.__module__ == __name__ and not name.startswith('_')
+        and name not in _run_code_template_names
+    ]
+    raise _RunCodeHandlerNotFound(
+        f"no function named {' or '.join(candidates)}; "
+        f"available functions: {', '.join(available) or '(none)'}"
+    )
+
+class _RunCodeContext:
+    """handler 的调用上下文: 调用ID、截止时间、函数名称和版本、语言、日志记录器和声明的环境变量(只读)"""
+
+    def __init__(self, info):
+        import types
+        self.invocation_id = info.get("invocation_id")
+        self.function_name = info.get("function_name")
+        self.function_version = info.get("function_version")
+        self.language = info.get("language")
+        # 截止时间为Unix时间戳(秒),与 time.time() 一致
+        self.deadline = info.get("deadline")
+        self.logger = logging.getLogger()
+        self.env = types.MappingProxyType(dict(info.get("env") or {}))
+
+    def get_remaining_time_in_millis(self):
+        """距离截止时间的剩余毫秒数"""
+        import time
+        if self.deadline is None:
+            return None
+        return max(int((self.deadline - time.time()) * 1000), 0)
+
+    def __repr__(self):
+        return f"Context(invocation_id={self.invocation_id!r}, function_name={self.function_name!r})"
+
+def _run_code_create_context():
+    """根据 RUN_CODE_CONTEXT 环境变量创建调用上下文"""
+    try:
+        info = json.loads(os.environ.get('RUN_CODE_CONTEXT') or '{}')
+    except ValueError as e:
+        logging.warning(f"解析调用上下文失败: {e}")
+        info = {}
+    return _RunCodeContext(info)
+
+def _run_code_call_args(func):
+    """入口函数没有参数时不传参,否则传入参数字典(或转换后的 pydantic 模型);
+    声明了第二个位置参数(或 *args)时同时传入调用上下文"""
+    import inspect
+    try:
+        parameters = list(inspect.signature(func).parameters.values())
+    except (TypeError, ValueError):
+        return [_run_code_convert_args(func, args)]
+    if not parameters:
+        return []
+    positional = [
+        p for p in parameters
+        if p.kind in (p.POSITIONAL_ONLY, p.POSITIONAL_OR_KEYWORD, p.VAR_POSITIONAL)
+    ]
+    call_args = [_run_code_convert_args(func, args)]
+    if len(positional) >= 2 or any(p.kind == p.VAR_POSITIONAL for p in positional):
+        call_args.append(_run_code_create_context())
+    return call_args
+
+def _run_code_user_lines():
+    """用户代码在脚本中的行号范围 (start, end],用户代码第 n 行对应脚本第 start + n 行;
+    添加依赖时 uv 会在脚本开头插入元数据,因此在运行时根据标记行计算"""
+    try:
+        with open(__file__, encoding="utf-8") as f:
+            lines = f.read().splitlines()
+        start = lines.index("# 用户代码开始") + 1
+        end = len(lines) - lines[::-1].index("# 用户代码结束") - 1
+        return start, end
+    except (OSError, ValueError):
+        return 0, 0
+
+def _run_code_location(filename, lineno):
+    """脚本中的位置转换为用户代码中的位置,如 `File "<user code>", line 3`;
+    多文件项目中其他文件显示相对路径,不在用户代码中时返回 None"""
+    script_path = os.path.abspath(__file__)
+    script_dir = os.path.dirname(script_path)
+    filename = os.path.abspath(filename)
+    if filename == script_path:
+        start, end = _run_code_user_lines()
+        if not start < lineno <= end:
+            return None
+        return f'File "<user code>", line {lineno - start}'
+    if filename.startswith(script_dir + os.sep) and "site-packages" not in filename:
+        return f'File "{os.path.relpath(filename, script_dir)}", line {lineno}'
+    return None
+
+def _run_code_format_exception(e):
+    """用户代码抛出的异常转换为错误信息: 第一行为 `异常类型: 消息`,之后为只包含用户代码的调用栈,
+    行号对应用户代码中的行;多文件项目中其他文件的调用栈显示相对路径"""
+    import traceback
+    frames = []
+    for frame in traceback.extract_tb(e.__traceback__):
+        location = _run_code_location(frame.filename, frame.lineno)
+        if location is None:
+            continue
+        frames.append(f"  {location}, in {frame.name}")
+        if frame.line:
+            frames.append(f"    {frame.line.strip()}")
+    message = "".join(traceback.format_exception_only(type(e), e)).strip()
+    if not frames:
+        return message
+    return message + "\nTraceback (most recent call last):\n" + "\n".join(frames)
+
+def _run_code_emit_output(result_json, error):
+    """恢复原始的输出,打印包含日志、结果和错误的最终输出"""
+    _run_code_restore_output()
+    print(json.dumps({
+        'logs': logs,
+        'log_entries': _run_code_log_entries,
+        'result': result_json,
+        'error': error
+    }))
+
+def _run_code_module_error(exc_type, exc, tb):
+    """用户代码在模块级别抛出的异常(如导入失败、未定义的名称)不会进入下面的 try,
+    通过 excepthook 与入口函数抛出的异常一样作为执行错误输出"""
+    sys.excepthook = sys.__excepthook__
+    _run_code_emit_output(None, _run_code_format_exception(exc))
+
+sys.excepthook = _run_code_module_error
+
+# 模板中定义的名称,不作为用户代码中可用的函数列出
+_run_code_template_names = set(globals())
+
 # 用户代码开始
 {{USER_CODE}}
+# 用户代码结束
 
 try:
-    # 执行handler函数或main函数并获取结果
+    # 查找并执行入口函数
     result = None
-    if 'handler' in globals() and callable(globals()['handler']):
-        # 优先使用 handler 函数
-        try:
-            if has_input:
-                result = handler(args)
-            else:
-                result = handler()
-            # 确保结果不为 None
-            if result is None:
-                print("警告: handler 函数返回了 None")
-        except Exception as e:
-            print(f"执行 handler 函数时出错: {e}")
-    elif 'main' in globals() and callable(globals()['main']):
-        # 如果没有 handler 函数，尝试使用 main 函数
-        try:
-            result = main(args)
-        except Exception as e:
-            print(f"执行 main 函数时出错: {e}")
-    
+    _run_code_name, _run_code_func = _run_code_resolve_entrypoint()
+    # 入口函数抛出的异常作为执行错误返回
+    result = _run_code_func(*_run_code_call_args(_run_code_func))
+    # 异步handler在事件循环中执行,超时时间作为截止时间
+    result = _run_code_await_result(result)
+    # 生成器函数逐个输出产生的数据块
+    result = _run_code_collect_chunks(result)
+    # 确保结果不为 None
+    if result is None:
+        print(f"警告: {_run_code_name} 函数返回了 None")
+
+    # 转换为可以JSON序列化的值,二进制数据编码为base64
+    result = _run_code_jsonable(result)
+
     # 打印最终输出为JSON
-    sys.stdout = original_stdout
     # 根据结果类型选择合适的处理方式
     result_json = None
     if result is not None:
@@ -104,20 +486,14 @@ try:
         else:
             # 其他类型（如字符串）转换为字符串
             result_json = str(result)
-    
-    print(json.dumps({
-        'logs': logs,
-        'result': result_json,
-        'error': None
-    }))
+
+    _run_code_emit_output(result_json, None)
 except Exception as e:
     # 处理错误
-    import traceback
-    error_msg = f"{str(e)}\n{traceback.format_exc()}"
-    # 处理错误
-    sys.stdout = original_stdout
-    print(json.dumps({
-        'logs': logs,
-        'result': None,
-        'error': error_msg
-    })) 
\ No newline at end of file
+    if isinstance(e, _RunCodeHandlerNotFound):
+        error_msg = f"HandlerNotFound: {e}"
+    elif isinstance(e, _RunCodeTimeout):
+        error_msg = f"TimeoutError: {e}"
+    else:
+        error_msg = _run_code_format_exception(e)
+    _run_code_emit_output(None, error_msg)
@@ -0,0 +1,67 @@
+#!/usr/bin/env bash
+# Shell脚本模板,用户代码需要定义入口函数(默认依次查找 handler、main,可通过 RUN_CODE_ENTRYPOINT 指定)
+# 参数: INPUT_JSON 为完整的JSON参数, PARAM_* 为扁平化后的参数(如 PARAM_USER_NAME)
+# 调用上下文: handler 的第二个参数为调用上下文的JSON(invocation_id、deadline、function_name 等),
+#           声明的环境变量直接设置为环境变量
+# 结果: handler 通过 set_result 或直接向文件描述符3(结果通道)写入JSON文档
+# 日志: handler 的标准输出和标准错误都会作为日志捕获,log_debug/log_info/log_warn/log_error 输出带级别的日志
+
+set -o pipefail
+
+# 打开结果通道
+exec 3>"${RUN_CODE_RESULT_FILE:-/dev/null}"
+
+# 写入执行结果到结果通道,参数为JSON文档(非JSON内容按字符串处理)
+set_result() {
+    printf '%s' "$1" >&3
+}
+
+# 输出带级别的日志,格式与其他语言一致,如 "[INFO] 消息"
+log_debug() {
+    echo "[DEBUG] $*"
+}
+log_info() {
+    echo "[INFO] $*"
+}
+log_warn() {
+    echo "[WARNING] $*"
+}
+log_error() {
+    echo "[ERROR] $*"
+}
+
+# 模板提供的函数,不作为用户代码中可用的函数列出
+run_code_template_functions=" set_result log_debug log_info log_warn log_error "
+
+# 用户代码开始
+{{USER_CODE}}
+# 用户代码结束
+
+# 查找入口函数: 指定 RUN_CODE_ENTRYPOINT 时按名称查找,否则依次查找 handler、main
+if [ -n "${RUN_CODE_ENTRYPOINT:-}" ]; then
+    run_code_candidates=("$RUN_CODE_ENTRYPOINT")
+else
+    run_code_candidates=(handler main)
+fi
+run_code_entrypoint=""
+for run_code_name in "${run_code_candidates[@]}"; do
+    if declare -F "$run_code_name" > /dev/null; then
+        run_code_entrypoint="$run_code_name"
+        break
+    fi
+done
+
+if [ -z "$run_code_entrypoint" ]; then
+    # 列出用户代码中定义的函数(不包括模板提供的函数)
+    run_code_available=""
+    while read -r _ _ run_code_name; do
+        if [[ "$run_code_template_functions" != *" $run_code_name "* ]]; then
+            run_code_available="${run_code_available:+$run_code_available, }$run_code_name"
+        fi
+    done < <(declare -F)
+    run_code_wanted="${run_code_candidates[*]}"
+    echo "HandlerNotFound: no function named ${run_code_wanted// / or }; available functions: ${run_code_available:-(none)}" >&2
+    exit 127
+fi
+
+"$run_code_entrypoint" "$INPUT_JSON" "${RUN_CODE_CONTEXT:-{\}}"
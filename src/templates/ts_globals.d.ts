@@ -0,0 +1,32 @@
+// TypeScript 类型检查使用的全局类型声明,与模板(js_harness.js)提供给用户代码的内容一致;
+// 用户代码可以直接使用这些类型,如 function handler(input: Input, context: InvocationContext)
+
+/** 带级别的日志记录器,输出格式如 "[INFO] 消息" */
+interface InvocationLogger {
+    debug(...args: unknown[]): void;
+    info(...args: unknown[]): void;
+    warn(...args: unknown[]): void;
+    error(...args: unknown[]): void;
+}
+
+/** handler 的第二个参数: 调用上下文 */
+interface InvocationContext {
+    /** 本次调用的唯一标识 */
+    readonly invocationId: string;
+    /** 已部署函数的名称,直接执行代码时为 null */
+    readonly functionName: string | null;
+    /** 已部署函数的版本号,直接执行代码时为 null */
+    readonly functionVersion: number | null;
+    /** 语言标识 */
+    readonly language: string;
+    /** 执行截止时间(Unix时间戳,毫秒),与 Date.now() 一致 */
+    readonly deadline: number;
+    /** 距离截止时间的剩余毫秒数 */
+    getRemainingTimeInMillis(): number;
+    readonly logger: InvocationLogger;
+    /** 声明的环境变量(只读) */
+    readonly env: Readonly<Record<string, string>>;
+}
+
+/** 输入参数,与 handler 的第一个参数相同 */
+declare const input: any;
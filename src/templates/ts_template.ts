@@ -1,27 +1,11 @@
 // TypeScript类型声明
 // @ts-nocheck
-type LogFunction = (...args: any[]) => void;
 type Handler = (input: any) => any;
 
-// Save original console.log
-const originalConsoleLog: LogFunction = console.log;
-let logs: string[] = [];
+// 用户代码中的静态 import 语句,需要放在模块顶层
+{{USER_IMPORTS}}
 
-// Replace console.log to capture logs
-console.log = function(...args: any[]): void {
-    // Convert arguments to string and join them
-    const message = args.map(arg =>
-        typeof arg === 'object' && arg !== null ? JSON.stringify(arg) : String(arg)
-    ).join(' ');
-
-    // Store log
-    logs.push(message);
-
-    // Also log to original console if showing logs
-    if ({{SHOW_LOGS}}) {
-        originalConsoleLog.apply(console, args);
-    }
-};
+{{JS_HARNESS}}
 
 // 从环境变量获取输入参数
 let input: any = {};
@@ -35,46 +19,27 @@ try {
     console.error("解析输入参数失败:", error);
 }
 
+{{JSON_SERIALIZER}}
+
 async function executeHandler() {
     try {
         // Add the original code
         {{USER_CODE}}
 
-        // Execute handler function and get result
-        let result: any = null;
-        
-        // 优先检查main函数
-        if (typeof main === 'function') {
-            // 检查main是否是异步函数
-            if (main.constructor.name === 'AsyncFunction') {
-                result = await (main as (input: any) => Promise<any>)(input);
-            } else {
-                result = (main as Handler)(input);
-            }
-        } else if (typeof handler === 'function') {
-            // 如果没有main函数，检查handler
-            if (handler.constructor.name === 'AsyncFunction') {
-                result = await (handler as (input: any) => Promise<any>)(input);
-            } else {
-                result = (handler as Handler)(input);
-            }
-        } else {
-            throw new Error("没有找到main或handler函数");
-        }
+        // 查找并执行入口函数(第二个参数为调用上下文),同步函数的返回值直接使用
+        let result: any = await runCodeResolveEntrypoint({{USER_FUNCTIONS}})(input, runCodeCreateContext());
+
+        // 生成器函数逐个输出产生的数据块
+        result = await runCodeCollectChunks(result);
+        // 转换为可以JSON序列化的值,二进制数据编码为base64
+        const value: any = await runCodeToJsonable(result);
 
-        // Print final output as JSON
-        originalConsoleLog(JSON.stringify({
-            logs: logs,
-            result: result !== undefined ? (typeof result === 'object' ? JSON.stringify(result) : String(result)) : null,
-            error: null
-        }));
+        // Wait one event loop turn, then print final output as JSON and exit
+        await runCodeSettle();
+        runCodeFinish(runCodeResultText(result, value), null);
     } catch (error) {
         // Handle errors
-        originalConsoleLog(JSON.stringify({
-            logs: logs,
-            result: null,
-            error: String(error)
-        }));
+        runCodeFinish(null, String(error));
     }
 }
 
@@ -0,0 +1,265 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod batch_tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use log::info;
+    use serde_json::{Value, json};
+
+    use crate::model::{
+        CodeExecutor, CodeScriptExecutionResult, ProjectFiles, RunCode, RunOptions,
+    };
+    use crate::registry::RunnerRegistry;
+    use crate::tests::test_utils::setup;
+
+    /// 记录准备次数和最大并发数的测试运行器
+    #[derive(Default)]
+    struct CountingRunner {
+        prepare_count: AtomicUsize,
+        running: AtomicUsize,
+        max_running: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl RunCode for CountingRunner {
+        async fn run_with_params(
+            &self,
+            _code: &str,
+            params: Option<Value>,
+            _timeout_seconds: Option<u64>,
+        ) -> Result<CodeScriptExecutionResult> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+
+            let value = params.unwrap_or_default()["value"].as_i64().unwrap_or(0);
+            if value < 0 {
+                anyhow::bail!("negative value: {value}");
+            }
+            Ok(CodeScriptExecutionResult {
+                result: Some(json!(value * 2)),
+                logs: vec![format!("value: {value}")],
+                log_entries: vec![],
+                success: true,
+                error: None,
+            })
+        }
+
+        async fn prepare(&self, _code: &str, _options: &RunOptions) -> Result<()> {
+            self.prepare_count.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    /// 记录准备和执行时收到的代码的测试运行器,支持多文件项目
+    #[derive(Default)]
+    struct RecordingRunner {
+        prepared: Mutex<Vec<String>>,
+        executed: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl RunCode for RecordingRunner {
+        async fn run_with_params(
+            &self,
+            code: &str,
+            _params: Option<Value>,
+            _timeout_seconds: Option<u64>,
+        ) -> Result<CodeScriptExecutionResult> {
+            self.executed.lock().unwrap().push(code.to_string());
+            Ok(CodeScriptExecutionResult {
+                result: None,
+                logs: vec![],
+                log_entries: vec![],
+                success: true,
+                error: None,
+            })
+        }
+
+        async fn prepare(&self, code: &str, _options: &RunOptions) -> Result<()> {
+            self.prepared.lock().unwrap().push(code.to_string());
+            Ok(())
+        }
+
+        fn supports_project(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_order_and_concurrency() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let runner = Arc::new(CountingRunner::default());
+        RunnerRegistry::register_global("batch_counting", runner.clone());
+
+        let params_list: Vec<Value> = (0..10).map(|i| json!({ "value": i })).collect();
+        let result = CodeExecutor::execute_batch(
+            "",
+            "batch_counting",
+            params_list,
+            &RunOptions::default(),
+            Some(3),
+        )
+        .await?;
+        info!("批量执行结果: {result:?}");
+
+        assert_eq!(result.total, 10);
+        assert_eq!(result.succeeded, 10);
+        assert_eq!(result.failed, 0);
+        for (i, item) in result.items.iter().enumerate() {
+            assert_eq!(item.index, i, "结果应与输入顺序一致");
+            assert_eq!(item.result, Some(json!(i * 2)));
+        }
+        assert_eq!(runner.prepare_count.load(Ordering::SeqCst), 1, "只准备一次");
+        assert!(
+            runner.max_running.load(Ordering::SeqCst) <= 3,
+            "并发数不应超过限制"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batch_prepare_project_entry() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let runner = Arc::new(RecordingRunner::default());
+        RunnerRegistry::register_global("batch_recording", runner.clone());
+
+        // 多文件项目的请求中没有代码,准备和执行都使用入口文件的内容
+        let entry_code = "def handler(args): return args";
+        let options = RunOptions {
+            project: Some(ProjectFiles {
+                entrypoint: "main.py".to_string(),
+                files: [
+                    ("main.py".to_string(), entry_code.to_string()),
+                    ("util.py".to_string(), "X = 1".to_string()),
+                ]
+                .into(),
+            }),
+            ..Default::default()
+        };
+        let result = CodeExecutor::execute_batch(
+            "",
+            "batch_recording",
+            vec![json!({}), json!({})],
+            &options,
+            None,
+        )
+        .await?;
+
+        assert_eq!(result.succeeded, 2);
+        assert_eq!(*runner.prepared.lock().unwrap(), vec![entry_code]);
+        assert_eq!(*runner.executed.lock().unwrap(), vec![entry_code; 2]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batch_shell_partial_failure() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+handler() {
+    if [ "$PARAM_N" -lt 0 ]; then
+        echo "negative" >&2
+        exit 3
+    fi
+    echo "processing $PARAM_N"
+    set_result "{\"square\": $((PARAM_N * PARAM_N))}"
+}
+"#;
+        let params_list = vec![json!({ "n": 2 }), json!({ "n": -1 }), json!({ "n": 5 })];
+        let result =
+            CodeExecutor::execute_batch(code, "shell", params_list, &RunOptions::default(), None)
+                .await?;
+        info!("批量执行结果: {result:?}");
+
+        assert_eq!(result.total, 3);
+        assert_eq!(result.succeeded, 2);
+        assert_eq!(result.failed, 1);
+
+        assert_eq!(result.items[0].result, Some(json!({ "square": 4 })));
+        assert!(result.items[0].logs.join("\n").contains("processing 2"));
+        assert!(!result.items[1].success);
+        assert!(result.items[1].error.is_some());
+        assert_eq!(result.items[2].result, Some(json!({ "square": 25 })));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batch_project_path_traversal() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 批量执行在准备阶段写入项目文件,路径不合法时不能写到项目目录之外
+        let escaped = format!("batch_escape_{}.ts", std::process::id());
+        let cases = [
+            (format!("../../{escaped}"), "main.ts".to_string()),
+            ("util.ts".to_string(), format!("/tmp/{escaped}")),
+        ];
+        for (file, entrypoint) in cases {
+            let options = RunOptions {
+                project: Some(ProjectFiles {
+                    entrypoint,
+                    files: [(file, "export const x = 1;".to_string())].into(),
+                }),
+                ..Default::default()
+            };
+            let result = CodeExecutor::execute_batch(
+                "export function handler() { return 1; }",
+                "ts",
+                vec![json!({}), json!({})],
+                &options,
+                None,
+            )
+            .await?;
+            info!("批量执行结果: {result:?}");
+
+            assert_eq!(result.failed, 2);
+            assert!(
+                result.items[0]
+                    .error
+                    .as_deref()
+                    .is_some_and(|error| error.contains("相对路径")),
+                "错误信息不正确: {:?}",
+                result.items[0].error
+            );
+        }
+        assert!(
+            !std::path::Path::new("/tmp/code_cache")
+                .join(&escaped)
+                .exists()
+        );
+        assert!(!std::path::Path::new("/tmp").join(&escaped).exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batch_unknown_language() {
+        // 初始化日志
+        setup();
+
+        let result = CodeExecutor::execute_batch(
+            "",
+            "unknown_language",
+            vec![json!({})],
+            &RunOptions::default(),
+            None,
+        )
+        .await;
+        assert!(result.is_err(), "未注册的语言应返回错误");
+    }
+}
@@ -0,0 +1,130 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod binary_tests {
+    use anyhow::Result;
+    use log::info;
+    use rmcp::{
+        RoleClient, ServiceExt,
+        model::{CallToolRequestParam, CallToolResult, ResourceContents},
+        service::RunningService,
+    };
+    use serde_json::{Value, json};
+
+    use crate::mcp::CodeRunnerService;
+    use crate::model::{CodeExecutor, JsRuntime, LanguageScript, RunOptions};
+    use crate::tests::test_utils::setup;
+
+    /// 连接到内存中的MCP服务
+    async fn connect() -> Result<RunningService<RoleClient, ()>> {
+        let (client_stream, server_stream) = tokio::io::duplex(65536);
+        tokio::spawn(async move {
+            if let Ok(server) = CodeRunnerService.serve(server_stream).await {
+                let _ = server.waiting().await;
+            }
+        });
+        Ok(().serve(client_stream).await?)
+    }
+
+    /// 工具结果中第一项JSON内容
+    fn json_content(result: &CallToolResult) -> Result<Value> {
+        let text = result
+            .content
+            .as_ref()
+            .and_then(|content| content.first())
+            .and_then(|content| content.as_text())
+            .map(|text| text.text.clone())
+            .unwrap_or_default();
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    #[tokio::test]
+    async fn test_binary_result_as_image_and_resource() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let client = connect().await?;
+        // PNG 文件头和 PDF 文件头,PNG 不声明类型,由服务端识别
+        let code = r#"handler() { set_result '{"chart": {"$binary": "iVBORw0KGgo="}, "report": {"$binary": "JVBERi0xLjQ=", "mime": "application/pdf"}, "pages": 3}'; }"#;
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "run_shell".into(),
+                arguments: json!({ "code": code }).as_object().cloned(),
+            })
+            .await?;
+        info!("工具结果: {result:?}");
+
+        let content = result.content.clone().unwrap_or_default();
+        assert_eq!(content.len(), 3);
+        assert_eq!(
+            json_content(&result)?["result"],
+            json!({
+                "chart": { "$content": 1, "mime": "image/png", "size": 8 },
+                "report": { "$content": 2, "mime": "application/pdf", "size": 8 },
+                "pages": 3
+            })
+        );
+
+        let image = content[1].as_image().expect("应为图片内容");
+        assert_eq!(image.mime_type, "image/png");
+        assert_eq!(image.data, "iVBORw0KGgo=");
+
+        let resource = content[2].as_resource().expect("应为内嵌资源");
+        match &resource.resource {
+            ResourceContents::BlobResourceContents {
+                uri,
+                mime_type,
+                blob,
+            } => {
+                assert_eq!(uri, "run-code://result/2");
+                assert_eq!(mime_type.as_deref(), Some("application/pdf"));
+                assert_eq!(blob, "JVBERi0xLjQ=");
+            }
+            other => panic!("应为二进制资源: {other:?}"),
+        }
+
+        client.cancel().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_binary_result() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+async function handler(input) {
+    const png = new Uint8Array([0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a]);
+    return {
+        chart: png,
+        report: new Blob(["%PDF-1.4"], { type: "application/pdf" }),
+        name: input.name
+    };
+}
+"#;
+        let options = RunOptions {
+            runtime: Some(JsRuntime::Node),
+            ..Default::default()
+        };
+        let result = CodeExecutor::execute_with_options(
+            code,
+            LanguageScript::Js.id(),
+            Some(json!({ "name": "月报" })),
+            &options,
+        )
+        .await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(
+            result.result,
+            Some(json!({
+                "chart": { "$binary": "iVBORw0KGgo=", "mime": null },
+                "report": { "$binary": "JVBERi0xLjQ=", "mime": "application/pdf" },
+                "name": "月报"
+            }))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod console_tests {
+    use crate::{
+        model::{
+            CodeExecutor, CodeScriptExecutionResult, JsRuntime, LanguageScript, LogEntry,
+            LogStream, RunOptions,
+        },
+        tests::test_utils::setup,
+    };
+    use anyhow::Result;
+    use log::info;
+
+    /// fixtures/test_js_console.js 捕获的日志,第一条为模板输出的接收参数
+    const EXPECTED_CONSOLE_LOGS: [&str; 13] = [
+        "接收到的参数: {}",
+        "log {\"a\":1}",
+        "[INFO] info",
+        "[DEBUG] debug",
+        "[WARNING] warn",
+        "[ERROR] error {\"k\":\"1n\"}",
+        "[ERROR] Assertion failed: math",
+        "default: 1",
+        "default: 2",
+        "group",
+        "  nested",
+        "[{\"id\":1}]",
+        "{\"name\":\"cycle\",\"self\":\"[Circular]\"}",
+    ];
+
+    /// 使用指定的运行时执行JavaScript代码
+    async fn run_js(code: &str, runtime: JsRuntime) -> Result<CodeScriptExecutionResult> {
+        let options = RunOptions {
+            runtime: Some(runtime),
+            ..Default::default()
+        };
+        let result =
+            CodeExecutor::execute_with_options(code, LanguageScript::Js.id(), None, &options)
+                .await?;
+        info!("执行结果: {result:?}");
+        Ok(result)
+    }
+
+    #[tokio::test]
+    async fn test_node_console_levels() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_js_console.js")?;
+        let result = run_js(&code, JsRuntime::Node).await?;
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.result, Some(serde_json::json!("done")));
+        // handler 返回后才触发的定时器不会产生日志
+        assert_eq!(result.logs, EXPECTED_CONSOLE_LOGS);
+
+        // 结构化的日志记录与日志文本一一对应
+        assert_eq!(result.log_entries.len(), EXPECTED_CONSOLE_LOGS.len());
+        let entry = |level: Option<&str>, message: &str, stream: LogStream| LogEntry {
+            level: level.map(String::from),
+            logger: None,
+            message: message.to_string(),
+            stream,
+        };
+        assert_eq!(
+            result.log_entries[1],
+            entry(None, "log {\"a\":1}", LogStream::Stdout)
+        );
+        assert_eq!(
+            result.log_entries[2],
+            entry(Some("INFO"), "info", LogStream::Stdout)
+        );
+        assert_eq!(
+            result.log_entries[4],
+            entry(Some("WARNING"), "warn", LogStream::Stderr)
+        );
+        assert_eq!(
+            result.log_entries[6],
+            entry(Some("ERROR"), "Assertion failed: math", LogStream::Stderr)
+        );
+        assert_eq!(
+            result.log_entries[10],
+            entry(None, "  nested", LogStream::Stdout)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deno_console_levels() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_js_console.js")?;
+        let result = run_js(&code, JsRuntime::Deno).await?;
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.logs, EXPECTED_CONSOLE_LOGS);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_unhandled_rejection() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+export function handler() {
+    console.log("before");
+    Promise.reject(new Error("boom"));
+    return "ignored";
+}
+"#;
+        let result = run_js(code, JsRuntime::Node).await?;
+
+        assert!(!result.success);
+        assert_eq!(result.result, None);
+        let error = result.error.unwrap_or_default();
+        assert!(
+            error.starts_with("UnhandledRejection: Error: boom"),
+            "错误信息不正确: {error}"
+        );
+        // 出错之前的日志仍然保留
+        assert!(result.logs.contains(&"before".to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_uncaught_timer_error() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+function handler() {
+    setTimeout(() => { throw new TypeError("late failure"); }, 10);
+    return new Promise(resolve => setTimeout(() => resolve("ignored"), 200));
+}
+module.exports = { handler };
+"#;
+        let result = run_js(code, JsRuntime::Node).await?;
+
+        assert!(!result.success);
+        let error = result.error.unwrap_or_default();
+        assert!(
+            error.starts_with("UncaughtError: TypeError: late failure"),
+            "错误信息不正确: {error}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deno_unhandled_rejection() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+export function handler() {
+    Promise.reject("plain reason");
+    return "ignored";
+}
+"#;
+        let result = run_js(code, JsRuntime::Deno).await?;
+
+        assert!(!result.success);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("UnhandledRejection: plain reason")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ts_console_levels() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+function handler(): string {
+    console.warn("careful");
+    console.error(new Map<string, number>([["n", 1]]));
+    return "ok";
+}
+"#;
+        let result =
+            CodeExecutor::execute_with_params(code, LanguageScript::Ts, None, None).await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert!(result.logs.contains(&"[WARNING] careful".to_string()));
+        assert!(result.logs.contains(&"[ERROR] {\"n\":1}".to_string()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,281 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod context_tests {
+    use std::collections::BTreeMap;
+
+    use anyhow::Result;
+    use log::info;
+    use serde_json::{Value, json};
+
+    use crate::function::{FunctionDeployment, FunctionStore};
+    use crate::model::{CodeExecutor, JsRuntime, LanguageScript, RunOptions};
+    use crate::tests::test_utils::setup;
+
+    fn deployment(name: &str, language: &str, code: &str) -> FunctionDeployment {
+        FunctionDeployment {
+            name: name.to_string(),
+            language: language.to_string(),
+            code: code.to_string(),
+            dependencies: vec![],
+            timeout_seconds: Some(30),
+            description: None,
+            input_schema: None,
+            output_schema: None,
+            entrypoint: None,
+            env: BTreeMap::from([("API_URL".to_string(), "https://api.example.com".to_string())]),
+        }
+    }
+
+    /// 调用ID是32位十六进制字符串
+    fn assert_invocation_id(context: &Value) {
+        let id = context["invocation_id"].as_str().unwrap_or_default();
+        assert_eq!(id.len(), 32, "调用ID不正确: {context}");
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[tokio::test]
+    async fn test_shell_function_context() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        // 第二个参数为调用上下文的JSON,声明的环境变量直接设置为环境变量
+        let code = r#"
+handler() {
+    log_info "api: $API_URL"
+    set_result "$2"
+}
+"#;
+        store
+            .deploy(deployment("shell-context", "shell", code))
+            .await?;
+        let result = store
+            .invoke("shell-context", None, &RunOptions::default())
+            .await?;
+        info!("函数调用结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        let context = result.result.unwrap_or_default();
+        assert_invocation_id(&context);
+        assert_eq!(context["function_name"], json!("shell-context"));
+        assert_eq!(context["function_version"], json!(1));
+        assert_eq!(context["language"], json!("shell"));
+        assert_eq!(
+            context["env"],
+            json!({ "API_URL": "https://api.example.com" })
+        );
+        assert!(context["deadline"].as_f64().is_some());
+        assert!(
+            result
+                .logs
+                .contains(&"[INFO] api: https://api.example.com".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shell_single_argument_handler() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 只使用参数的handler不受影响,每次调用的调用ID不同
+        let code = "handler() { set_result \"{\\\"args\\\": $1, \\\"id\\\": $(printf '%s' \"$2\" | grep -o '\\\"invocation_id\\\":\\\"[0-9a-f]*\\\"' | cut -d: -f2)}\"; }";
+        let params = Some(json!({ "a": 1 }));
+        let first =
+            CodeExecutor::execute_with_params(code, LanguageScript::Shell, params.clone(), None)
+                .await?;
+        let second =
+            CodeExecutor::execute_with_params(code, LanguageScript::Shell, params, None).await?;
+        info!("执行结果: {first:?} {second:?}");
+
+        let (first, second) = (
+            first.result.unwrap_or_default(),
+            second.result.unwrap_or_default(),
+        );
+        assert_eq!(first["args"], json!({ "a": 1 }));
+        assert_eq!(first["id"].as_str().map(str::len), Some(32));
+        assert_ne!(first["id"], second["id"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_context() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+export function handler(input, context) {
+    context.logger.info("hello", input.name);
+    context.logger.warn("careful");
+    let writable = true;
+    try {
+        context.env.API_URL = "changed";
+        writable = context.env.API_URL === "changed";
+    } catch (error) {
+        writable = false;
+    }
+    return {
+        input,
+        idLength: context.invocationId.length,
+        functionName: context.functionName,
+        functionVersion: context.functionVersion,
+        language: context.language,
+        deadlineInFuture: context.deadline > Date.now(),
+        remainingPositive: context.getRemainingTimeInMillis() > 0,
+        env: context.env,
+        writable,
+    };
+}
+"#;
+        let options = RunOptions {
+            runtime: Some(JsRuntime::Node),
+            env: BTreeMap::from([("API_URL".to_string(), "https://api.example.com".to_string())]),
+            ..Default::default()
+        };
+        let result = CodeExecutor::execute_with_options(
+            code,
+            LanguageScript::Js.id(),
+            Some(json!({ "name": "node" })),
+            &options,
+        )
+        .await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(
+            result.result,
+            Some(json!({
+                "input": { "name": "node" },
+                "idLength": 32,
+                "functionName": null,
+                "functionVersion": null,
+                "language": "js",
+                "deadlineInFuture": true,
+                "remainingPositive": true,
+                "env": { "API_URL": "https://api.example.com" },
+                "writable": false
+            }))
+        );
+        assert!(result.logs.contains(&"[INFO] hello node".to_string()));
+        assert!(result.logs.contains(&"[WARNING] careful".to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_function_context() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        let code = std::fs::read_to_string("fixtures/test_python_context.py")?;
+        store
+            .deploy(deployment("python-context", "python", &code))
+            .await?;
+        let result = store
+            .invoke(
+                "python-context",
+                Some(json!({ "name": "py" })),
+                &RunOptions::default(),
+            )
+            .await?;
+        info!("函数调用结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        // 参数原样传入,不会复制到 params 中
+        assert_eq!(
+            result.result,
+            Some(json!({
+                "args": { "name": "py" },
+                "function_name": "python-context",
+                "function_version": 1,
+                "language": "python",
+                "deadline_in_future": true,
+                "remaining_positive": true,
+                "api_url": "https://api.example.com"
+            }))
+        );
+        assert!(
+            result
+                .logs
+                .iter()
+                .any(|log| log.starts_with("[INFO] 调用 "))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_go_context() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+package main
+
+import "os"
+
+func Handler(input map[string]any, ctx *InvocationContext) (map[string]any, error) {
+	ctx.Logger.Info("hello", input["name"])
+	apiURL, _ := ctx.Env("API_URL")
+	return map[string]any{
+		"id_length":   len(ctx.InvocationID),
+		"language":    ctx.Language,
+		"remaining":   ctx.RemainingTime() > 0,
+		"api_url":     apiURL,
+		"process_env": os.Getenv("API_URL"),
+	}, nil
+}
+"#;
+        let options = RunOptions {
+            env: BTreeMap::from([("API_URL".to_string(), "https://api.example.com".to_string())]),
+            ..Default::default()
+        };
+        let result = CodeExecutor::execute_with_options(
+            code,
+            LanguageScript::Go.id(),
+            Some(json!({ "name": "go" })),
+            &options,
+        )
+        .await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(
+            result.result,
+            Some(json!({
+                "id_length": 32,
+                "language": "go",
+                "remaining": true,
+                "api_url": "https://api.example.com",
+                "process_env": "https://api.example.com"
+            }))
+        );
+        assert!(result.logs.contains(&"[INFO] hello go".to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deploy_rejects_reserved_env() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        let mut function = deployment("reserved-env", "shell", "handler() { :; }");
+        function.env = BTreeMap::from([("INPUT_JSON".to_string(), "{}".to_string())]);
+        let error = store.deploy(function).await.unwrap_err();
+        assert!(
+            error.to_string().contains("INPUT_JSON"),
+            "错误信息不正确: {error}"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,331 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod entrypoint_tests {
+    use anyhow::Result;
+    use log::info;
+    use serde_json::{Value, json};
+
+    use crate::function::{FunctionDeployment, FunctionStore};
+    use crate::mcp::LanguageCodeRunRequest;
+    use crate::model::{
+        CodeExecutor, CodeScriptExecutionResult, JsRuntime, LanguageScript, RunOptions,
+    };
+    use crate::tests::test_utils::setup;
+
+    /// 使用指定的入口函数执行代码
+    async fn run(
+        code: &str,
+        language: LanguageScript,
+        params: Value,
+        entrypoint: Option<&str>,
+        runtime: Option<JsRuntime>,
+    ) -> Result<CodeScriptExecutionResult> {
+        let options = RunOptions {
+            entrypoint: entrypoint.map(String::from),
+            runtime,
+            ..Default::default()
+        };
+        let result =
+            CodeExecutor::execute_with_options(code, language.id(), Some(params), &options).await?;
+        info!("执行结果: {result:?}");
+        Ok(result)
+    }
+
+    #[tokio::test]
+    async fn test_node_select_entrypoint() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_js_entrypoints.js")?;
+        let params = json!({ "a": 3, "b": 4 });
+        let node = Some(JsRuntime::Node);
+
+        let add = run(&code, LanguageScript::Js, params.clone(), Some("add"), node).await?;
+        assert!(add.success, "执行失败: {:?}", add.error);
+        assert_eq!(add.result, Some(json!(7)));
+
+        // 异步函数与箭头函数同样可以作为入口函数
+        let multiply = run(
+            &code,
+            LanguageScript::Js,
+            params.clone(),
+            Some("multiply"),
+            node,
+        )
+        .await?;
+        assert_eq!(multiply.result, Some(json!(12)));
+        let describe = run(
+            &code,
+            LanguageScript::Js,
+            params.clone(),
+            Some("describe"),
+            node,
+        )
+        .await?;
+        assert_eq!(describe.result, Some(json!("a=3, b=4")));
+
+        // 未指定时优先使用 handler
+        let default = run(&code, LanguageScript::Js, params, None, node).await?;
+        assert_eq!(default.result, Some(json!("handler")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_entrypoint_not_found() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_js_entrypoints.js")?;
+        let result = run(
+            &code,
+            LanguageScript::Js,
+            json!({}),
+            Some("subtract"),
+            Some(JsRuntime::Node),
+        )
+        .await?;
+        assert!(!result.success);
+        let error = result.error.unwrap_or_default();
+        assert!(error.contains("HandlerNotFound"), "错误信息: {error}");
+        assert!(error.contains("subtract"), "错误信息: {error}");
+        assert!(error.contains("add, multiply"), "错误信息: {error}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_module_entrypoints() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let node = Some(JsRuntime::Node);
+
+        // ES 模块中导出的函数
+        let esm = "export function sum(input) {\n    return input.a + input.b;\n}\nexport function handler() {\n    return 0;\n}\n";
+        let result = run(
+            esm,
+            LanguageScript::Js,
+            json!({ "a": 1, "b": 2 }),
+            Some("sum"),
+            node,
+        )
+        .await?;
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.result, Some(json!(3)));
+
+        // CommonJS 中挂在 module.exports 上的函数
+        let cjs = "module.exports.twice = (input) => input.n * 2;\n";
+        let result = run(
+            cjs,
+            LanguageScript::Js,
+            json!({ "n": 21 }),
+            Some("twice"),
+            node,
+        )
+        .await?;
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.result, Some(json!(42)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shell_entrypoint() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_shell_entrypoints.sh")?;
+        let params = json!({ "a": 3, "b": 4 });
+
+        let multiply = run(
+            &code,
+            LanguageScript::Shell,
+            params.clone(),
+            Some("multiply"),
+            None,
+        )
+        .await?;
+        assert!(multiply.success, "执行失败: {:?}", multiply.error);
+        assert_eq!(multiply.result, Some(json!(12)));
+
+        // 没有 handler 时使用 main
+        let default = run(&code, LanguageScript::Shell, params.clone(), None, None).await?;
+        assert_eq!(default.result, Some(json!("main")));
+
+        let missing = run(&code, LanguageScript::Shell, params, Some("divide"), None).await?;
+        assert!(!missing.success);
+        let error = missing.error.unwrap_or_default();
+        assert!(error.contains("HandlerNotFound"), "错误信息: {error}");
+        assert!(error.contains("add, main, multiply"), "错误信息: {error}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_entrypoint() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_python_entrypoints.py")?;
+        let params = json!({ "a": 3, "b": 4 });
+
+        let add = run(
+            &code,
+            LanguageScript::Python,
+            params.clone(),
+            Some("add"),
+            None,
+        )
+        .await?;
+        assert!(add.success, "执行失败: {:?}", add.error);
+        assert_eq!(add.result, Some(json!(7)));
+
+        // 没有参数的函数不传入参数
+        let ping = run(
+            &code,
+            LanguageScript::Python,
+            params.clone(),
+            Some("ping"),
+            None,
+        )
+        .await?;
+        assert_eq!(ping.result, Some(json!("pong")));
+
+        let default = run(&code, LanguageScript::Python, params.clone(), None, None).await?;
+        assert_eq!(default.result, Some(json!("handler")));
+
+        let missing = run(&code, LanguageScript::Python, params, Some("divide"), None).await?;
+        assert!(!missing.success);
+        let error = missing.error.unwrap_or_default();
+        assert!(error.contains("HandlerNotFound"), "错误信息: {error}");
+        assert!(error.contains("add"), "错误信息: {error}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ts_entrypoint() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = "export function sum(input: { a: number; b: number }): number {\n    return input.a + input.b;\n}\n\nfunction handler() {\n    return \"handler\";\n}\n";
+        let result = run(
+            code,
+            LanguageScript::Ts,
+            json!({ "a": 1, "b": 2 }),
+            Some("sum"),
+            None,
+        )
+        .await?;
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.result, Some(json!(3)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_go_entrypoint_not_found() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 入口函数不存在时在编译前返回错误
+        let code =
+            "package main\n\nfunc Add(input map[string]interface{}) int {\n    return 1\n}\n";
+        let result = run(code, LanguageScript::Go, json!({}), Some("subtract"), None).await?;
+        assert!(!result.success);
+        let error = result.error.unwrap_or_default();
+        assert!(error.contains("HandlerNotFound"), "错误信息: {error}");
+        assert!(error.contains("Add"), "错误信息: {error}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_entrypoint() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let result = run(
+            "function handler() {}",
+            LanguageScript::Js,
+            json!({}),
+            Some("process.exit"),
+            Some(JsRuntime::Node),
+        )
+        .await;
+        assert!(result.unwrap_err().to_string().contains("process.exit"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_entrypoint_options() -> Result<()> {
+        let request: LanguageCodeRunRequest = serde_json::from_value(json!({
+            "language": "python",
+            "code": "def add(args): pass",
+            "entrypoint": "add"
+        }))?;
+        let options = request.options.into_run_options();
+        assert_eq!(options.entrypoint.as_deref(), Some("add"));
+        assert!(options.project.is_none());
+
+        // 多文件项目中为 入口文件[:函数名]
+        let project: LanguageCodeRunRequest = serde_json::from_value(json!({
+            "language": "python",
+            "files": { "main.py": "def add(args): pass" },
+            "entrypoint": "main.py:add"
+        }))?;
+        let options = project.options.into_run_options();
+        assert_eq!(options.entrypoint.as_deref(), Some("add"));
+        assert_eq!(
+            options.project.map(|p| p.entrypoint),
+            Some("main.py".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_function_entrypoint() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        let code = std::fs::read_to_string("fixtures/test_shell_entrypoints.sh")?;
+        store
+            .deploy(FunctionDeployment {
+                name: "calc".to_string(),
+                language: "shell".to_string(),
+                code,
+                dependencies: vec![],
+                timeout_seconds: Some(10),
+                description: None,
+                input_schema: None,
+                output_schema: None,
+                entrypoint: Some("add".to_string()),
+                env: Default::default(),
+            })
+            .await?;
+
+        let params = Some(json!({ "a": 3, "b": 4 }));
+        // 使用部署时指定的入口函数
+        let result = store
+            .invoke("calc", params.clone(), &RunOptions::default())
+            .await?;
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.result, Some(json!(7)));
+
+        // 调用时可以覆盖入口函数
+        let options = RunOptions {
+            entrypoint: Some("multiply".to_string()),
+            ..Default::default()
+        };
+        let result = store.invoke("calc", params, &options).await?;
+        assert_eq!(result.result, Some(json!(12)));
+
+        Ok(())
+    }
+}
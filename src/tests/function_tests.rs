@@ -0,0 +1,331 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod function_tests {
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use log::info;
+    use serde_json::{Value, json};
+
+    use crate::cache::CodeFileCache;
+    use crate::function::{FunctionDeployment, FunctionStore};
+    use crate::model::{CodeScriptExecutionResult, LanguageScript, RunCode, RunOptions};
+    use crate::registry::RunnerRegistry;
+    use crate::tests::test_utils::setup;
+
+    /// 记录准备和执行时收到的声明依赖的测试运行器
+    #[derive(Default)]
+    struct DependencyRecordingRunner {
+        prepared: Mutex<Vec<Vec<String>>>,
+        executed: Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl RunCode for DependencyRecordingRunner {
+        async fn run_with_params(
+            &self,
+            _code: &str,
+            _params: Option<Value>,
+            _timeout_seconds: Option<u64>,
+        ) -> Result<CodeScriptExecutionResult> {
+            anyhow::bail!("应通过 run_with_options 执行")
+        }
+
+        async fn run_with_options(
+            &self,
+            _code: &str,
+            _params: Option<Value>,
+            options: &RunOptions,
+        ) -> Result<CodeScriptExecutionResult> {
+            self.executed
+                .lock()
+                .unwrap()
+                .push(options.dependencies.clone());
+            Ok(CodeScriptExecutionResult {
+                result: None,
+                logs: vec![],
+                log_entries: vec![],
+                success: true,
+                error: None,
+            })
+        }
+
+        async fn prepare(&self, _code: &str, options: &RunOptions) -> Result<()> {
+            self.prepared
+                .lock()
+                .unwrap()
+                .push(options.dependencies.clone());
+            Ok(())
+        }
+    }
+
+    fn shell_function(name: &str, code: &str) -> FunctionDeployment {
+        FunctionDeployment {
+            name: name.to_string(),
+            language: "shell".to_string(),
+            code: code.to_string(),
+            dependencies: vec![],
+            timeout_seconds: Some(10),
+            description: Some("测试函数".to_string()),
+            input_schema: None,
+            output_schema: None,
+            entrypoint: None,
+            env: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_function_deploy_and_invoke() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        let code = "handler() { set_result \"{\\\"greeting\\\": \\\"hello $PARAM_NAME\\\"}\"; }";
+        let function = store.deploy(shell_function("greet", code)).await?;
+        let version = function.latest().expect("latest version");
+        assert_eq!(version.version, 1);
+        assert_eq!(version.content_hash, CodeFileCache::obtain_code_hash(code));
+
+        // 部署时已生成代码缓存,调用时直接复用
+        assert!(
+            CodeFileCache::check_code_file_cache_exisht(
+                &CodeFileCache::obtain_script_hash(code),
+                &LanguageScript::Shell
+            )
+            .await
+        );
+
+        let result = store
+            .invoke(
+                "greet",
+                Some(json!({ "name": "rust" })),
+                &RunOptions::default(),
+            )
+            .await?;
+        info!("函数调用结果: {result:?}");
+        assert!(result.success);
+        assert_eq!(result.result, Some(json!({ "greeting": "hello rust" })));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_function_versions_and_rollback() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        let first = store
+            .deploy(shell_function("versioned", "handler() { set_result '1'; }"))
+            .await?;
+        store.set_alias("versioned", "stable", 1)?;
+        let second = store
+            .deploy(shell_function("versioned", "handler() { set_result '2'; }"))
+            .await?;
+
+        // 重新部署生成新版本,保留首次部署时间和旧版本
+        assert_eq!(second.created_at, first.created_at);
+        assert_eq!(second.versions.len(), 2);
+        assert_eq!(second.latest().map(|v| v.version), Some(2));
+        assert_eq!(second.aliases_of(1), vec!["stable"]);
+
+        let invoke = |reference: &'static str| {
+            let store = store.clone();
+            async move {
+                store
+                    .invoke(reference, None, &RunOptions::default())
+                    .await
+                    .map(|r| r.result)
+            }
+        };
+        assert_eq!(invoke("versioned").await?, Some(json!(2)));
+        assert_eq!(invoke("versioned@1").await?, Some(json!(1)));
+        assert_eq!(invoke("versioned:stable").await?, Some(json!(1)));
+        assert_eq!(invoke("versioned:latest").await?, Some(json!(2)));
+        assert!(invoke("versioned@9").await.is_err());
+        assert!(invoke("versioned:beta").await.is_err());
+
+        // 每个版本单独统计调用次数
+        let function = store.get("versioned")?.expect("function not found");
+        assert_eq!(function.version(1).map(|v| v.invocation_count), Some(2));
+        assert_eq!(function.version(2).map(|v| v.invocation_count), Some(2));
+
+        // 回滚 latest 到上一个版本,已经是最早版本时无法继续回滚
+        let rolled_back = store.rollback("versioned", None, None)?;
+        assert_eq!(rolled_back.latest().map(|v| v.version), Some(1));
+        assert_eq!(invoke("versioned").await?, Some(json!(1)));
+        assert!(store.rollback("versioned", None, None).is_err());
+        assert!(store.rollback("versioned", Some("latest"), Some(2)).is_ok());
+
+        // 回滚后再部署,版本号继续递增
+        let third = store
+            .deploy(shell_function("versioned", "handler() { set_result '3'; }"))
+            .await?;
+        assert_eq!(third.latest().map(|v| v.version), Some(3));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_function_list_show_delete() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        store
+            .deploy(shell_function("b_func", "handler() { set_result '2'; }"))
+            .await?;
+        store
+            .deploy(shell_function("a_func", "handler() { set_result '1'; }"))
+            .await?;
+
+        let names: Vec<String> = store.list()?.into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["a_func", "b_func"]);
+        assert_eq!(
+            store
+                .get("a_func")?
+                .and_then(|f| f.latest().and_then(|v| v.description.clone())),
+            Some("测试函数".to_string())
+        );
+
+        assert!(store.delete("a_func")?);
+        assert!(!store.delete("a_func")?);
+        assert!(store.get("a_func")?.is_none());
+        assert!(
+            store
+                .invoke("a_func", None, &RunOptions::default())
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_function_deploy_invalid() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        assert!(store.deploy(shell_function("../evil", "")).await.is_err());
+
+        let mut deployment = shell_function("cobol_func", "");
+        deployment.language = "cobol".to_string();
+        assert!(store.deploy(deployment).await.is_err());
+        assert!(store.list()?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_function_invocation_stats() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        store
+            .deploy(shell_function("counted", "handler() { set_result '1'; }"))
+            .await?;
+        let definition_path = dir.path().join("counted.json");
+        let definition = std::fs::read(&definition_path)?;
+
+        // 多个存储实例(相当于多个进程)同时调用,调用次数不会丢失
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let store = FunctionStore::new(dir.path()).expect("invalid store dir");
+                tokio::spawn(async move {
+                    store
+                        .invoke("counted", None, &RunOptions::default())
+                        .await
+                        .map(|r| r.result)
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.await??, Some(json!(1)));
+        }
+        let function = store.get("counted")?.expect("function not found");
+        assert_eq!(function.version(1).map(|v| v.invocation_count), Some(8));
+        assert_eq!(store.list()?[0].versions[0].invocation_count, 8);
+
+        // 调用不会改写函数记录,部署新版本时不会把调用次数写回函数记录
+        assert_eq!(std::fs::read(&definition_path)?, definition);
+        store
+            .deploy(shell_function("counted", "handler() { set_result '2'; }"))
+            .await?;
+        let function = store.get("counted")?.expect("function not found");
+        assert_eq!(function.version(1).map(|v| v.invocation_count), Some(8));
+        assert_eq!(function.version(2).map(|v| v.invocation_count), Some(0));
+
+        // 删除函数时同时删除统计文件
+        assert!(store.delete("counted")?);
+        assert!(!dir.path().join("counted.stats").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_function_deploy_dependency_failure() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 声明的依赖安装失败时部署失败,不保存函数
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        let deployment = FunctionDeployment {
+            name: "broken_deps".to_string(),
+            language: LanguageScript::Python.id().to_string(),
+            code: "def handler(args):\n    return 1\n".to_string(),
+            dependencies: vec!["run-code-rmcp-nonexistent-package".to_string()],
+            ..shell_function("broken_deps", "")
+        };
+        let err = store
+            .deploy(deployment)
+            .await
+            .expect_err("依赖安装失败时部署应失败");
+        let message = format!("{err:#}");
+        info!("部署失败: {message}");
+        assert!(message.contains("函数 broken_deps 部署失败"));
+        assert!(message.contains("添加声明的依赖失败"));
+        assert!(message.contains("run-code-rmcp-nonexistent-package"));
+        assert!(store.get("broken_deps")?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_function_dependencies_passed_to_runner() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 声明的依赖在部署和调用时都传给运行器,Python运行器据此写入脚本的依赖元数据
+        let runner = Arc::new(DependencyRecordingRunner::default());
+        RunnerRegistry::register_global("deps_recording", runner.clone());
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        let dependencies = vec!["requests<3".to_string(), "rich".to_string()];
+        store
+            .deploy(FunctionDeployment {
+                language: "deps_recording".to_string(),
+                dependencies: dependencies.clone(),
+                ..shell_function("with_deps", "")
+            })
+            .await?;
+        store
+            .invoke("with_deps", None, &RunOptions::default())
+            .await?;
+
+        assert_eq!(*runner.prepared.lock().unwrap(), vec![dependencies.clone()]);
+        assert_eq!(*runner.executed.lock().unwrap(), vec![dependencies]);
+
+        Ok(())
+    }
+}
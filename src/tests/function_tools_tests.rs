@@ -0,0 +1,115 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod function_tools_tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use rmcp::{
+        ClientHandler, RoleClient, ServiceExt, model::CallToolRequestParam,
+        service::NotificationContext,
+    };
+    use serde_json::json;
+    use tokio::sync::Notify;
+
+    use crate::function::{FunctionDeployment, FunctionStore};
+    use crate::mcp::CodeRunnerService;
+    use crate::tests::test_utils::setup;
+
+    /// 记录工具列表变化通知的测试客户端
+    #[derive(Clone, Default)]
+    struct ListChangedClient {
+        tool_list_changed: Arc<Notify>,
+    }
+
+    impl ClientHandler for ListChangedClient {
+        async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+            self.tool_list_changed.notify_one();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_function_exposed_as_tool() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let (client_stream, server_stream) = tokio::io::duplex(8192);
+        tokio::spawn(async move {
+            if let Ok(server) = CodeRunnerService.serve(server_stream).await {
+                let _ = server.waiting().await;
+            }
+        });
+        let handler = ListChangedClient::default();
+        let tool_list_changed = handler.tool_list_changed.clone();
+        let client = handler.serve(client_stream).await?;
+
+        // 部署前不存在对应的工具
+        let name = format!("tool_fn_{}", std::process::id());
+        let tools = client.list_all_tools().await?;
+        assert!(!tools.iter().any(|tool| tool.name == name));
+
+        // 部署函数后收到工具列表变化通知,并出现对应的工具
+        FunctionStore::global()?
+            .deploy(FunctionDeployment {
+                name: name.clone(),
+                language: "shell".to_string(),
+                code: "handler() { set_result \"{\\\"doubled\\\": $((PARAM_N * 2))}\"; }"
+                    .to_string(),
+                dependencies: vec![],
+                timeout_seconds: None,
+                description: Some("将数字乘以2".to_string()),
+                input_schema: Some(json!({
+                    "type": "object",
+                    "properties": { "n": { "type": "integer" } },
+                    "required": ["n"]
+                })),
+                output_schema: None,
+                entrypoint: None,
+                env: Default::default(),
+            })
+            .await?;
+        tokio::time::timeout(Duration::from_secs(5), tool_list_changed.notified()).await?;
+
+        let tools = client.list_all_tools().await?;
+        let tool = tools
+            .iter()
+            .find(|tool| tool.name == name)
+            .expect("function tool not listed");
+        assert!(
+            tool.description
+                .as_deref()
+                .is_some_and(|d| d.contains("将数字乘以2"))
+        );
+        assert_eq!(tool.input_schema.get("required"), Some(&json!(["n"])));
+        assert!(tools.iter().any(|tool| tool.name == "run_code"));
+
+        // 直接调用函数对应的工具
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: name.clone().into(),
+                arguments: json!({ "n": 21 }).as_object().cloned(),
+            })
+            .await?;
+        let content = serde_json::to_value(&result.content)?;
+        assert!(content.to_string().contains("42"), "{content}");
+
+        // 删除函数后工具消失
+        assert!(FunctionStore::global()?.delete(&name)?);
+        tokio::time::timeout(Duration::from_secs(5), tool_list_changed.notified()).await?;
+        let tools = client.list_all_tools().await?;
+        assert!(!tools.iter().any(|tool| tool.name == name));
+        assert!(
+            client
+                .call_tool(CallToolRequestParam {
+                    name: name.into(),
+                    arguments: None,
+                })
+                .await
+                .is_err()
+        );
+
+        client.cancel().await?;
+        Ok(())
+    }
+}
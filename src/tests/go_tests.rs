@@ -0,0 +1,116 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod go_tests {
+    use anyhow::Result;
+    use log::info;
+    use serde_json::json;
+
+    use crate::model::{CodeExecutor, LanguageScript};
+    use crate::tests::test_utils::setup;
+
+    #[tokio::test]
+    async fn test_go_with_params() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 从 fixtures 目录读取测试脚本
+        let code = std::fs::read_to_string("fixtures/test_go.go")?;
+        info!("读取测试脚本: test_go.go");
+
+        // 准备参数
+        let params = json!({
+            "a": 10,
+            "b": 20,
+            "name": "user"
+        });
+        info!("准备测试参数: {params:?}");
+
+        // 执行脚本,第二次执行使用缓存的二进制文件
+        for _ in 0..2 {
+            let result = CodeExecutor::execute_with_params_compat(
+                &code,
+                LanguageScript::Go,
+                Some(params.clone()),
+            )
+            .await?;
+            info!("脚本执行完成, 日志: {:?}", result.logs);
+
+            assert!(result.error.is_none(), "不应有错误: {:?}", result.error);
+            let logs_str = result.logs.join(" ");
+            assert!(
+                logs_str.contains("Handler function called"),
+                "日志应包含fmt输出"
+            );
+            assert!(logs_str.contains("Received input"), "日志应包含log输出");
+
+            let result_val = result.result.expect("应有返回结果");
+            assert_eq!(result_val["greeting"], "Hello, USER!");
+            assert_eq!(result_val["sum"], 30.0);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_go_struct_params() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // Handler 的参数和返回值为结构体,参数按结构体的 json 标签解码
+        let code = std::fs::read_to_string("fixtures/test_go_struct.go")?;
+        let result = CodeExecutor::execute_with_params_compat(
+            &code,
+            LanguageScript::Go,
+            Some(json!({ "name": "go", "times": 2 })),
+        )
+        .await?;
+        assert!(result.error.is_none(), "不应有错误: {:?}", result.error);
+        let result_val = result.result.expect("应有返回结果");
+        assert_eq!(result_val["greeting"], "Hello, go! Hello, go! ");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_go_handler_error() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+import "errors"
+
+func Handler(input map[string]any) (any, error) {
+	return nil, errors.New("invalid input")
+}
+"#;
+        let result =
+            CodeExecutor::execute_with_params_compat(code, LanguageScript::Go, None).await?;
+
+        assert!(!result.success, "Handler返回错误应执行失败");
+        assert_eq!(result.error.as_deref(), Some("invalid input"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_go_compile_error() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+func Handler(input map[string]any) (any, error) {
+	return undefinedValue, nil
+}
+"#;
+        let result =
+            CodeExecutor::execute_with_params_compat(code, LanguageScript::Go, None).await?;
+
+        assert!(!result.success, "编译失败应执行失败");
+        let error = result.error.unwrap_or_default();
+        assert!(error.contains("Go编译失败"), "错误信息应提示编译失败");
+        assert!(error.contains("undefinedValue"), "错误信息应包含编译错误");
+
+        Ok(())
+    }
+}
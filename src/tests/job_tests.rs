@@ -0,0 +1,182 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod job_tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use log::info;
+    use serde_json::{Value, json};
+
+    use crate::job::{JobManager, JobManagerConfig, JobRecord, JobStatus, JobStore, JobSubmission};
+    use crate::tests::test_utils::setup;
+
+    fn manager(dir: &tempfile::TempDir, max_concurrent_jobs: usize) -> Result<JobManager> {
+        JobManager::new(JobManagerConfig {
+            store_dir: dir.path().to_path_buf(),
+            max_concurrent_jobs,
+            ..Default::default()
+        })
+    }
+
+    fn shell_job(code: &str, params: Option<Value>) -> JobSubmission {
+        JobSubmission {
+            language: "shell".to_string(),
+            code: code.to_string(),
+            params,
+            options: Default::default(),
+        }
+    }
+
+    /// 轮询任务直到满足条件
+    async fn wait_for(
+        manager: &JobManager,
+        id: &str,
+        condition: impl Fn(&JobRecord) -> bool,
+    ) -> JobRecord {
+        for _ in 0..200 {
+            let record = manager.get(id).expect("job not found");
+            if condition(&record) {
+                return record;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("等待任务 {id} 超时: {:?}", manager.get(id));
+    }
+
+    #[tokio::test]
+    async fn test_job_submit_and_result() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let manager = manager(&dir, 2)?;
+        let submitted = manager.submit(shell_job(
+            "handler() { echo \"working\"; set_result \"{\\\"sum\\\": $((PARAM_A + PARAM_B))}\"; }",
+            Some(json!({ "a": 1, "b": 2 })),
+        ))?;
+        assert_eq!(submitted.status, JobStatus::Pending);
+
+        let record = wait_for(&manager, &submitted.id, |r| r.status.is_finished()).await;
+        info!("任务执行结果: {record:?}");
+
+        assert_eq!(record.status, JobStatus::Succeeded);
+        assert_eq!(record.result, Some(json!({ "sum": 3 })));
+        assert!(record.logs.join("\n").contains("working"));
+        assert!(record.started_at.is_some() && record.finished_at.is_some());
+        assert_eq!(record.result_view()["finished"], json!(true));
+
+        // 任务记录已持久化
+        let stored = JobStore::new(dir.path())?.load_all()?;
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].status, JobStatus::Succeeded);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_job_logs_so_far_and_cancel() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let manager = manager(&dir, 2)?;
+        let submitted = manager.submit(shell_job(
+            "handler() { echo \"started\"; sleep 30; set_result '\"done\"'; }",
+            None,
+        ))?;
+
+        // 执行中可以查询到目前为止的日志
+        let running = wait_for(&manager, &submitted.id, |r| {
+            r.logs.iter().any(|line| line.contains("started"))
+        })
+        .await;
+        assert_eq!(running.status, JobStatus::Running);
+
+        let cancelled = manager.cancel(&submitted.id)?.expect("job not found");
+        assert_eq!(cancelled.status, JobStatus::Cancelled);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let record = manager.get(&submitted.id).expect("job not found");
+        assert_eq!(record.status, JobStatus::Cancelled);
+        assert!(manager.cancel("not-exist")?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_job_concurrency_limit() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let manager = manager(&dir, 1)?;
+        let first = manager.submit(shell_job("handler() { sleep 30; }", None))?;
+        let second = manager.submit(shell_job("handler() { set_result '1'; }", None))?;
+
+        wait_for(&manager, &first.id, |r| r.status == JobStatus::Running).await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            manager.get(&second.id).map(|r| r.status),
+            Some(JobStatus::Pending)
+        );
+
+        // 取消第一个任务后,排队的任务开始执行
+        manager.cancel(&first.id)?;
+        let record = wait_for(&manager, &second.id, |r| r.status.is_finished()).await;
+        assert_eq!(record.status, JobStatus::Succeeded);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_job_restart_and_retention() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = JobStore::new(dir.path())?;
+        let mut running = JobRecord::new("running-job".to_string(), "shell".to_string());
+        running.status = JobStatus::Running;
+        store.save(&running)?;
+        let mut finished = JobRecord::new("finished-job".to_string(), "shell".to_string());
+        finished.finish(JobStatus::Succeeded, None);
+        store.save(&finished)?;
+
+        // 重启后未完成的任务标记为 unknown
+        let manager = manager(&dir, 1)?;
+        let record = manager.get("running-job").expect("job not found");
+        assert_eq!(record.status, JobStatus::Unknown);
+        assert!(record.error.is_some());
+        assert_eq!(
+            manager.get("finished-job").map(|r| r.status),
+            Some(JobStatus::Succeeded)
+        );
+
+        // 保留时间为0时,已结束的任务在下次加载时被清理
+        let manager = JobManager::new(JobManagerConfig {
+            store_dir: dir.path().to_path_buf(),
+            retention: Duration::ZERO,
+            ..Default::default()
+        })?;
+        assert!(manager.list().is_empty());
+        assert!(store.load_all()?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_job_unknown_language() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let manager = manager(&dir, 1)?;
+        let mut submission = shell_job("", None);
+        submission.language = "cobol".to_string();
+        assert!(manager.submit(submission).is_err());
+        assert!(manager.list().is_empty());
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::module_inception)]
+
 #[cfg(test)]
 mod js_tests {
     use anyhow::Result;
@@ -267,10 +269,8 @@ async function handler(input) {
             assert!(json_str.contains("message"), "结果应包含 message 字段");
 
             // 检查计算结果是否正确 (5 + 7 = 12)
-            if let Some(obj) = result_val.as_object() {
-                if let Some(message) = obj.get("message") {
-                    assert_eq!(message, 12, "message 字段的值应为 12");
-                }
+            if let Some(message) = result_val.as_object().and_then(|obj| obj.get("message")) {
+                assert_eq!(message, 12, "message 字段的值应为 12");
             }
         }
 
@@ -647,10 +647,8 @@ async function handler(input) {
             assert!(json_str.contains("value"), "key 字段的值应为 value");
             
             // 检查JSON结构
-            if let Some(obj) = result_val.as_object() {
-                if let Some(key_value) = obj.get("key") {
-                    assert_eq!(key_value, "value", "key 字段的值应为 value");
-                }
+            if let Some(key_value) = result_val.as_object().and_then(|obj| obj.get("key")) {
+                assert_eq!(key_value, "value", "key 字段的值应为 value");
             }
         }
 
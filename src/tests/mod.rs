@@ -13,14 +13,15 @@ pub mod test_utils {
 
     pub fn setup() {
         INIT.call_once(|| {
-            env_logger::builder().filter_level(LevelFilter::Info).init();
+            let _ = env_logger::builder()
+                .filter_level(LevelFilter::Info)
+                .is_test(true)
+                .try_init();
         });
 
         // 只有当环境变量设置为"1"时才清理缓存
-        if let Ok(clean_cache) = std::env::var(CLEAN_CACHE_ENV) {
-            if clean_cache == "1" {
-                clean_cache_dir();
-            }
+        if std::env::var(CLEAN_CACHE_ENV).is_ok_and(|clean_cache| clean_cache == "1") {
+            clean_cache_dir();
         }
     }
 
@@ -34,15 +35,13 @@ pub mod test_utils {
 
             match fs::read_dir(cache_dir) {
                 Ok(entries) => {
-                    for entry in entries {
-                        if let Ok(entry) = entry {
-                            let path = entry.path();
-                            if path.is_file() {
-                                if let Err(e) = fs::remove_file(&path) {
-                                    info!("删除文件失败 {path:?}: {e}");
-                                } else {
-                                    info!("已删除缓存文件: {path:?}");
-                                }
+                    for entry in entries.flatten() {
+                        let path = entry.path();
+                        if path.is_file() {
+                            if let Err(e) = fs::remove_file(&path) {
+                                info!("删除文件失败 {path:?}: {e}");
+                            } else {
+                                info!("已删除缓存文件: {path:?}");
                             }
                         }
                     }
@@ -63,10 +62,8 @@ pub mod test_utils {
         }
 
         // 确保缓存目录存在且可写
-        if !cache_dir.exists() {
-            if let Err(e) = fs::create_dir_all(cache_dir) {
-                info!("创建缓存目录失败: {e}");
-            }
+        if let Err(e) = fs::create_dir_all(cache_dir) {
+            info!("创建缓存目录失败: {e}");
         }
 
         // 设置目录权限为777（所有用户可读写执行）
@@ -82,6 +79,24 @@ pub mod test_utils {
     }
 }
 
+pub mod batch_tests;
+pub mod binary_tests;
+pub mod console_tests;
+pub mod context_tests;
+pub mod entrypoint_tests;
+pub mod function_tests;
+pub mod function_tools_tests;
+pub mod go_tests;
+pub mod job_tests;
 pub mod js_tests;
+pub mod node_tests;
+pub mod pipeline_tests;
+pub mod project_tests;
 pub mod python_tests;
+pub mod request_options_tests;
+pub mod schema_tests;
+pub mod serialization_tests;
+pub mod shell_tests;
+pub mod signature_tests;
+pub mod stream_tests;
 pub mod ts_tests;
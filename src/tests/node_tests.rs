@@ -0,0 +1,200 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod node_tests {
+    use anyhow::Result;
+    use log::info;
+    use serde_json::json;
+
+    use crate::model::{CodeExecutor, JsRuntime, LanguageScript, RunOptions};
+    use crate::tests::test_utils::setup;
+
+    fn node_options(timeout_seconds: Option<u64>) -> RunOptions {
+        RunOptions {
+            timeout_seconds,
+            runtime: Some(JsRuntime::Node),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_node_commonjs_module_exports() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 从 fixtures 目录读取测试脚本
+        let code = std::fs::read_to_string("fixtures/test_node_commonjs.js")?;
+        info!("读取测试脚本: test_node_commonjs.js");
+
+        let params = json!({ "name": "测试用户" });
+        let result = CodeExecutor::execute_with_options(
+            &code,
+            LanguageScript::Js.id(),
+            Some(params),
+            &node_options(None),
+        )
+        .await?;
+        info!("脚本执行完成, 日志: {:?}", result.logs);
+
+        // 验证结果
+        assert!(result.error.is_none(), "不应有错误: {:?}", result.error);
+        assert!(
+            result.logs.iter().any(|log| log.contains("CommonJS script loaded")),
+            "日志应包含脚本输出"
+        );
+        let result_val = result.result.expect("应有返回结果");
+        assert_eq!(result_val["greeting"], "Hello, 测试用户!");
+        assert_eq!(result_val["ext"], ".csv");
+        assert!(result_val["nodeVersion"].is_string(), "应运行在Node.js上");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_esm_module() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 从 fixtures 目录读取测试脚本
+        let code = std::fs::read_to_string("fixtures/test_node_esm.mjs")?;
+        info!("读取测试脚本: test_node_esm.mjs");
+
+        let params = json!({ "path": "/data/report.csv", "a": 2, "b": 3 });
+        let result = CodeExecutor::execute_with_options(
+            &code,
+            LanguageScript::Js.id(),
+            Some(params),
+            &node_options(None),
+        )
+        .await?;
+        info!("脚本执行完成, 日志: {:?}", result.logs);
+
+        assert!(result.error.is_none(), "不应有错误: {:?}", result.error);
+        let result_val = result.result.expect("应有返回结果");
+        assert_eq!(result_val["file"], "report.csv");
+        assert_eq!(result_val["sum"], 5);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_language_id() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 通过注册表中的 node 语言标识直接执行
+        let code = "function handler(input) { return { runtime: typeof process.versions.node, value: input.value * 2 }; }";
+        let result =
+            CodeExecutor::execute_by_language_id(code, "node", Some(json!({"value": 21})), None)
+                .await?;
+
+        assert!(result.error.is_none(), "不应有错误: {:?}", result.error);
+        let result_val = result.result.expect("应有返回结果");
+        assert_eq!(result_val["runtime"], "string");
+        assert_eq!(result_val["value"], 42);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_handler_error() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = "function handler(input) { throw new Error('boom'); }";
+        let result = CodeExecutor::execute_with_options(
+            code,
+            LanguageScript::Js.id(),
+            None,
+            &node_options(None),
+        )
+        .await?;
+
+        assert!(!result.success, "handler抛出异常应执行失败");
+        assert!(result.error.unwrap_or_default().contains("boom"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_with_timeout() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+async function handler(input) {
+    await new Promise(resolve => setTimeout(resolve, 10000));
+    return "完成";
+}
+"#;
+        let start_time = std::time::Instant::now();
+        let result = CodeExecutor::execute_with_options(
+            code,
+            LanguageScript::Js.id(),
+            None,
+            &node_options(Some(1)),
+        )
+        .await;
+        let elapsed = start_time.elapsed();
+
+        info!("脚本执行耗时: {elapsed:?}");
+        assert!(elapsed.as_secs() < 3, "脚本应该在3秒内超时");
+        let err = result.expect_err("应该返回超时错误");
+        assert!(format!("{err:#}").contains("timed out"), "错误信息应包含超时信息");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_require_npm_package() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 从 fixtures 目录读取测试脚本,lodash 会通过 npm install 安装到本地缓存
+        let code = std::fs::read_to_string("fixtures/node_require_lodash.js")?;
+        info!("读取测试脚本: node_require_lodash.js");
+
+        let params = json!({ "data": [1, 2, 3, 4], "chunkSize": 2 });
+        let result = CodeExecutor::execute_with_options(
+            &code,
+            LanguageScript::Js.id(),
+            Some(params),
+            &node_options(None),
+        )
+        .await?;
+        info!("脚本执行完成, 日志: {:?}", result.logs);
+
+        assert!(result.error.is_none(), "不应有错误: {:?}", result.error);
+        let result_val = result.result.expect("应有返回结果");
+        assert_eq!(result_val["chunks"], json!([[1, 2], [3, 4]]));
+        assert_eq!(result_val["sum"], 10);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_npm_install_failure() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 不存在的包安装失败时直接返回错误,错误信息包含 npm 的输出
+        let code = r#"
+const missing = require('run-code-rmcp-nonexistent-package');
+function handler(input) { return missing(input); }
+"#;
+        let result = CodeExecutor::execute_with_options(
+            code,
+            LanguageScript::Js.id(),
+            None,
+            &node_options(None),
+        )
+        .await;
+
+        let err = format!("{:#}", result.expect_err("安装npm依赖失败应返回错误"));
+        info!("安装失败的错误: {err}");
+        assert!(err.contains("安装npm依赖"), "错误信息不正确: {err}");
+        assert!(err.contains("npm"), "错误信息应包含npm的输出: {err}");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod pipeline_tests {
+    use anyhow::Result;
+    use log::info;
+    use serde_json::json;
+
+    use crate::model::{CodeExecutor, PipelineStep};
+    use crate::tests::test_utils::setup;
+
+    fn steps(value: serde_json::Value) -> Vec<PipelineStep> {
+        serde_json::from_value(value).expect("invalid pipeline steps")
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_chains_results() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let steps = steps(json!([
+            {
+                "name": "sum",
+                "language": "shell",
+                "code": "handler() { echo \"sum step\"; set_result \"{\\\"total\\\": $((PARAM_A + PARAM_B)), \\\"unit\\\": \\\"cm\\\"}\"; }"
+            },
+            {
+                "name": "format",
+                "language": "bash",
+                "code": "handler() { set_result \"\\\"$PARAM_VALUE $PARAM_UNIT\\\"\"; }",
+                "param_mapping": { "value": "/total" },
+                "params": { "unit": "mm" }
+            }
+        ]));
+
+        let result =
+            CodeExecutor::execute_pipeline(&steps, Some(json!({ "a": 3, "b": 4 }))).await?;
+        info!("流水线执行结果: {result:?}");
+
+        assert!(result.success);
+        assert_eq!(result.failed_step, None);
+        assert_eq!(result.steps.len(), 2);
+        assert_eq!(
+            result.steps[0].result,
+            Some(json!({ "total": 7, "unit": "cm" }))
+        );
+        assert!(result.steps[0].logs.join("\n").contains("sum step"));
+        assert_eq!(result.result, Some(json!("7 mm")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_stops_on_error() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let steps = steps(json!([
+            { "language": "shell", "code": "handler() { set_result '{\"n\": 1}'; }" },
+            { "name": "broken", "language": "shell", "code": "handler() { echo boom >&2; exit 2; }" },
+            { "language": "shell", "code": "handler() { set_result '\"unreachable\"'; }" }
+        ]));
+
+        let result = CodeExecutor::execute_pipeline(&steps, None).await?;
+        info!("流水线执行结果: {result:?}");
+
+        assert!(!result.success);
+        assert_eq!(result.failed_step, Some(1));
+        assert_eq!(result.steps.len(), 2, "失败后不应继续执行");
+        assert!(result.steps[1].error.is_some());
+        assert_eq!(result.result, Some(json!({ "n": 1 })));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_continue_on_error() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let steps = steps(json!([
+            { "language": "shell", "code": "handler() { set_result '{\"n\": 1}'; }" },
+            {
+                "language": "shell",
+                "code": "handler() { exit 1; }",
+                "continue_on_error": true
+            },
+            { "language": "shell", "code": "handler() { set_result \"{\\\"n\\\": $((PARAM_N + 1))}\"; }" }
+        ]));
+
+        let result = CodeExecutor::execute_pipeline(&steps, None).await?;
+        info!("流水线执行结果: {result:?}");
+
+        assert!(result.success);
+        assert_eq!(result.failed_step, Some(1));
+        assert_eq!(result.steps.len(), 3);
+        assert_eq!(
+            result.result,
+            Some(json!({ "n": 2 })),
+            "应使用最近一次成功的结果"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod project_tests {
+    use std::collections::BTreeMap;
+
+    use anyhow::Result;
+    use log::info;
+    use serde_json::json;
+
+    use crate::mcp::LanguageCodeRunRequest;
+    use crate::model::{CodeExecutor, JsRuntime, LanguageScript, ProjectFiles, RunOptions};
+    use crate::tests::test_utils::setup;
+
+    /// 多文件项目的执行选项
+    fn project_options(entrypoint: &str, files: &[(&str, &str)]) -> RunOptions {
+        RunOptions {
+            project: Some(ProjectFiles {
+                entrypoint: entrypoint.to_string(),
+                files: files
+                    .iter()
+                    .map(|(path, content)| (path.to_string(), content.to_string()))
+                    .collect::<BTreeMap<_, _>>(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_node_commonjs_project() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let options = RunOptions {
+            runtime: Some(JsRuntime::Node),
+            ..project_options(
+                "main.js",
+                &[
+                    (
+                        "main.js",
+                        "const { add } = require('./lib/math');\nfunction handler(input) {\n    return { sum: add(input.a, input.b) };\n}\n",
+                    ),
+                    (
+                        "lib/math.js",
+                        "module.exports = { add: (a, b) => a + b };\n",
+                    ),
+                ],
+            )
+        };
+        let result = CodeExecutor::execute_with_options(
+            "",
+            LanguageScript::Js.id(),
+            Some(json!({ "a": 1, "b": 2 })),
+            &options,
+        )
+        .await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.result, Some(json!({ "sum": 3 })));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_esm_project_cache_covers_all_files() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let entry = "import { greet } from './greet.js';\nexport function handler(input) {\n    return greet(input.name);\n}\n";
+        let run = |greet: &'static str| {
+            let options = RunOptions {
+                runtime: Some(JsRuntime::Node),
+                ..project_options("main.js", &[("greet.js", greet)])
+            };
+            async move {
+                CodeExecutor::execute_with_options(
+                    entry,
+                    LanguageScript::Js.id(),
+                    Some(json!({ "name": "项目" })),
+                    &options,
+                )
+                .await
+            }
+        };
+
+        // 入口文件不在 files 中时使用 code 作为入口文件
+        let first = run("export const greet = (name) => `Hello, ${name}`;\n").await?;
+        assert!(first.success, "执行失败: {:?}", first.error);
+        assert_eq!(first.result, Some(json!("Hello, 项目")));
+
+        // 入口文件相同,其他文件变化时不会使用旧的缓存
+        let second = run("export const greet = (name) => `你好, ${name}`;\n").await?;
+        assert!(second.success, "执行失败: {:?}", second.error);
+        assert_eq!(second.result, Some(json!("你好, 项目")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_project_sibling_module() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let options = project_options(
+            "app/main.py",
+            &[
+                (
+                    "app/main.py",
+                    "from helper import double\n\ndef handler(input):\n    return {\"doubled\": double(input[\"n\"])}\n",
+                ),
+                ("app/helper.py", "def double(n):\n    return n * 2\n"),
+            ],
+        );
+        let result = CodeExecutor::execute_with_options(
+            "",
+            LanguageScript::Python.id(),
+            Some(json!({ "n": 21 })),
+            &options,
+        )
+        .await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.result, Some(json!({ "doubled": 42 })));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ts_project_relative_import() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let options = project_options(
+            "src/index.ts",
+            &[
+                (
+                    "src/index.ts",
+                    "import { multiply } from \"./math.ts\";\n\nfunction handler(input: { a: number; b: number }) {\n    return multiply(input.a, input.b);\n}\n",
+                ),
+                (
+                    "src/math.ts",
+                    "export function multiply(a: number, b: number): number {\n    return a * b;\n}\n",
+                ),
+            ],
+        );
+        let result = CodeExecutor::execute_with_options(
+            "",
+            LanguageScript::Ts.id(),
+            Some(json!({ "a": 6, "b": 7 })),
+            &options,
+        )
+        .await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.result, Some(json!(42)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_project() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 路径不能跳出项目目录
+        let escaped = CodeExecutor::execute_with_options(
+            "function handler() {}",
+            LanguageScript::Js.id(),
+            None,
+            &project_options("main.js", &[("../outside.js", "")]),
+        )
+        .await;
+        assert!(escaped.unwrap_err().to_string().contains("outside.js"));
+
+        // 不支持多文件项目的语言
+        let shell = CodeExecutor::execute_with_options(
+            "handler() { :; }",
+            LanguageScript::Shell.id(),
+            None,
+            &project_options("main.sh", &[]),
+        )
+        .await;
+        assert!(shell.unwrap_err().to_string().contains("多文件项目"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_project_options() -> Result<()> {
+        let request: LanguageCodeRunRequest = serde_json::from_value(json!({
+            "language": "python",
+            "files": { "main.py": "def handler(input): pass", "helper.py": "" },
+            "entrypoint": "main.py"
+        }))?;
+        let project = request.options.into_run_options().project.expect("应为多文件项目");
+        assert_eq!(project.entrypoint, "main.py");
+        assert_eq!(
+            project.entry_code(&request.code),
+            "def handler(input): pass"
+        );
+
+        let single: LanguageCodeRunRequest =
+            serde_json::from_value(json!({ "language": "python", "code": "print(1)" }))?;
+        assert!(single.options.into_run_options().project.is_none());
+
+        Ok(())
+    }
+}
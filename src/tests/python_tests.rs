@@ -1,10 +1,12 @@
+#![allow(clippy::module_inception)]
+
 #[cfg(test)]
 mod python_tests {
     use anyhow::Result;
     use log::info;
     use serde_json::json;
 
-    use crate::model::{CodeExecutor, LanguageScript};
+    use crate::model::{CodeExecutor, LanguageScript, LogStream, RunOptions};
     use crate::tests::test_utils::setup;
 
     #[tokio::test]
@@ -323,24 +325,17 @@ mod python_tests {
             if let Some(result_str) = result_val.as_str() {
                 let json_val = serde_json::from_str::<serde_json::Value>(result_str)?;
 
-                // 验证两种访问方式都能获取到参数
+                // 验证直接访问能获取到参数
                 assert_eq!(
                     json_val["direct_access"], "直接提供的参数",
                     "直接访问应该能获取到参数"
                 );
+                // 参数原样传入,不会复制到 params 中
+                assert_eq!(json_val["nested_access"], json!(null));
                 assert_eq!(
-                    json_val["nested_access"], "直接提供的参数",
-                    "嵌套访问也应该能获取到参数"
-                );
-
-                // 验证args结构中同时包含直接参数和params嵌套参数
-                assert!(
-                    json_val["args_structure"].get("input").is_some(),
-                    "args结构应包含直接参数"
-                );
-                assert!(
-                    json_val["args_structure"].get("params").is_some(),
-                    "args结构应包含params参数"
+                    json_val["args_structure"],
+                    params,
+                    "args结构应与传入的参数一致"
                 );
             }
         }
@@ -535,4 +530,265 @@ def main(args: dict) -> dict:
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_python_with_version() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+import sys
+
+def handler(args):
+    return f"{sys.version_info.major}.{sys.version_info.minor}"
+"#;
+        let options = RunOptions {
+            python_version: Some("3.13".to_string()),
+            ..Default::default()
+        };
+        let result = CodeExecutor::execute_with_options(
+            code,
+            LanguageScript::Python.id(),
+            None,
+            &options,
+        )
+        .await?;
+        info!("脚本执行结果: {:?}", result.result);
+
+        assert!(result.error.is_none(), "不应有错误: {:?}", result.error);
+        assert_eq!(result.result, Some(json!("3.13")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_with_invalid_version() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = "def handler(args):\n    return 1\n";
+        for version in ["3.11; rm -rf /", "latest", "1.0"] {
+            let options = RunOptions {
+                python_version: Some(version.to_string()),
+                ..Default::default()
+            };
+            let result = CodeExecutor::execute_with_options(
+                code,
+                LanguageScript::Python.id(),
+                None,
+                &options,
+            )
+            .await;
+            info!("Python版本 {version} 执行结果: {result:?}");
+            assert!(result.is_err(), "无效或未安装的Python版本应执行失败");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_async_handler_http() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 本地HTTP服务器,模拟异步handler请求的外部接口
+        let app = axum::Router::new().route(
+            "/items/{id}",
+            axum::routing::get(|axum::extract::Path(id): axum::extract::Path<u64>| async move {
+                axum::Json(json!({ "id": id, "name": format!("item-{id}") }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let code = r#"
+import asyncio
+import aiohttp
+
+async def fetch(session, base_url, item_id):
+    async with session.get(f"{base_url}/items/{item_id}") as response:
+        return await response.json()
+
+async def handler(args):
+    async with aiohttp.ClientSession() as session:
+        items = await asyncio.gather(*(fetch(session, args["base_url"], i) for i in args["ids"]))
+    return {"names": [item["name"] for item in items]}
+"#;
+        let params = json!({ "base_url": format!("http://{addr}"), "ids": [1, 2, 3] });
+        let result =
+            CodeExecutor::execute_with_params(code, LanguageScript::Python, Some(params), Some(60))
+                .await?;
+        info!("脚本执行结果: {result:?}");
+
+        assert!(result.error.is_none(), "不应有错误: {:?}", result.error);
+        assert_eq!(
+            result.result,
+            Some(json!({ "names": ["item-1", "item-2", "item-3"] }))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_async_handler_deadline() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 返回协程的普通函数同样会被执行
+        let code = r#"
+import asyncio
+
+async def slow():
+    await asyncio.sleep(30)
+    return "完成"
+
+def handler(args):
+    return slow()
+"#;
+        let start_time = std::time::Instant::now();
+        let result =
+            CodeExecutor::execute_with_params(code, LanguageScript::Python, None, Some(5)).await;
+        info!("脚本执行结果: {result:?}");
+        assert!(start_time.elapsed().as_secs() < 7, "应在超时时间内结束");
+
+        // 截止时间到达时协程被取消;uv 启动较慢时可能先触发外层超时
+        let error = match result {
+            Ok(result) => result.error.expect("应返回超时错误"),
+            Err(e) => format!("{e:#}"),
+        };
+        assert!(error.contains("timed out"), "错误信息: {error}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_handler_exception() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_python_error.py")?;
+        let result = CodeExecutor::execute_with_params(
+            &code,
+            LanguageScript::Python,
+            Some(json!({ "values": [] })),
+            None,
+        )
+        .await?;
+        info!("脚本执行结果: {result:?}");
+
+        // 异常作为执行错误返回,而不是结果为空的成功执行
+        assert!(!result.success, "handler 抛出异常时应执行失败");
+        assert!(result.result.is_none());
+        let error = result.error.unwrap_or_default();
+        assert!(
+            error.starts_with("ZeroDivisionError: division by zero"),
+            "错误信息: {error}"
+        );
+        // 调用栈只包含用户代码,行号对应用户代码中的行
+        assert!(
+            error.contains("File \"<user code>\", line 11, in handler"),
+            "错误信息: {error}"
+        );
+        assert!(
+            error.contains("File \"<user code>\", line 6, in average\n    return total / len(values)"),
+            "错误信息: {error}"
+        );
+        assert!(!error.contains("_run_code"), "错误信息: {error}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_module_level_exception() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 模块级别的异常与入口函数抛出的异常一样作为执行错误返回,之前的输出保留在日志中
+        let code = "print(\"loading\")\nconfig = undefined_name\n\ndef handler(args):\n    return config\n";
+        let result =
+            CodeExecutor::execute_with_params(code, LanguageScript::Python, None, None).await?;
+        info!("脚本执行结果: {result:?}");
+
+        assert!(!result.success, "模块级别抛出异常时应执行失败");
+        assert!(result.result.is_none());
+        let error = result.error.unwrap_or_default();
+        assert!(
+            error.starts_with("NameError: name 'undefined_name' is not defined"),
+            "错误信息: {error}"
+        );
+        assert!(
+            error.contains("File \"<user code>\", line 2, in <module>"),
+            "错误信息: {error}"
+        );
+        assert_eq!(result.logs, vec!["loading"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_output_capture() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_python_output.py")?;
+        let result =
+            CodeExecutor::execute_with_params(&code, LanguageScript::Python, None, None).await?;
+        info!("脚本执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(
+            result.logs,
+            [
+                "接收到的参数: {}",
+                // 分多次写入的一行作为一条日志
+                "partial line",
+                "[stderr] to stderr",
+                // 回车之前的内容被覆盖
+                "[stderr] progress 100%",
+                "[WARNING] UserWarning: old api (File \"<user code>\", line 13)",
+                "[DEBUG] debug message",
+                "[WARNING] app.db: slow query",
+                "[ERROR] app: failed\nZeroDivisionError: division by zero\n\
+                 Traceback (most recent call last):\n  \
+                 File \"<user code>\", line 18, in handler\n    1 / 0",
+                // 不以换行结尾的内容在执行结束时记录
+                "no newline at end",
+            ]
+        );
+
+        // 结构化的日志记录: logging 的记录包括日志记录器名称,与 logging 默认一致输出到标准错误
+        assert_eq!(result.log_entries.len(), result.logs.len());
+        let entry = |index: usize| {
+            let entry = &result.log_entries[index];
+            (
+                entry.level.as_deref(),
+                entry.logger.as_deref(),
+                entry.message.as_str(),
+                entry.stream,
+            )
+        };
+        assert_eq!(entry(1), (None, None, "partial line", LogStream::Stdout));
+        assert_eq!(entry(2), (None, None, "to stderr", LogStream::Stderr));
+        assert_eq!(
+            entry(5),
+            (
+                Some("DEBUG"),
+                Some("root"),
+                "debug message",
+                LogStream::Stderr
+            )
+        );
+        assert_eq!(
+            entry(6),
+            (
+                Some("WARNING"),
+                Some("app.db"),
+                "slow query",
+                LogStream::Stderr
+            )
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,118 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod request_options_tests {
+    use anyhow::Result;
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+
+    use crate::function::FunctionStore;
+    use crate::job::JobSubmission;
+    use crate::mcp::{
+        BatchRunRequest, CodeRunRequest, FunctionInvokeRequest, LanguageCodeRunRequest,
+    };
+    use crate::model::{DataFrameOrient, JsRuntime, PipelineStep, RunOptions, RunOptionsRequest};
+    use crate::tests::test_utils::setup;
+
+    /// 所有请求共用的执行选项
+    fn options_json() -> Value {
+        json!({
+            "timeout_seconds": 5,
+            "runtime": "node",
+            "python_version": "3.11",
+            "dataframe_orient": "split",
+            "input_schema": { "type": "object" },
+            "output_schema": { "type": "number" },
+            "files": { "main.ts": "export const handler = () => 1;" },
+            "entrypoint": "main.ts:handler",
+            "type_check": true
+        })
+    }
+
+    /// 请求参数与执行选项合并后反序列化,返回其中的执行选项
+    fn parse<T: DeserializeOwned>(
+        fields: Value,
+        options: impl Fn(T) -> RunOptionsRequest,
+    ) -> Result<RunOptions> {
+        let mut value = options_json();
+        value
+            .as_object_mut()
+            .expect("options should be an object")
+            .extend(fields.as_object().cloned().unwrap_or_default());
+        Ok(options(serde_json::from_value(value)?).into_run_options())
+    }
+
+    fn assert_options(options: &RunOptions) {
+        assert_eq!(options.timeout_seconds, Some(5));
+        assert_eq!(options.runtime, Some(JsRuntime::Node));
+        assert_eq!(options.python_version.as_deref(), Some("3.11"));
+        assert_eq!(options.dataframe_orient, Some(DataFrameOrient::Split));
+        assert_eq!(options.input_schema, Some(json!({ "type": "object" })));
+        assert_eq!(options.output_schema, Some(json!({ "type": "number" })));
+        assert_eq!(
+            options.project.as_ref().map(|p| p.entrypoint.as_str()),
+            Some("main.ts")
+        );
+        assert_eq!(options.entrypoint.as_deref(), Some("handler"));
+        assert!(options.type_check);
+    }
+
+    #[test]
+    fn test_requests_share_run_options() -> Result<()> {
+        let code = json!({ "code": "" });
+        let language = json!({ "language": "ts", "code": "" });
+        assert_options(&parse(code, |r: CodeRunRequest| r.options)?);
+        assert_options(&parse(language.clone(), |r: LanguageCodeRunRequest| {
+            r.options
+        })?);
+        assert_options(&parse(language.clone(), |r: JobSubmission| r.options)?);
+        assert_options(&parse(language, |r: PipelineStep| r.options)?);
+        assert_options(&parse(
+            json!({ "language": "ts", "params_list": [] }),
+            |r: BatchRunRequest| r.options,
+        )?);
+        assert_options(&parse(
+            json!({ "name": "add" }),
+            |r: FunctionInvokeRequest| r.options,
+        )?);
+
+        // 未设置执行选项时使用默认值
+        let request: LanguageCodeRunRequest =
+            serde_json::from_value(json!({ "language": "ts", "code": "" }))?;
+        let options = request.options.into_run_options();
+        assert!(options.timeout_seconds.is_none() && options.project.is_none());
+        assert!(!options.type_check);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_schema_contains_run_options() {
+        // 工具的输入参数中执行选项与其他参数平级
+        let schema = serde_json::to_value(schemars::schema_for!(PipelineStep)).unwrap();
+        let properties = schema["properties"].as_object().expect("properties");
+        for name in options_json().as_object().unwrap().keys() {
+            assert!(properties.contains_key(name), "缺少执行选项: {name}");
+        }
+        assert!(properties.contains_key("param_mapping"));
+    }
+
+    #[tokio::test]
+    async fn test_invoke_rejects_project_files() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        let options = parse(json!({ "name": "add" }), |r: FunctionInvokeRequest| {
+            r.options
+        })?;
+        let error = store.invoke("add", None, &options).await.unwrap_err();
+        assert!(
+            error.to_string().contains("files"),
+            "错误信息不正确: {error}"
+        );
+
+        Ok(())
+    }
+}
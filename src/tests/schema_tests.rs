@@ -0,0 +1,185 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod schema_tests {
+    use anyhow::Result;
+    use log::info;
+    use serde_json::json;
+
+    use crate::app_error::AppError;
+    use crate::function::{FunctionDeployment, FunctionStore};
+    use crate::model::{CodeExecutor, RunOptions};
+    use crate::tests::test_utils::setup;
+
+    fn schemas() -> RunOptions {
+        RunOptions {
+            input_schema: Some(json!({
+                "type": "object",
+                "properties": {
+                    "a": { "type": "integer" },
+                    "b": { "type": "integer", "minimum": 0 }
+                },
+                "required": ["a", "b"]
+            })),
+            output_schema: Some(json!({
+                "type": "object",
+                "properties": { "sum": { "type": "integer" } },
+                "required": ["sum"]
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_params_not_executed() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let marker = dir.path().join("executed");
+        let code = format!(
+            "handler() {{ touch {}; set_result '{{\"sum\": 1}}'; }}",
+            marker.display()
+        );
+
+        let error = CodeExecutor::execute_with_options(
+            &code,
+            "shell",
+            Some(json!({ "a": "1", "b": -1 })),
+            &schemas(),
+        )
+        .await
+        .expect_err("invalid params should be rejected");
+        info!("参数校验错误: {error}");
+
+        let Some(AppError::ValidationError(violations)) = error.downcast_ref::<AppError>() else {
+            panic!("unexpected error: {error:?}");
+        };
+        let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(paths, vec!["/a", "/b"]);
+        assert!(error.to_string().contains("/b: 不能小于 0"));
+        assert!(!marker.exists(), "child process should not be launched");
+
+        // 参数符合时正常执行
+        let result = CodeExecutor::execute_with_options(
+            &code,
+            "shell",
+            Some(json!({ "a": 1, "b": 2 })),
+            &schemas(),
+        )
+        .await?;
+        assert!(result.success);
+        assert!(marker.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_result_contract_violation() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let result = CodeExecutor::execute_with_options(
+            "handler() { echo computing; set_result '{\"sum\": \"3\"}'; }",
+            "shell",
+            Some(json!({ "a": 1, "b": 2 })),
+            &schemas(),
+        )
+        .await?;
+        info!("执行结果: {result:?}");
+
+        assert!(!result.success);
+        let error = result.error.unwrap_or_default();
+        assert!(error.contains("Result violates output schema"), "{error}");
+        assert!(
+            error.contains("/sum: 类型应为 integer, 实际为 string"),
+            "{error}"
+        );
+        // 保留日志和原始结果,便于排查
+        assert!(result.logs.join("\n").contains("computing"));
+        assert_eq!(result.result, Some(json!({ "sum": "3" })));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_function_schemas() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let store = FunctionStore::new(dir.path())?;
+        let options = schemas();
+        store
+            .deploy(FunctionDeployment {
+                name: "checked_sum".to_string(),
+                language: "shell".to_string(),
+                code: "handler() { set_result \"{\\\"sum\\\": $((PARAM_A + PARAM_B))}\"; }"
+                    .to_string(),
+                dependencies: vec![],
+                timeout_seconds: None,
+                description: None,
+                input_schema: options.input_schema,
+                output_schema: options.output_schema,
+                entrypoint: None,
+                env: Default::default(),
+            })
+            .await?;
+
+        let result = store
+            .invoke(
+                "checked_sum",
+                Some(json!({ "a": 1, "b": 2 })),
+                &RunOptions::default(),
+            )
+            .await?;
+        assert_eq!(result.result, Some(json!({ "sum": 3 })));
+
+        let error = store
+            .invoke(
+                "checked_sum",
+                Some(json!({ "a": 1 })),
+                &RunOptions::default(),
+            )
+            .await
+            .expect_err("missing param should be rejected");
+        assert!(error.to_string().contains("/b: 缺少必需的属性"), "{error}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_schema_rejected_before_execution() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let marker = dir.path().join("executed");
+        let code = format!(
+            "handler() {{ touch {}; set_result '1'; }}",
+            marker.display()
+        );
+        let options = RunOptions {
+            output_schema: Some(json!({ "$ref": "#/$defs/missing" })),
+            ..Default::default()
+        };
+
+        let error = CodeExecutor::execute_with_options(&code, "shell", None, &options)
+            .await
+            .expect_err("invalid schema should be rejected");
+        info!("schema错误: {error:#}");
+        assert!(
+            format!("{error:#}").contains("output_schema 无效"),
+            "{error:#}"
+        );
+
+        // 批量执行时 schema 只编译一次,无效时所有参数都返回同一个错误
+        let batch =
+            CodeExecutor::execute_batch(&code, "shell", vec![json!({}), json!({})], &options, None)
+                .await?;
+        assert_eq!(batch.failed, 2);
+        assert!(!marker.exists(), "child process should not be launched");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod serialization_tests {
+    use anyhow::Result;
+    use log::info;
+    use serde_json::{Value, json};
+
+    use crate::mcp::LanguageCodeRunRequest;
+    use crate::model::{
+        CodeExecutor, CodeScriptExecutionResult, DataFrameOrient, JsRuntime, LanguageScript,
+        RunOptions,
+    };
+    use crate::tests::test_utils::setup;
+
+    #[tokio::test]
+    async fn test_python_rich_types() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_python_serialization.py")?;
+        let result =
+            CodeExecutor::execute_with_params(&code, LanguageScript::Python, None, None).await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(
+            result.result,
+            Some(json!({
+                "at": "2024-03-01T08:30:00+00:00",
+                "elapsed": 90.0,
+                "price": 19.99,
+                "tags": ["a", "b"],
+                "status": "active",
+                "id": "00000000-0000-0000-0000-000000000001",
+                "order": { "id": 1, "created": "2024-03-01", "total": 100 },
+                "missing": null,
+                "balance": "123.45 CNY",
+                "by_date": { "2024-03-01": 1 }
+            }))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_pandas_numpy() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+import numpy as np
+import pandas as pd
+
+def handler(args):
+    df = pd.DataFrame({
+        "day": pd.to_datetime(["2024-01-01", "2024-01-02"]),
+        "value": [np.int64(1), np.nan],
+    })
+    return {
+        "frame": df,
+        "mean": np.float64(1.5),
+        "matrix": np.arange(4).reshape(2, 2),
+    }
+"#;
+        let run = |orient: Option<DataFrameOrient>| {
+            let options = RunOptions {
+                dataframe_orient: orient,
+                ..Default::default()
+            };
+            async move {
+                CodeExecutor::execute_with_options(
+                    code,
+                    LanguageScript::Python.id(),
+                    None,
+                    &options,
+                )
+                .await
+            }
+        };
+
+        // 默认按行记录转换
+        let records = run(None).await?;
+        info!("执行结果: {records:?}");
+        assert!(records.success, "执行失败: {:?}", records.error);
+        assert_eq!(
+            records.result,
+            Some(json!({
+                "frame": [
+                    { "day": "2024-01-01T00:00:00", "value": 1.0 },
+                    { "day": "2024-01-02T00:00:00", "value": null }
+                ],
+                "mean": 1.5,
+                "matrix": [[0, 1], [2, 3]]
+            }))
+        );
+
+        let split = run(Some(DataFrameOrient::Split)).await?;
+        assert!(split.success, "执行失败: {:?}", split.error);
+        assert_eq!(
+            split.result.map(|result| result["frame"].clone()),
+            Some(json!({
+                "index": [0, 1],
+                "columns": ["day", "value"],
+                "data": [["2024-01-01T00:00:00", 1.0], ["2024-01-02T00:00:00", null]]
+            }))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_pydantic_result() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+import datetime
+from pydantic import BaseModel
+
+class Item(BaseModel):
+    name: str
+    updated: datetime.datetime
+
+def handler(args):
+    return [Item(name="a", updated=datetime.datetime(2024, 1, 1, 12))]
+"#;
+        let result =
+            CodeExecutor::execute_with_params(code, LanguageScript::Python, None, None).await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(
+            result.result,
+            Some(json!([{ "name": "a", "updated": "2024-01-01T12:00:00" }]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_dataframe_orient() -> Result<()> {
+        let request: LanguageCodeRunRequest = serde_json::from_value(json!({
+            "language": "python",
+            "code": "def handler(args): pass",
+            "dataframe_orient": "split"
+        }))?;
+        assert_eq!(
+            request.options.into_run_options().dataframe_orient,
+            Some(DataFrameOrient::Split)
+        );
+        assert_eq!(DataFrameOrient::default().as_str(), "records");
+
+        let invalid = serde_json::from_value::<LanguageCodeRunRequest>(json!({
+            "language": "python",
+            "code": "",
+            "dataframe_orient": "series"
+        }));
+        assert!(invalid.is_err());
+
+        Ok(())
+    }
+
+    /// fixtures/test_js_serialization.js 的转换结果
+    fn expected_js_rich_types() -> Value {
+        json!({
+            "big": "9007199254740993",
+            "when": "2024-03-01T08:30:00.000Z",
+            "invalidDate": null,
+            "counts": { "a": 1, "b": 2 },
+            "byObject": [[{ "id": 1 }, "first"]],
+            "tags": ["x", "y"],
+            "floats": [1.5, null],
+            "bigs": ["1", "2"],
+            "bytes": { "$binary": "AQID", "mime": null },
+            "infinite": null,
+            "sparse": [1, null, null],
+            "error": { "name": "RangeError", "message": "out of range" },
+            "pattern": "/run-code/i",
+            "temperature": "21°C",
+            "point": { "x": 1, "y": 2 }
+        })
+    }
+
+    /// 使用指定的运行时执行JavaScript代码
+    async fn run_js(code: &str, runtime: JsRuntime) -> Result<CodeScriptExecutionResult> {
+        let options = RunOptions {
+            runtime: Some(runtime),
+            ..Default::default()
+        };
+        let result =
+            CodeExecutor::execute_with_options(code, LanguageScript::Js.id(), None, &options)
+                .await?;
+        info!("执行结果: {result:?}");
+        Ok(result)
+    }
+
+    #[tokio::test]
+    async fn test_node_rich_types() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_js_serialization.js")?;
+        let result = run_js(&code, JsRuntime::Node).await?;
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.result, Some(expected_js_rich_types()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deno_rich_types() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_js_serialization.js")?;
+        let result = run_js(&code, JsRuntime::Deno).await?;
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.result, Some(expected_js_rich_types()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_circular_reference() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+export function handler() {
+    const root = { name: "root", children: [] };
+    const child = { name: "child", parent: root };
+    root.children.push(child);
+    root.self = root;
+    // 重复出现但不构成循环的对象完整保留
+    const shared = { id: 1 };
+    return { root, pair: [shared, shared] };
+}
+"#;
+        let result = run_js(code, JsRuntime::Node).await?;
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(
+            result.result,
+            Some(json!({
+                "root": {
+                    "name": "root",
+                    "children": [{ "name": "child", "parent": { "$ref": "$.root" } }],
+                    "self": { "$ref": "$.root" }
+                },
+                "pair": [{ "id": 1 }, { "id": 1 }]
+            }))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_top_level_bigint() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 超过 Number.MAX_SAFE_INTEGER 的 BigInt 直接返回时保持为字符串,不丢失精度
+        let code = "export function handler() { return 9007199254740993n; }";
+        let result = run_js(code, JsRuntime::Node).await?;
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(result.result, Some(json!("9007199254740993")));
+
+        // 普通数字的结果不受影响
+        let result = run_js("export function handler() { return 42; }", JsRuntime::Node).await?;
+        assert_eq!(result.result, Some(json!(42)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ts_rich_types() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+interface TreeNode {
+    id: bigint;
+    seen: Set<string>;
+    next?: TreeNode;
+}
+
+function handler(): Record<string, unknown> {
+    const node: TreeNode = { id: 1n, seen: new Set(["a"]) };
+    node.next = node;
+    return { node, at: new Date(0), index: new Map([[1, true]]) };
+}
+"#;
+        let result =
+            CodeExecutor::execute_with_params(code, LanguageScript::Ts, None, None).await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(
+            result.result,
+            Some(json!({
+                "node": { "id": "1", "seen": ["a"], "next": { "$ref": "$.node" } },
+                "at": "1970-01-01T00:00:00.000Z",
+                "index": { "1": true }
+            }))
+        );
+
+        Ok(())
+    }
+}
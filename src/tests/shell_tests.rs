@@ -0,0 +1,191 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod shell_tests {
+    use anyhow::Result;
+    use log::info;
+    use rmcp::{ServiceExt, model::CallToolRequestParam};
+    use serde_json::{Value, json};
+
+    use crate::mcp::CodeRunnerService;
+    use crate::model::{CodeExecutor, LanguageScript, LogStream};
+    use crate::tests::test_utils::setup;
+
+    #[tokio::test]
+    async fn test_shell_with_params() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 从 fixtures 目录读取测试脚本
+        let code = std::fs::read_to_string("fixtures/test_shell.sh")?;
+        info!("读取测试脚本: test_shell.sh");
+
+        // 准备参数
+        let params = json!({
+            "a": 10,
+            "b": 20,
+            "name": "测试用户",
+            "user": { "city": "Beijing" }
+        });
+        info!("准备测试参数: {params:?}");
+
+        // 执行脚本
+        info!("开始执行Shell脚本...");
+        let result =
+            CodeExecutor::execute_with_params_compat(&code, LanguageScript::Shell, Some(params))
+                .await?;
+        info!("脚本执行完成, 日志: {:?}", result.logs);
+
+        // 验证结果
+        assert!(result.error.is_none(), "不应有错误");
+        let logs_str = result.logs.join("\n");
+        assert!(logs_str.contains("Shell script loaded"), "日志应包含脚本输出");
+        assert!(logs_str.contains("Hello, 测试用户"), "日志应包含handler输出");
+        assert!(
+            logs_str.contains("[stderr] 这是一条错误输出"),
+            "日志应包含标准错误输出"
+        );
+        // 结构化的日志记录从日志文本解析
+        assert!(result.log_entries.iter().any(|entry| {
+            entry.stream == LogStream::Stderr && entry.message == "这是一条错误输出"
+        }));
+
+        let result_val = result.result.expect("应有返回结果");
+        assert_eq!(result_val["sum"], 30, "sum 应为 30");
+        assert_eq!(result_val["greeting"], "Hello, 测试用户");
+        assert_eq!(result_val["city"], "Beijing");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shell_handler_failure() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+handler() {
+    echo "即将失败"
+    echo "something went wrong" >&2
+    return 3
+}
+"#;
+        let result = CodeExecutor::execute_with_params_compat(code, LanguageScript::Shell, None)
+            .await?;
+        info!("脚本执行完成, 日志: {:?}", result.logs);
+
+        assert!(!result.success, "handler 返回非0应执行失败");
+        let error = result.error.expect("应有错误信息");
+        assert!(error.contains("something went wrong"), "错误信息应包含标准错误");
+        assert!(result.result.is_none(), "不应有返回结果");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shell_without_handler() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = "echo 'no handler here'";
+        let result = CodeExecutor::execute_with_params_compat(code, LanguageScript::Shell, None)
+            .await?;
+
+        assert!(!result.success, "没有handler函数应执行失败");
+        let error = result.error.unwrap_or_default();
+        assert!(
+            error.contains("HandlerNotFound: no function named handler or main"),
+            "错误信息应提示缺少handler函数: {error}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shell_with_timeout() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = r#"
+handler() {
+    echo "开始执行耗时操作"
+    sleep 10
+    set_result '"完成"'
+}
+"#;
+        let start_time = std::time::Instant::now();
+        let result =
+            CodeExecutor::execute_with_params(code, LanguageScript::Shell, None, Some(1)).await;
+        let elapsed = start_time.elapsed();
+
+        info!("脚本执行耗时: {elapsed:?}");
+        assert!(elapsed.as_secs() < 3, "脚本应该在3秒内超时");
+        let err = result.expect_err("应该返回超时错误");
+        assert!(format!("{err:#}").contains("timed out"), "错误信息应包含超时信息");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shell_tool_log_entries() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let (client_stream, server_stream) = tokio::io::duplex(65536);
+        tokio::spawn(async move {
+            if let Ok(server) = CodeRunnerService.serve(server_stream).await {
+                let _ = server.waiting().await;
+            }
+        });
+        let client = ().serve(client_stream).await?;
+
+        // 执行成功和失败时,工具结果都包含结构化的日志记录
+        let cases = [
+            (
+                r#"handler() { echo "working"; echo "careful" >&2; set_result '1'; }"#,
+                true,
+            ),
+            (
+                r#"handler() { echo "working"; echo "careful" >&2; exit 2; }"#,
+                false,
+            ),
+        ];
+        for (code, success) in cases {
+            let result = client
+                .call_tool(CallToolRequestParam {
+                    name: "run_shell".into(),
+                    arguments: json!({ "code": code }).as_object().cloned(),
+                })
+                .await?;
+            let text = result
+                .content
+                .as_ref()
+                .and_then(|content| content.first())
+                .and_then(|content| content.as_text())
+                .map(|text| text.text.clone())
+                .unwrap_or_default();
+            let payload: Value = serde_json::from_str(&text)?;
+            info!("工具结果: {payload}");
+
+            assert_eq!(payload["success"], success);
+            let entries = payload["log_entries"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
+            assert!(entries.contains(&json!({
+                "level": null,
+                "logger": null,
+                "message": "working",
+                "stream": "stdout"
+            })));
+            assert!(entries.contains(&json!({
+                "level": null,
+                "logger": null,
+                "message": "careful",
+                "stream": "stderr"
+            })));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod signature_tests {
+    use anyhow::Result;
+    use rmcp::{ServiceExt, model::CallToolRequestParam};
+    use serde_json::{Value, json};
+
+    use crate::mcp::CodeRunnerService;
+    use crate::signature::infer_signature;
+    use crate::tests::test_utils::setup;
+
+    #[test]
+    fn test_infer_from_fixtures() -> Result<()> {
+        setup();
+
+        // TypeScript: interface 中的可选属性不是必填
+        let code = std::fs::read_to_string("fixtures/test_ts_params.ts")?;
+        let signature = infer_signature("ts", &code, None);
+        assert_eq!(signature.handler.as_deref(), Some("handler"));
+        assert!(
+            signature
+                .description
+                .as_deref()
+                .is_some_and(|d| d.starts_with("处理函数"))
+        );
+        assert_eq!(signature.input_schema["required"], json!(["a", "b"]));
+        assert_eq!(
+            signature.input_schema["properties"]["name"],
+            json!({ "type": "string" })
+        );
+
+        // Go: 结构体字段使用 json 标签名,omitempty 字段可选
+        let code = std::fs::read_to_string("fixtures/test_go_struct.go")?;
+        let signature = infer_signature("go", &code, None);
+        assert_eq!(
+            signature.input_schema,
+            json!({
+                "type": "object",
+                "description": "GreetInput 问候参数",
+                "properties": {
+                    "name": { "type": "string", "description": "用户名" },
+                    "times": { "type": "integer", "description": "重复次数,默认为1" }
+                },
+                "required": ["name"]
+            })
+        );
+
+        // 没有类型信息时不限制参数
+        let code = std::fs::read_to_string("fixtures/test_python_params.py")?;
+        let signature = infer_signature("python", &code, None);
+        assert_eq!(signature.handler.as_deref(), Some("main"));
+        assert!(!signature.inferred);
+        assert!(!infer_signature("shell", "handler() { :; }", None).inferred);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_function_tool() -> Result<()> {
+        setup();
+
+        let (client_stream, server_stream) = tokio::io::duplex(8192);
+        tokio::spawn(async move {
+            if let Ok(server) = CodeRunnerService.serve(server_stream).await {
+                let _ = server.waiting().await;
+            }
+        });
+        let client = ().serve(client_stream).await?;
+
+        let code = r#"
+from typing import TypedDict
+
+class Input(TypedDict):
+    city: str  # 城市名
+
+def handler(args: Input):
+    """查询天气"""
+    return {"city": args["city"]}
+"#;
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "describe_function".into(),
+                arguments: json!({ "language": "python", "code": code })
+                    .as_object()
+                    .cloned(),
+            })
+            .await?;
+        let text = result
+            .content
+            .as_ref()
+            .and_then(|content| content.first())
+            .and_then(|content| content.as_text())
+            .map(|text| text.text.clone())
+            .unwrap_or_default();
+        let description: Value = serde_json::from_str(&text)?;
+        assert_eq!(description["handler"], "handler");
+        assert_eq!(description["description"], "查询天气");
+        assert_eq!(description["inferred"], true);
+        assert_eq!(
+            description["input_schema"]["properties"]["city"],
+            json!({ "type": "string", "description": "城市名" })
+        );
+
+        // 缺少参数时返回错误说明
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "describe_function".into(),
+                arguments: json!({ "language": "python" }).as_object().cloned(),
+            })
+            .await?;
+        let content = serde_json::to_value(&result.content)?;
+        assert!(content.to_string().contains("需要指定 name"), "{content}");
+
+        client.cancel().await?;
+        Ok(())
+    }
+}
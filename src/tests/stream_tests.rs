@@ -0,0 +1,199 @@
+#![allow(clippy::module_inception)]
+
+#[cfg(test)]
+mod stream_tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use log::info;
+    use serde_json::{Value, json};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use crate::job::{JobManager, JobManagerConfig, JobSubmission, job_router};
+    use crate::model::{CHUNK_MARKER, ChunkSink, CodeExecutor, LanguageScript, RunOptions};
+    use crate::tests::test_utils::setup;
+
+    /// 收集数据块的执行选项
+    fn collecting_options() -> (RunOptions, Arc<Mutex<Vec<Value>>>) {
+        let chunks = Arc::new(Mutex::new(vec![]));
+        let sink_chunks = chunks.clone();
+        let options = RunOptions {
+            chunk_sink: Some(ChunkSink::new(move |chunk| {
+                sink_chunks.lock().unwrap().push(chunk.clone())
+            })),
+            ..Default::default()
+        };
+        (options, chunks)
+    }
+
+    /// 输出数据块标记行的Shell脚本,模拟生成器handler
+    fn shell_chunks_code() -> String {
+        format!(
+            "handler() {{\n    echo \"开始\"\n    echo '{CHUNK_MARKER}{{\"index\": 0}}'\n    echo '{CHUNK_MARKER}\"第二段\"'\n    set_result '{{\"done\": true}}'\n}}"
+        )
+    }
+
+    #[tokio::test]
+    async fn test_chunk_sink_receives_marker_lines() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let (options, chunks) = collecting_options();
+        let result = CodeExecutor::execute_with_options(
+            &shell_chunks_code(),
+            LanguageScript::Shell.id(),
+            None,
+            &options,
+        )
+        .await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success);
+        assert_eq!(result.result, Some(json!({ "done": true })));
+        assert_eq!(
+            *chunks.lock().unwrap(),
+            vec![json!({ "index": 0 }), json!("第二段")]
+        );
+        // 数据块不作为日志返回
+        let logs = result.logs.join("\n");
+        assert!(logs.contains("开始"));
+        assert!(!logs.contains(CHUNK_MARKER));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_job_records_chunks() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let dir = tempfile::tempdir()?;
+        let manager = JobManager::new(JobManagerConfig {
+            store_dir: dir.path().to_path_buf(),
+            ..Default::default()
+        })?;
+        let submitted = manager.submit(JobSubmission {
+            language: "shell".to_string(),
+            code: shell_chunks_code(),
+            params: None,
+            options: Default::default(),
+        })?;
+        let mut record = manager.get(&submitted.id).expect("job not found");
+        for _ in 0..200 {
+            if record.status.is_finished() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            record = manager.get(&submitted.id).expect("job not found");
+        }
+
+        assert!(record.status.is_finished());
+        assert_eq!(record.chunks, vec![json!({ "index": 0 }), json!("第二段")]);
+        assert_eq!(record.status_view()["chunks"][1], json!("第二段"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_stream_ndjson() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, job_router()).await });
+
+        let body = json!({ "language": "shell", "code": shell_chunks_code() }).to_string();
+        let mut stream = tokio::net::TcpStream::connect(addr).await?;
+        stream
+            .write_all(
+                format!(
+                    "POST /run/stream HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .as_bytes(),
+            )
+            .await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        info!("流式响应: {response}");
+
+        assert!(response.contains("application/x-ndjson"));
+        // 响应使用分块传输编码,只取其中的JSON行
+        let events: Vec<Value> = response
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        let chunks: Vec<&Value> = events.iter().filter(|e| e["type"] == "chunk").collect();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0]["index"], json!(0));
+        assert_eq!(chunks[0]["data"], json!({ "index": 0 }));
+        assert_eq!(chunks[1]["data"], json!("第二段"));
+        assert!(
+            events
+                .iter()
+                .any(|e| e["type"] == "log" && e["line"] == "开始")
+        );
+
+        let last = events.last().expect("应有结果事件");
+        assert_eq!(last["type"], "result");
+        assert_eq!(last["success"], json!(true));
+        assert_eq!(last["result"], json!({ "done": true }));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_python_generator_handler() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_python_generator.py")?;
+        let (options, chunks) = collecting_options();
+        let result = CodeExecutor::execute_with_options(
+            &code,
+            LanguageScript::Python.id(),
+            Some(json!({ "count": 2 })),
+            &options,
+        )
+        .await?;
+        info!("执行结果: {result:?}");
+
+        let expected = vec![
+            json!({ "index": 0, "text": "第1段" }),
+            json!({ "index": 1, "text": "第2段" }),
+        ];
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(*chunks.lock().unwrap(), expected);
+        // 异步生成器没有返回值,结果为所有数据块
+        assert_eq!(result.result, Some(json!(expected)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_js_generator_handler() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        let code = std::fs::read_to_string("fixtures/test_js_generator.js")?;
+        let (options, chunks) = collecting_options();
+        let result = CodeExecutor::execute_with_options(
+            &code,
+            LanguageScript::Js.id(),
+            Some(json!({ "count": 2 })),
+            &options,
+        )
+        .await?;
+        info!("执行结果: {result:?}");
+
+        assert!(result.success, "执行失败: {:?}", result.error);
+        assert_eq!(chunks.lock().unwrap().len(), 2);
+        // 生成器的返回值作为最终结果
+        assert_eq!(result.result, Some(json!({ "total": 2 })));
+
+        Ok(())
+    }
+}
@@ -1,10 +1,18 @@
+#![allow(clippy::module_inception)]
+
 #[cfg(test)]
 mod ts_tests {
     use anyhow::Result;
     use log::info;
     use serde_json::json;
 
-    use crate::model::{CodeExecutor, LanguageScript};
+    use crate::app_error::AppError;
+    use crate::deno_runner::check_typescript;
+    use crate::job::{JobManager, JobManagerConfig, JobStatus, JobSubmission};
+    use crate::mcp::LanguageCodeRunRequest;
+    use crate::model::{
+        CodeExecutor, LanguageScript, PipelineStep, ProjectFiles, RunOptions, USER_CODE_FILE,
+    };
     use crate::tests::test_utils::setup;
 
     #[tokio::test]
@@ -115,10 +123,15 @@ mod ts_tests {
         let code = std::fs::read_to_string("fixtures/test_ts.ts")?;
         info!("读取测试脚本: test_ts.ts");
 
-        // 执行脚本
+        // 执行前检查类型
         info!("开始执行TypeScript类型检查...");
+        let options = RunOptions {
+            type_check: true,
+            ..Default::default()
+        };
         let result =
-            CodeExecutor::execute_with_params_compat(&code, LanguageScript::Ts, None).await?;
+            CodeExecutor::execute_with_options(&code, LanguageScript::Ts.id(), None, &options)
+                .await?;
         info!("类型检查完成");
 
         if let Some(error) = &result.error {
@@ -207,4 +220,165 @@ async function handler(input: any): Promise<{result: string}> {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_ts_type_errors() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 有类型错误时不执行代码,诊断信息的行号对应用户代码中的行
+        let code = r#"interface Input {
+    a: number;
+}
+
+function handler(input: Input): number {
+    const label: number = "total";
+    return input.a + input.missing;
+}
+"#;
+        let options = RunOptions {
+            type_check: true,
+            ..Default::default()
+        };
+        let error =
+            CodeExecutor::execute_with_options(code, LanguageScript::Ts.id(), None, &options)
+                .await
+                .unwrap_err();
+        info!("类型检查错误: {error}");
+        let Some(AppError::TypeCheckError(diagnostics)) = error.downcast_ref::<AppError>() else {
+            panic!("应返回类型检查错误: {error}");
+        };
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| d.file == USER_CODE_FILE));
+        assert_eq!(
+            (
+                diagnostics[0].line,
+                diagnostics[0].column,
+                diagnostics[0].code.as_deref()
+            ),
+            (6, 11, Some("TS2322"))
+        );
+        assert_eq!(
+            (diagnostics[1].line, diagnostics[1].code.as_deref()),
+            (7, Some("TS2339"))
+        );
+        assert!(diagnostics[1].message.contains("missing"));
+
+        // 不检查类型时按原来的方式执行
+        let result = CodeExecutor::execute_with_params(
+            code,
+            LanguageScript::Ts,
+            Some(json!({"a": 1})),
+            None,
+        )
+        .await?;
+        assert!(result.success);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_check_typescript_globals_and_project() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 模板提供的调用上下文类型可以直接使用
+        let code = r#"export async function handler(input: { name: string }, context: InvocationContext) {
+    context.logger.info("hello", input.name);
+    return { id: context.invocationId, remaining: context.getRemainingTimeInMillis() };
+}
+"#;
+        assert_eq!(check_typescript(code, None, None).await?, vec![]);
+
+        // 多文件项目中诊断信息的文件为相对路径
+        let project = ProjectFiles {
+            entrypoint: "main.ts".to_string(),
+            files: [
+                (
+                    "main.ts".to_string(),
+                    "import { add } from \"./lib/math.ts\";\nexport const handler = () => add(1, \"2\");\n"
+                        .to_string(),
+                ),
+                (
+                    "lib/math.ts".to_string(),
+                    "export function add(a: number, b: number): number {\n    return a + b;\n}\n"
+                        .to_string(),
+                ),
+            ]
+            .into(),
+        };
+        let diagnostics = check_typescript(project.entry_code(""), Some(&project), None).await?;
+        info!("诊断信息: {diagnostics:?}");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, "main.ts");
+        assert_eq!(diagnostics[0].line, 2);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("TS2345"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_type_check_request_option() -> Result<()> {
+        let request: LanguageCodeRunRequest = serde_json::from_value(json!({
+            "language": "ts",
+            "code": "export const handler = () => 1;",
+            "type_check": true
+        }))?;
+        assert!(request.options.into_run_options().type_check);
+
+        let request: LanguageCodeRunRequest =
+            serde_json::from_value(json!({ "language": "ts", "code": "" }))?;
+        assert!(!request.options.into_run_options().type_check);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ts_type_check_in_pipeline_and_job() -> Result<()> {
+        // 初始化日志
+        setup();
+
+        // 流水线步骤和异步任务同样支持 type_check,有类型错误时不执行代码
+        let code =
+            "function handler(): number {\n    console.log(\"executed\");\n    return \"1\";\n}\n";
+        let steps: Vec<PipelineStep> = serde_json::from_value(json!([
+            { "language": "ts", "code": code, "type_check": true }
+        ]))?;
+        let result = CodeExecutor::execute_pipeline(&steps, None).await?;
+        info!("流水线执行结果: {result:?}");
+        assert!(!result.success);
+        let error = result.steps[0].error.clone().unwrap_or_default();
+        assert!(
+            error.contains("Type check failed"),
+            "错误信息不正确: {error}"
+        );
+        assert!(error.contains("TS2322"), "错误信息不正确: {error}");
+
+        let dir = tempfile::tempdir()?;
+        let manager = JobManager::new(JobManagerConfig {
+            store_dir: dir.path().to_path_buf(),
+            ..Default::default()
+        })?;
+        let submission: JobSubmission = serde_json::from_value(json!({
+            "language": "ts",
+            "code": code,
+            "type_check": true
+        }))?;
+        let submitted = manager.submit(submission)?;
+        let mut record = manager.get(&submitted.id).expect("job not found");
+        for _ in 0..600 {
+            if record.status.is_finished() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            record = manager.get(&submitted.id).expect("job not found");
+        }
+        info!("任务执行结果: {record:?}");
+        assert_eq!(record.status, JobStatus::Failed);
+        let error = record.error.unwrap_or_default();
+        assert!(error.contains("TS2322"), "错误信息不正确: {error}");
+        assert!(!record.logs.join("\n").contains("executed"));
+
+        Ok(())
+    }
 }
@@ -1,5 +1,8 @@
-use crate::model::CommandExecutor;
-use anyhow::Result;
+use crate::{
+    model::{CommandExecutor, LanguageScript},
+    python_runner::installed_python_versions,
+};
+use anyhow::{Context, Result, bail};
 use log::{info, warn};
 use std::path::Path;
 use tokio::process::Command;
@@ -7,20 +10,23 @@ use tokio::process::Command;
 //定义国内python加速地址: https://mirrors.aliyun.com/pypi/simple
 const PYTHON_ACCELERATION_ADDRESS: &str = "https://mirrors.aliyun.com/pypi/simple";
 
-//使用 uv安装 python 3.13，比如： uv python install 3.13
-async fn install_python_3_13() -> Result<()> {
+//默认预热安装的Python版本
+const DEFAULT_PYTHON_VERSIONS: &[&str] = &["3.13"];
+
+//使用 uv安装 python 指定版本，比如： uv python install 3.13
+async fn install_python_version(version: &str) -> Result<()> {
     let mut cmd = Command::new("uv");
     cmd.arg("python")
         .arg("install")
-        .arg("3.13")
+        .arg(version)
         .kill_on_drop(true);
     match CommandExecutor::with_timeout(cmd.status(), 600).await {
         Ok(Ok(status)) => {
             if !status.success() {
-                warn!("安装Python 3.13失败");
-                return Err(anyhow::anyhow!("安装Python 3.13失败"));
+                warn!("安装Python {version}失败");
+                return Err(anyhow::anyhow!("安装Python {}失败", version));
             }
-            info!("安装Python 3.13成功");
+            info!("安装Python {version}成功");
             Ok(())
         }
         Ok(Err(e)) => {
@@ -34,6 +40,30 @@ async fn install_python_3_13() -> Result<()> {
     }
 }
 
+/// 安装需要的Python版本，未指定时安装默认版本
+async fn install_python_versions(custom_versions: Option<Vec<String>>) -> Result<()> {
+    let versions = if let Some(versions) = custom_versions {
+        info!("使用自定义Python版本列表");
+        versions
+    } else {
+        info!("使用默认Python版本列表");
+        DEFAULT_PYTHON_VERSIONS
+            .iter()
+            .map(|&s| s.to_string())
+            .collect()
+    };
+
+    for version in versions.iter() {
+        if let Err(e) = install_python_version(version).await {
+            warn!("安装Python {version}失败: {e}");
+        }
+    }
+
+    // 刷新已安装版本的缓存，让按版本执行的请求能识别新安装的版本
+    installed_python_versions(true).await?;
+    Ok(())
+}
+
 // 检查 uv 虚拟环境是否存在
 async fn check_and_create_uv_venv() -> Result<()> {
     info!("检查 uv 虚拟环境...");
@@ -297,8 +327,62 @@ async fn warm_up_js_env(
     Ok(())
 }
 
+/// 安装函数声明的依赖,JavaScript/TypeScript缓存npm包和 `jsr:` 开头的JSR包;
+/// Python的依赖由运行器在准备脚本时写入脚本的依赖元数据(`uv run` 的隔离环境),这里不安装
+///
+/// 与预热不同,任何一个依赖安装失败都会返回错误,错误信息包含安装命令的输出
+pub(crate) async fn install_function_dependencies(
+    language_id: &str,
+    dependencies: &[String],
+) -> Result<()> {
+    if dependencies.is_empty() {
+        return Ok(());
+    }
+    match LanguageScript::from_id(language_id) {
+        Some(LanguageScript::Python) => Ok(()),
+        Some(LanguageScript::Js | LanguageScript::Ts) => {
+            for dep in dependencies {
+                let specifier = if dep.starts_with("jsr:") {
+                    dep.clone()
+                } else {
+                    format!("npm:{dep}")
+                };
+                let mut cmd = Command::new("deno");
+                cmd.args(["cache", "--reload", &specifier]);
+                install_dependency(cmd, dep).await?;
+            }
+            Ok(())
+        }
+        _ => {
+            warn!("语言 {language_id} 不支持预先安装声明的依赖,已忽略: {dependencies:?}");
+            Ok(())
+        }
+    }
+}
+
+/// 执行安装单个依赖的命令,失败或超时时返回错误
+async fn install_dependency(mut cmd: Command, dep: &str) -> Result<()> {
+    info!("正在安装函数依赖: {dep}");
+    cmd.kill_on_drop(true);
+    match CommandExecutor::with_timeout(cmd.output(), 600).await {
+        Ok(Ok(output)) => {
+            if !output.status.success() {
+                bail!(
+                    "安装依赖 {dep} 失败，状态码: {}: {}",
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+            Ok(())
+        }
+        Ok(Err(e)) => Err(e).with_context(|| format!("安装依赖 {dep} 失败")),
+        Err(e) => Err(e).with_context(|| format!("安装依赖 {dep} 超时")),
+    }
+}
+
 /// 预热所有脚本执行环境
 pub async fn warm_up_all_envs(
+    custom_python_versions: Option<Vec<String>>,
     custom_python_deps: Option<Vec<String>>,
     custom_npm_packages: Option<Vec<String>>,
     custom_jsr_packages: Option<Vec<String>>,
@@ -311,9 +395,9 @@ pub async fn warm_up_all_envs(
         warn!("检查或创建 uv 虚拟环境失败: {e}");
     }
 
-    // 安装Python 3.13
-    if let Err(e) = install_python_3_13().await {
-        warn!("安装Python 3.13失败: {e}");
+    // 安装Python版本
+    if let Err(e) = install_python_versions(custom_python_versions).await {
+        warn!("安装Python版本失败: {e}");
     }
 
     // 预热Python环境